@@ -1,4 +1,6 @@
 use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
+use crate::domain::config::{ConnectionConfig, CustomCommand, DeviceConfig, FramingConfig, Rs485Config, Rs485Mode, UnixSocketMode};
+use crate::domain::error::TermComError;
 use serde::{Deserialize, Serialize};
 
 /// Command line arguments for TermCom
@@ -38,6 +40,8 @@ pub enum Command {
     Serial(SerialArgs),
     /// TCP communication commands
     Tcp(TcpArgs),
+    /// Unix domain socket communication commands
+    Unix(UnixArgs),
     /// Session management commands
     Session(SessionArgs),
     /// Configuration management commands
@@ -46,14 +50,19 @@ pub enum Command {
     Tui,
     /// Echo server for testing
     EchoServer(EchoServerArgs),
+    /// Background session daemon
+    Daemon(DaemonArgs),
+    /// Pseudo-terminal utilities
+    Pty(PtyArgs),
     /// Display version information
     Version,
 }
 
 /// Output format options
-#[derive(ValueEnum, Debug, Clone, Serialize, Deserialize)]
+#[derive(ValueEnum, Debug, Clone, Default, Serialize, Deserialize)]
 pub enum OutputFormat {
     /// Human-readable text output
+    #[default]
     Text,
     /// JSON output
     Json,
@@ -66,9 +75,11 @@ pub enum OutputFormat {
 /// Serial communication arguments
 #[derive(ClapArgs, Debug)]
 pub struct SerialArgs {
-    /// Serial port path
+    /// Serial port path, or a USB match such as usb:0403:6001[:SERIAL]
+    ///
+    /// Required by every subcommand except list.
     #[arg(short, long)]
-    pub port: String,
+    pub port: Option<String>,
 
     /// Baud rate
     #[arg(short, long, default_value = "9600")]
@@ -90,11 +101,48 @@ pub struct SerialArgs {
     #[arg(long, value_enum, default_value = "none")]
     pub flow_control: FlowControlArg,
 
+    /// Drive an RS-485 transceiver, with the kernel or by toggling RTS
+    #[arg(long, value_enum)]
+    pub rs485: Option<Rs485ModeArg>,
+
+    /// Milliseconds to wait after enabling the RS-485 transmitter
+    #[arg(long, value_name = "MS", default_value = "0", requires = "rs485")]
+    pub rs485_delay_before: u32,
+
+    /// Milliseconds to keep the RS-485 transmitter on after sending
+    #[arg(long, value_name = "MS", default_value = "0", requires = "rs485")]
+    pub rs485_delay_after: u32,
+
+    /// Drive RTS low, rather than high, while sending
+    #[arg(long, requires = "rs485")]
+    pub rs485_rts_low: bool,
+
+    /// Keep the echo of sent data instead of dropping it
+    #[arg(long, requires = "rs485")]
+    pub rs485_keep_echo: bool,
+
+    /// How data is split into messages (see `config add-device --framing`)
+    #[arg(long, default_value = "raw")]
+    pub framing: FramingConfig,
+
     /// Serial subcommand
     #[command(subcommand)]
     pub command: SerialCommand,
 }
 
+impl SerialArgs {
+    /// RS-485 settings given on the command line, if RS-485 is enabled
+    pub fn rs485_config(&self) -> Option<Rs485Config> {
+        self.rs485.map(|mode| Rs485Config {
+            mode: mode.into(),
+            rts_on_send: !self.rs485_rts_low,
+            delay_before_send_ms: self.rs485_delay_before,
+            delay_after_send_ms: self.rs485_delay_after,
+            suppress_echo: !self.rs485_keep_echo,
+        })
+    }
+}
+
 /// TCP communication arguments
 #[derive(ClapArgs, Debug)]
 pub struct TcpArgs {
@@ -103,6 +151,14 @@ pub struct TcpArgs {
     pub command: TcpCommand,
 }
 
+/// Unix domain socket communication arguments
+#[derive(ClapArgs, Debug)]
+pub struct UnixArgs {
+    /// Unix socket subcommand
+    #[command(subcommand)]
+    pub command: UnixCommand,
+}
+
 /// Session management arguments
 #[derive(ClapArgs, Debug)]
 pub struct SessionArgs {
@@ -139,6 +195,26 @@ pub struct EchoServerArgs {
     pub command: EchoServerCommand,
 }
 
+/// Daemon arguments
+#[derive(ClapArgs, Debug)]
+pub struct DaemonArgs {
+    /// Control socket path (defaults to $TERMCOM_SOCKET or the user runtime directory)
+    #[arg(short, long)]
+    pub socket: Option<String>,
+
+    /// Daemon subcommand
+    #[command(subcommand)]
+    pub command: DaemonCommand,
+}
+
+/// Pseudo-terminal arguments
+#[derive(ClapArgs, Debug)]
+pub struct PtyArgs {
+    /// Pseudo-terminal subcommand
+    #[command(subcommand)]
+    pub command: PtyCommand,
+}
+
 /// Serial communication subcommands
 #[derive(Subcommand, Debug)]
 pub enum SerialCommand {
@@ -150,6 +226,9 @@ pub enum SerialCommand {
         /// Session name
         #[arg(short, long)]
         session: Option<String>,
+        /// Reopen the port when the device is unplugged and comes back
+        #[arg(long)]
+        auto_reconnect: bool,
     },
     /// Send data to serial device
     Send {
@@ -162,7 +241,99 @@ pub enum SerialCommand {
         #[arg(short, long, value_enum, default_value = "text")]
         format: DataFormat,
     },
-    /// List available serial ports
+    /// Set the DTR/RTS lines and show the modem status lines
+    ///
+    /// Without --session the port is opened just for this command.
+    Signal {
+        /// Session ID or name
+        #[arg(short, long)]
+        session: Option<String>,
+        /// Data Terminal Ready level
+        #[arg(long, value_enum)]
+        dtr: Option<LineLevel>,
+        /// Request To Send level
+        #[arg(long, value_enum)]
+        rts: Option<LineLevel>,
+    },
+    /// Send a break condition
+    ///
+    /// Without --session the port is opened just for this command.
+    Break {
+        /// Session ID or name
+        #[arg(short, long)]
+        session: Option<String>,
+        /// Break duration in milliseconds
+        #[arg(long, default_value = "250")]
+        ms: u64,
+    },
+    /// Change the line settings of an open session
+    ///
+    /// Settings left out keep their current value; with none given the
+    /// current settings are shown.
+    Set {
+        /// Session ID or name
+        #[arg(short, long)]
+        session: String,
+        /// Baud rate
+        #[arg(long)]
+        baud: Option<u32>,
+        /// Data bits
+        #[arg(long)]
+        data_bits: Option<u8>,
+        /// Stop bits
+        #[arg(long)]
+        stop_bits: Option<u8>,
+        /// Parity (none, even, odd)
+        #[arg(long, value_enum)]
+        parity: Option<ParityArg>,
+        /// Flow control (none, software, hardware)
+        #[arg(long, value_enum)]
+        flow_control: Option<FlowControlArg>,
+    },
+    /// Run a reset sequence and save the boot log
+    ///
+    /// Without --session the port is opened just for this command.
+    Reset {
+        /// Sequence name, such as esp32-bootloader or arduino-reset
+        sequence: String,
+        /// Session ID or name
+        #[arg(short, long)]
+        session: Option<String>,
+        /// Device from the configuration whose reset sequences to use
+        #[arg(long, conflicts_with = "session")]
+        profile: Option<String>,
+        /// Boot log file (named after the device, sequence and time if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Find the baud rate of the device on the port
+    ///
+    /// Each rate is tried in turn and scored by how much of what the device
+    /// sends is printable, or by whether --expect shows up.
+    Detect {
+        /// Comma separated baud rates to try (common rates if omitted)
+        #[arg(long, value_delimiter = ',')]
+        rates: Vec<u32>,
+        /// Data to send after switching to each rate
+        #[arg(long)]
+        probe: Option<String>,
+        /// Probe data format (hex, text, base64)
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: DataFormat,
+        /// Text that identifies the right rate
+        #[arg(long)]
+        expect: Option<String>,
+        /// How long to listen at each rate, in milliseconds
+        #[arg(long, default_value = "500")]
+        listen_ms: u64,
+        /// Open a session at the detected rate
+        #[arg(long)]
+        open: bool,
+        /// Session name for --open
+        #[arg(short, long, requires = "open")]
+        session: Option<String>,
+    },
+    /// List serial ports with their USB details and aliases
     List,
     /// Monitor serial communication
     Monitor {
@@ -171,6 +342,15 @@ pub enum SerialCommand {
         /// Output file for logging
         #[arg(short, long)]
         output: Option<String>,
+        /// Rendering of each chunk
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: MonitorFormat,
+        /// Timestamp style
+        #[arg(short, long, value_enum, default_value = "absolute")]
+        timestamps: TimestampMode,
+        /// Only show data in one direction
+        #[arg(short, long, value_enum, default_value = "both")]
+        direction: Direction,
     },
 }
 
@@ -192,6 +372,9 @@ pub enum TcpCommand {
         /// Connection timeout in seconds
         #[arg(short, long, default_value = "5")]
         timeout: u64,
+        /// How data is split into messages (see `config add-device --framing`)
+        #[arg(long, default_value = "raw")]
+        framing: FramingConfig,
     },
     /// Start TCP server
     Server {
@@ -206,6 +389,9 @@ pub enum TcpCommand {
         /// Session name
         #[arg(short, long)]
         session: Option<String>,
+        /// Maximum number of simultaneous clients (unlimited by default)
+        #[arg(long)]
+        max_clients: Option<usize>,
     },
     /// Send data to TCP connection
     Send {
@@ -217,6 +403,14 @@ pub enum TcpCommand {
         /// Data format (hex, text, base64)
         #[arg(short, long, value_enum, default_value = "text")]
         format: DataFormat,
+        /// Send only to this client of a server session (IP:PORT)
+        #[arg(long)]
+        peer: Option<String>,
+    },
+    /// List the clients connected to a server session
+    Peers {
+        /// Session ID or name
+        session: String,
     },
     /// Monitor TCP communication
     Monitor {
@@ -225,6 +419,76 @@ pub enum TcpCommand {
         /// Output file for logging
         #[arg(short, long)]
         output: Option<String>,
+        /// Rendering of each chunk
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: MonitorFormat,
+        /// Timestamp style
+        #[arg(short, long, value_enum, default_value = "absolute")]
+        timestamps: TimestampMode,
+        /// Only show data in one direction
+        #[arg(short, long, value_enum, default_value = "both")]
+        direction: Direction,
+    },
+}
+
+/// Unix domain socket communication subcommands
+#[derive(Subcommand, Debug)]
+pub enum UnixCommand {
+    /// Connect to a Unix socket
+    Connect {
+        /// Socket path
+        path: String,
+        /// Use a datagram socket instead of a stream
+        #[arg(long)]
+        datagram: bool,
+        /// Device name (optional)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Session name
+        #[arg(short, long)]
+        session: Option<String>,
+    },
+    /// Create a Unix socket and wait for a client
+    Listen {
+        /// Socket path to create
+        path: String,
+        /// Use a datagram socket instead of a stream
+        #[arg(long)]
+        datagram: bool,
+        /// Device name (optional)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Session name
+        #[arg(short, long)]
+        session: Option<String>,
+    },
+    /// Send data to a Unix socket session
+    Send {
+        /// Data to send
+        data: String,
+        /// Session ID
+        #[arg(short, long)]
+        session: Option<String>,
+        /// Data format (hex, text, base64)
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: DataFormat,
+    },
+    /// Monitor Unix socket communication
+    Monitor {
+        /// Session ID to monitor
+        session: Option<String>,
+        /// Output file for logging
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Rendering of each chunk
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: MonitorFormat,
+        /// Timestamp style
+        #[arg(short, long, value_enum, default_value = "absolute")]
+        timestamps: TimestampMode,
+        /// Only show data in one direction
+        #[arg(short, long, value_enum, default_value = "both")]
+        direction: Direction,
     },
 }
 
@@ -250,11 +514,43 @@ pub enum EchoServerCommand {
         #[arg(short, long)]
         output: Option<String>,
         /// Show client connections
-        #[arg(short, long)]
+        #[arg(long)]
         clients: bool,
     },
 }
 
+/// Daemon subcommands
+#[derive(Subcommand, Debug)]
+pub enum DaemonCommand {
+    /// Start the daemon
+    Start {
+        /// Run in the foreground instead of detaching
+        #[arg(short, long)]
+        foreground: bool,
+        /// Log file path for background mode
+        #[arg(short, long)]
+        log_file: Option<String>,
+    },
+    /// Stop the running daemon and all of its sessions
+    Stop,
+    /// Show daemon status
+    Status,
+}
+
+/// Pseudo-terminal subcommands
+#[derive(Subcommand, Debug)]
+pub enum PtyCommand {
+    /// Create two linked pseudo-terminals that behave like a null-modem cable
+    Pair {
+        /// Symlink to create for the first end
+        #[arg(long)]
+        link_a: Option<String>,
+        /// Symlink to create for the second end
+        #[arg(long)]
+        link_b: Option<String>,
+    },
+}
+
 /// Session management subcommands
 #[derive(Subcommand, Debug)]
 pub enum SessionCommand {
@@ -298,8 +594,8 @@ pub enum SessionCommand {
     },
     /// Create new session from config
     Create {
-        /// Configuration file or device name
-        config: String,
+        /// Device name from the configuration, or a device TOML file
+        device: String,
         /// Session name
         #[arg(short, long)]
         name: Option<String>,
@@ -307,6 +603,16 @@ pub enum SessionCommand {
         #[arg(short, long, value_enum, default_value = "interactive")]
         r#type: SessionTypeArg,
     },
+    /// Run a reset sequence on a session and save the boot log
+    Reset {
+        /// Session ID
+        id: String,
+        /// Sequence name, such as esp32-bootloader or arduino-reset
+        sequence: String,
+        /// Boot log file (named after the device, sequence and time if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
     /// Export session data
     Export {
         /// Session ID
@@ -318,6 +624,20 @@ pub enum SessionCommand {
         #[arg(short, long, value_enum, default_value = "json")]
         format: ExportFormat,
     },
+    /// Import a session export and display it
+    Import {
+        /// Export file
+        file: String,
+        /// Export format (detected from the file if omitted)
+        #[arg(short, long, value_enum)]
+        format: Option<ExportFormat>,
+        /// Include message history
+        #[arg(short, long)]
+        messages: bool,
+        /// Include activity history
+        #[arg(short, long)]
+        activities: bool,
+    },
     /// Session statistics
     Stats,
 }
@@ -344,18 +664,128 @@ pub enum ConfigCommand {
     /// List device configurations
     Devices,
     /// Add device configuration
-    AddDevice {
+    AddDevice(Box<AddDeviceArgs>),
+    /// Remove device configuration
+    RemoveDevice {
         /// Device name
         name: String,
-        /// Device description
-        #[arg(short, long)]
-        description: Option<String>,
-        /// Connection type (serial, tcp)
-        #[arg(short, long, value_enum)]
-        connection: ConnectionTypeArg,
+    },
+    /// Set a configuration value
+    ///
+    /// Keys are global settings such as `log_level` or `global.history_limit`,
+    /// or device fields such as `devices.<name>.baud_rate`.
+    Set {
+        /// Setting key
+        key: String,
+        /// New value
+        value: String,
     },
 }
 
+/// Arguments for adding a device profile
+#[derive(ClapArgs, Debug)]
+pub struct AddDeviceArgs {
+    /// Device name
+    pub name: String,
+    /// Device description
+    #[arg(short, long)]
+    pub description: Option<String>,
+    /// Connection type (serial, tcp, tcp-server, udp, pty, process, unix)
+    #[arg(short = 't', long, value_enum)]
+    pub connection: ConnectionTypeArg,
+    /// Serial port path, TCP port number, existing PTY to attach to, or Unix socket path
+    #[arg(short, long)]
+    pub port: Option<String>,
+    /// Baud rate
+    #[arg(short, long, default_value = "9600")]
+    pub baud: u32,
+    /// Data bits
+    #[arg(long, default_value = "8")]
+    pub data_bits: u8,
+    /// Stop bits
+    #[arg(long, default_value = "1")]
+    pub stop_bits: u8,
+    /// Parity
+    #[arg(long, value_enum, default_value = "none")]
+    pub parity: ParityArg,
+    /// Flow control
+    #[arg(long, value_enum, default_value = "none")]
+    pub flow_control: FlowControlArg,
+    /// TCP host, or the address a TCP server listens on (default 0.0.0.0)
+    #[arg(long)]
+    pub host: Option<String>,
+    /// TCP connection timeout in milliseconds
+    #[arg(long, default_value = "3000")]
+    pub timeout: u64,
+    /// Enable TCP keep-alive
+    #[arg(long)]
+    pub keep_alive: bool,
+    /// Maximum number of simultaneous TCP server clients
+    #[arg(long)]
+    pub max_clients: Option<usize>,
+    /// Local UDP address to bind
+    #[arg(long, default_value = "0.0.0.0:0")]
+    pub bind: String,
+    /// UDP destination address for sent datagrams
+    #[arg(long)]
+    pub remote: Option<String>,
+    /// Allow sending UDP broadcasts
+    #[arg(long)]
+    pub broadcast: bool,
+    /// UDP multicast group to join
+    #[arg(long)]
+    pub multicast: Option<String>,
+    /// Symlink to point at a newly created PTY
+    #[arg(long)]
+    pub link: Option<String>,
+    /// Program to run for a process device
+    #[arg(long, value_name = "PROGRAM")]
+    pub exec: Option<String>,
+    /// Argument passed to the program (repeatable)
+    #[arg(long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub process_args: Vec<String>,
+    /// Environment variable for the program as KEY=VALUE (repeatable)
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    pub process_env: Vec<String>,
+    /// Working directory of the program
+    #[arg(long)]
+    pub cwd: Option<String>,
+    /// How a Unix socket device is reached
+    #[arg(long, value_enum, default_value = "stream")]
+    pub unix_mode: UnixModeArg,
+    /// How data is split into messages: raw, line[:cr|lf|crlf], delimiter:HEX,
+    /// fixed:LENGTH, length:WIDTH[:be|le][:OFFSET], slip, cobs or stx-etx
+    #[arg(long, default_value = "raw")]
+    pub framing: FramingConfig,
+    /// Reconnect sessions when the connection drops
+    #[arg(long)]
+    pub auto_reconnect: bool,
+    /// Reconnection attempts before giving up (0 = keep trying)
+    #[arg(long, value_name = "N", default_value = "3")]
+    pub max_reconnect_attempts: u32,
+    /// Delay before the first reconnection attempt, doubled for each further one
+    #[arg(long, value_name = "MS", default_value = "1000")]
+    pub reconnect_delay_ms: u64,
+    /// Session tag (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// Session property as KEY=VALUE (repeatable)
+    #[arg(long = "property", value_name = "KEY=VALUE")]
+    pub properties: Vec<String>,
+    /// Custom command as NAME=TEMPLATE (repeatable)
+    #[arg(long = "command", value_name = "NAME=TEMPLATE")]
+    pub commands: Vec<String>,
+    /// Expected response pattern for a command as NAME=REGEX (repeatable)
+    #[arg(long = "expect", value_name = "NAME=REGEX")]
+    pub expects: Vec<String>,
+    /// Timeout for a command as NAME=MS (repeatable)
+    #[arg(long = "command-timeout", value_name = "NAME=MS")]
+    pub command_timeouts: Vec<String>,
+    /// Replace an existing device with the same name
+    #[arg(long)]
+    pub replace: bool,
+}
+
 /// Parity configuration argument
 #[derive(ValueEnum, Debug, Clone)]
 pub enum ParityArg {
@@ -372,6 +802,44 @@ pub enum FlowControlArg {
     Hardware,
 }
 
+/// RS-485 direction control argument
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rs485ModeArg {
+    /// Kernel driver when it supports RS-485, RTS toggling otherwise
+    Auto,
+    /// Kernel driver only
+    Kernel,
+    /// RTS toggled around each write
+    Software,
+}
+
+impl From<Rs485ModeArg> for Rs485Mode {
+    fn from(mode: Rs485ModeArg) -> Self {
+        match mode {
+            Rs485ModeArg::Auto => Self::Auto,
+            Rs485ModeArg::Kernel => Self::Kernel,
+            Rs485ModeArg::Software => Self::Software,
+        }
+    }
+}
+
+/// Level of a serial control line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineLevel {
+    /// Asserted
+    #[value(alias = "high", alias = "1")]
+    On,
+    /// Deasserted
+    #[value(alias = "low", alias = "0")]
+    Off,
+}
+
+impl From<LineLevel> for bool {
+    fn from(level: LineLevel) -> Self {
+        level == LineLevel::On
+    }
+}
+
 /// Data format argument
 #[derive(ValueEnum, Debug, Clone)]
 pub enum DataFormat {
@@ -380,6 +848,41 @@ pub enum DataFormat {
     Base64,
 }
 
+/// Monitor rendering format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorFormat {
+    /// Printable text with escaped control bytes
+    Text,
+    /// Space separated hex bytes
+    Hex,
+    /// Offset, hex and ASCII columns
+    Hexdump,
+    /// One JSON object per line
+    Json,
+}
+
+/// Monitor timestamp style
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampMode {
+    /// Wall clock time in UTC
+    Absolute,
+    /// Seconds since the monitor started
+    Relative,
+    /// No timestamps
+    None,
+}
+
+/// Monitor direction filter
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent and received data
+    Both,
+    /// Received data only
+    Rx,
+    /// Sent data only
+    Tx,
+}
+
 /// Session type argument
 #[derive(ValueEnum, Debug, Clone)]
 pub enum SessionTypeArg {
@@ -405,6 +908,24 @@ pub enum SessionStatusArg {
 pub enum ConnectionTypeArg {
     Serial,
     Tcp,
+    TcpServer,
+    Udp,
+    Pty,
+    Process,
+    Unix,
+}
+
+/// Unix socket mode argument
+#[derive(ValueEnum, Debug, Clone)]
+pub enum UnixModeArg {
+    /// Connect to a listening stream socket
+    Stream,
+    /// Create a stream socket and accept a client
+    StreamListen,
+    /// Send datagrams to a bound socket
+    Datagram,
+    /// Bind a datagram socket and reply to the last sender
+    DatagramListen,
 }
 
 /// Export format argument
@@ -415,6 +936,130 @@ pub enum ExportFormat {
     Log,
 }
 
+impl From<ExportFormat> for crate::core::session::SessionExportFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Json => Self::Json,
+            ExportFormat::Csv => Self::Csv,
+            ExportFormat::Log => Self::Log,
+        }
+    }
+}
+
+impl AddDeviceArgs {
+    /// Build the device profile described by the arguments
+    pub fn into_device_config(self) -> Result<DeviceConfig, TermComError> {
+        let connection = match self.connection {
+            ConnectionTypeArg::Serial => ConnectionConfig::Serial {
+                port: self.port.ok_or_else(|| missing_flag("--port", "serial"))?,
+                baud_rate: self.baud,
+                data_bits: self.data_bits,
+                stop_bits: self.stop_bits,
+                parity: self.parity.into(),
+                flow_control: self.flow_control.into(),
+                rs485: None,
+            },
+            ConnectionTypeArg::Tcp => ConnectionConfig::Tcp {
+                port: parse_tcp_port(self.port, "TCP")?,
+                host: self.host.ok_or_else(|| missing_flag("--host", "TCP"))?,
+                timeout_ms: self.timeout,
+                keep_alive: self.keep_alive,
+            },
+            ConnectionTypeArg::TcpServer => ConnectionConfig::TcpServer {
+                port: parse_tcp_port(self.port, "TCP server")?,
+                bind: self.host.unwrap_or_else(|| "0.0.0.0".to_string()),
+                max_clients: self.max_clients,
+            },
+            ConnectionTypeArg::Udp => ConnectionConfig::Udp {
+                bind: self.bind,
+                remote: self.remote,
+                broadcast: self.broadcast,
+                multicast: self.multicast,
+            },
+            ConnectionTypeArg::Pty => ConnectionConfig::Pty {
+                path: self.port,
+                link: self.link,
+            },
+            ConnectionTypeArg::Process => {
+                let mut env = std::collections::HashMap::new();
+                for spec in &self.process_env {
+                    let (key, value) = split_key_value(spec, "--env")?;
+                    env.insert(key.to_string(), value.to_string());
+                }
+                ConnectionConfig::Process {
+                    command: self.exec.ok_or_else(|| missing_flag("--exec", "process"))?,
+                    args: self.process_args,
+                    env,
+                    cwd: self.cwd,
+                }
+            }
+            ConnectionTypeArg::Unix => ConnectionConfig::Unix {
+                path: self.port.ok_or_else(|| missing_flag("--port", "Unix socket"))?,
+                mode: self.unix_mode.into(),
+            },
+        };
+
+        let mut commands = Vec::new();
+        for spec in &self.commands {
+            let (name, template) = split_key_value(spec, "--command")?;
+            commands.push(CustomCommand::new(name.to_string(), template.to_string()));
+        }
+        for spec in &self.expects {
+            let (name, pattern) = split_key_value(spec, "--expect")?;
+            find_command(&mut commands, name)?.response_pattern = Some(pattern.to_string());
+        }
+        for spec in &self.command_timeouts {
+            let (name, timeout) = split_key_value(spec, "--command-timeout")?;
+            find_command(&mut commands, name)?.timeout_ms = timeout
+                .parse()
+                .map_err(|_| TermComError::InvalidInput(format!("Invalid command timeout '{}'", timeout)))?;
+        }
+
+        let mut properties = std::collections::HashMap::new();
+        for spec in &self.properties {
+            let (key, value) = split_key_value(spec, "--property")?;
+            properties.insert(key.to_string(), value.to_string());
+        }
+
+        Ok(DeviceConfig {
+            name: self.name,
+            description: self.description.unwrap_or_default(),
+            connection,
+            framing: self.framing,
+            commands,
+            reset_sequences: Vec::new(),
+            auto_reconnect: self.auto_reconnect,
+            max_reconnect_attempts: self.max_reconnect_attempts,
+            reconnect_delay_ms: self.reconnect_delay_ms,
+            tags: self.tags,
+            properties,
+        })
+    }
+}
+
+fn missing_flag(flag: &str, connection: &str) -> TermComError {
+    TermComError::InvalidInput(format!("{} is required for {} devices", flag, connection))
+}
+
+fn parse_tcp_port(port: Option<String>, connection: &str) -> Result<u16, TermComError> {
+    let port = port.ok_or_else(|| missing_flag("--port", connection))?;
+    port.parse()
+        .map_err(|_| TermComError::InvalidInput(format!("Invalid TCP port '{}'", port)))
+}
+
+fn split_key_value<'a>(spec: &'a str, flag: &str) -> Result<(&'a str, &'a str), TermComError> {
+    spec.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| TermComError::InvalidInput(format!("{} expects KEY=VALUE, got '{}'", flag, spec)))
+}
+
+fn find_command<'a>(commands: &'a mut [CustomCommand], name: &str) -> Result<&'a mut CustomCommand, TermComError> {
+    commands
+        .iter_mut()
+        .find(|command| command.name == name)
+        .ok_or_else(|| TermComError::InvalidInput(format!("Unknown command '{}'; define it with --command", name)))
+}
+
 impl From<ParityArg> for crate::domain::config::ParityConfig {
     fn from(parity: ParityArg) -> Self {
         match parity {
@@ -435,6 +1080,29 @@ impl From<FlowControlArg> for crate::domain::config::FlowControlConfig {
     }
 }
 
+impl From<UnixModeArg> for UnixSocketMode {
+    fn from(mode: UnixModeArg) -> Self {
+        match mode {
+            UnixModeArg::Stream => Self::Stream,
+            UnixModeArg::StreamListen => Self::StreamListen,
+            UnixModeArg::Datagram => Self::Datagram,
+            UnixModeArg::DatagramListen => Self::DatagramListen,
+        }
+    }
+}
+
+impl UnixCommand {
+    /// Socket mode for a connect or listen subcommand
+    pub fn socket_mode(listen: bool, datagram: bool) -> UnixSocketMode {
+        match (listen, datagram) {
+            (false, false) => UnixSocketMode::Stream,
+            (true, false) => UnixSocketMode::StreamListen,
+            (false, true) => UnixSocketMode::Datagram,
+            (true, true) => UnixSocketMode::DatagramListen,
+        }
+    }
+}
+
 impl From<SessionTypeArg> for crate::core::session::SessionType {
     fn from(session_type: SessionTypeArg) -> Self {
         match session_type {
@@ -459,12 +1127,6 @@ impl From<SessionStatusArg> for crate::core::session::SessionStatus {
     }
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        Self::Text
-    }
-}
-
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -484,4 +1146,235 @@ impl std::fmt::Display for DataFormat {
             DataFormat::Base64 => write!(f, "base64"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args_are_consistent() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_add_device_args() {
+        let args = Args::try_parse_from([
+            "termcom", "config", "add-device", "board", "-t", "serial", "-p", "/dev/ttyUSB0",
+            "--tag", "lab", "--command", "status=STATUS\\r\\n",
+        ])
+        .unwrap();
+
+        match args.command {
+            Command::Config(ConfigArgs { command: ConfigCommand::AddDevice(add) }) => {
+                assert_eq!(add.name, "board");
+                assert_eq!(add.port.as_deref(), Some("/dev/ttyUSB0"));
+                assert_eq!(add.tags, vec!["lab".to_string()]);
+                assert_eq!(add.commands.len(), 1);
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_serial_signal_args() {
+        let args = Args::try_parse_from([
+            "termcom", "serial", "-p", "/dev/ttyUSB0", "signal", "--dtr", "off", "--rts", "high",
+        ])
+        .unwrap();
+
+        match args.command {
+            Command::Serial(SerialArgs { command: SerialCommand::Signal { session, dtr, rts }, .. }) => {
+                assert!(session.is_none());
+                assert_eq!(dtr, Some(LineLevel::Off));
+                assert_eq!(rts, Some(LineLevel::On));
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        let args = Args::try_parse_from(["termcom", "serial", "-p", "/dev/ttyUSB0", "break"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Command::Serial(SerialArgs { command: SerialCommand::Break { ms: 250, .. }, .. })
+        ));
+
+        let args = Args::try_parse_from([
+            "termcom", "serial", "-p", "/dev/ttyUSB0", "reset", "esp32-bootloader", "--profile", "esp32",
+        ])
+        .unwrap();
+        match args.command {
+            Command::Serial(SerialArgs { command: SerialCommand::Reset { sequence, session, profile, output }, .. }) => {
+                assert_eq!(sequence, "esp32-bootloader");
+                assert!(session.is_none());
+                assert_eq!(profile.as_deref(), Some("esp32"));
+                assert!(output.is_none());
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        assert!(Args::try_parse_from([
+            "termcom", "serial", "-p", "/dev/ttyUSB0", "reset", "arduino-reset", "-s", "board", "--profile", "uno",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_serial_set_args() {
+        let args = Args::try_parse_from([
+            "termcom", "serial", "-p", "/dev/ttyUSB0", "-b", "9600", "set", "-s", "boot", "--baud", "921600", "--parity", "even",
+        ])
+        .unwrap();
+        match args.command {
+            Command::Serial(SerialArgs { baud: 9600, command: SerialCommand::Set { session, baud, data_bits, parity, .. }, .. }) => {
+                assert_eq!(session, "boot");
+                assert_eq!(baud, Some(921600));
+                assert!(data_bits.is_none());
+                assert!(matches!(parity, Some(ParityArg::Even)));
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        assert!(Args::try_parse_from(["termcom", "serial", "-p", "/dev/ttyUSB0", "set", "--baud", "115200"]).is_err());
+    }
+
+    #[test]
+    fn test_serial_detect_args() {
+        let args = Args::try_parse_from([
+            "termcom", "serial", "--port", "/dev/ttyUSB0", "detect", "--rates", "9600,115200", "--probe", "0d", "-f", "hex",
+        ])
+        .unwrap();
+        match args.command {
+            Command::Serial(SerialArgs { command: SerialCommand::Detect { rates, probe, format, listen_ms, open, .. }, .. }) => {
+                assert_eq!(rates, vec![9600, 115200]);
+                assert_eq!(probe.as_deref(), Some("0d"));
+                assert!(matches!(format, DataFormat::Hex));
+                assert_eq!(listen_ms, 500);
+                assert!(!open);
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        // Naming a session only makes sense when one is opened
+        assert!(Args::try_parse_from(["termcom", "serial", "-p", "/dev/ttyUSB0", "detect", "-s", "board"]).is_err());
+    }
+
+    #[test]
+    fn test_serial_list_needs_no_port() {
+        let args = Args::try_parse_from(["termcom", "--output", "json", "serial", "list"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Command::Serial(SerialArgs { port: None, command: SerialCommand::List, .. })
+        ));
+
+        let args = Args::try_parse_from(["termcom", "serial", "-p", "usb:0403:6001:A50285BI", "connect"]).unwrap();
+        match args.command {
+            Command::Serial(SerialArgs { port, .. }) => assert_eq!(port.as_deref(), Some("usb:0403:6001:A50285BI")),
+            other => panic!("Unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_serial_rs485_flags() {
+        let args = Args::try_parse_from([
+            "termcom", "serial", "-p", "/dev/ttyUSB0", "--rs485", "software", "--rs485-delay-after", "2", "--rs485-keep-echo",
+            "connect",
+        ])
+        .unwrap();
+        let Command::Serial(serial) = args.command else { panic!("Expected a serial command") };
+        let rs485 = serial.rs485_config().unwrap();
+        assert_eq!(rs485.mode, Rs485Mode::Software);
+        assert_eq!((rs485.delay_before_send_ms, rs485.delay_after_send_ms), (0, 2));
+        assert!(rs485.rts_on_send);
+        assert!(!rs485.suppress_echo);
+
+        // Tuning flags without RS-485 mode are rejected
+        assert!(Args::try_parse_from(["termcom", "serial", "-p", "/dev/ttyUSB0", "--rs485-rts-low", "connect"]).is_err());
+    }
+
+    fn parse_add_device(extra: &[&str]) -> Result<DeviceConfig, TermComError> {
+        let mut argv = vec!["termcom", "config", "add-device", "board"];
+        argv.extend_from_slice(extra);
+        match Args::try_parse_from(argv).unwrap().command {
+            Command::Config(ConfigArgs { command: ConfigCommand::AddDevice(add) }) => add.into_device_config(),
+            other => panic!("Unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_add_device_into_device_config() {
+        let device = parse_add_device(&[
+            "-t", "tcp", "--host", "10.0.0.2", "-p", "23", "--keep-alive", "--auto-reconnect",
+            "--max-reconnect-attempts", "0",
+            "--property", "site=lab=2", "--command", "status=STATUS", "--expect", "status=OK.*",
+            "--command-timeout", "status=2500",
+        ])
+        .unwrap();
+
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::Tcp { ref host, port: 23, keep_alive: true, .. } if host == "10.0.0.2"
+        ));
+        assert!(device.auto_reconnect);
+        assert_eq!(device.max_reconnect_attempts, 0);
+        assert_eq!(device.reconnect_delay_ms, 1000);
+        assert_eq!(device.properties.get("site"), Some(&"lab=2".to_string()));
+        assert_eq!(device.commands[0].template, "STATUS");
+        assert_eq!(device.commands[0].response_pattern.as_deref(), Some("OK.*"));
+        assert_eq!(device.commands[0].timeout_ms, 2500);
+
+        let device = parse_add_device(&["-t", "udp", "--bind", "0.0.0.0:5000", "--multicast", "239.1.2.3"]).unwrap();
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::Udp { ref bind, remote: None, broadcast: false, multicast: Some(_) } if bind == "0.0.0.0:5000"
+        ));
+
+        let device = parse_add_device(&["-t", "serial", "-p", "/dev/ttyUSB0", "--framing", "length:2:le:1"]).unwrap();
+        assert_eq!(
+            device.framing,
+            FramingConfig::LengthPrefixed { offset: 1, width: 2, endianness: crate::domain::config::Endianness::Little }
+        );
+        assert!(Args::try_parse_from(["termcom", "config", "add-device", "board", "-t", "serial", "--framing", "length:3"]).is_err());
+
+        let device = parse_add_device(&["-t", "tcp-server", "-p", "9000", "--max-clients", "4"]).unwrap();
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::TcpServer { ref bind, port: 9000, max_clients: Some(4) } if bind == "0.0.0.0"
+        ));
+
+        let device = parse_add_device(&["-t", "pty", "--link", "/tmp/ttyV0"]).unwrap();
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::Pty { path: None, link: Some(ref link) } if link == "/tmp/ttyV0"
+        ));
+
+        let device = parse_add_device(&[
+            "-t", "process", "--exec", "python3", "--arg", "-u", "--arg", "sim.py",
+            "--env", "SIM_MODE=loopback", "--cwd", "/opt/sim",
+        ])
+        .unwrap();
+        match device.connection {
+            ConnectionConfig::Process { command, args, env, cwd } => {
+                assert_eq!(command, "python3");
+                assert_eq!(args, vec!["-u", "sim.py"]);
+                assert_eq!(env.get("SIM_MODE"), Some(&"loopback".to_string()));
+                assert_eq!(cwd.as_deref(), Some("/opt/sim"));
+            }
+            other => panic!("Unexpected connection: {:?}", other),
+        }
+
+        let device = parse_add_device(&["-t", "unix", "-p", "/tmp/qemu.sock", "--unix-mode", "stream-listen"]).unwrap();
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::Unix { ref path, mode: UnixSocketMode::StreamListen } if path == "/tmp/qemu.sock"
+        ));
+
+        assert!(parse_add_device(&["-t", "serial"]).is_err());
+        assert!(parse_add_device(&["-t", "tcp", "-p", "23"]).is_err());
+        assert!(parse_add_device(&["-t", "process", "--env", "A=1"]).is_err());
+        assert!(parse_add_device(&["-t", "unix"]).is_err());
+        assert!(parse_add_device(&["-t", "tcp", "--host", "h", "-p", "http"]).is_err());
+        assert!(parse_add_device(&["-t", "serial", "-p", "/dev/null", "--expect", "x=y"]).is_err());
+        assert!(parse_add_device(&["-t", "serial", "-p", "/dev/null", "--property", "novalue"]).is_err());
+    }
+}
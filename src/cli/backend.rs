@@ -0,0 +1,300 @@
+use crate::core::communication::{CommunicationEngine, ControlLines, Message, ModemStatus};
+use crate::core::session::{
+    manager::GlobalStatistics, state::SessionActivity, BootLog, SessionConfig, SessionFilter, SessionManager,
+    SessionState, SessionSummary,
+};
+#[cfg(unix)]
+use crate::daemon::DaemonClient;
+use crate::domain::config::{QueueConfig, SerialSettings, TermComConfig};
+use crate::domain::error::{TermComError, TermComResult};
+use crate::infrastructure::queue::{self, QueueReceiver};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Backend executing session commands for the CLI
+///
+/// When a daemon is running, commands are forwarded to it so sessions
+/// outlive the individual `termcom` invocation. Otherwise an in-process
+/// session manager is used, whose sessions end when the command exits.
+pub enum SessionBackend {
+    /// In-process session manager
+    Local(Arc<SessionManager>),
+    /// Sessions hosted by a running daemon
+    #[cfg(unix)]
+    Daemon(DaemonClient),
+}
+
+impl SessionBackend {
+    /// Use the daemon if one is reachable, otherwise a local session manager
+    pub async fn detect(config: &TermComConfig) -> TermComResult<Self> {
+        #[cfg(unix)]
+        if let Some(client) = DaemonClient::connect(crate::daemon::default_socket_path()).await {
+            return Ok(Self::Daemon(client));
+        }
+
+        Self::local(config).await
+    }
+
+    /// Create a backend with an in-process session manager
+    pub async fn local(config: &TermComConfig) -> TermComResult<Self> {
+        let comm_engine = Arc::new(
+            CommunicationEngine::builder()
+                .max_history_size(config.global.history_limit)
+                .max_sessions_per_transport(config.global.max_sessions)
+                .queue(config.global.queue())
+                .build(),
+        );
+        comm_engine.start().await?;
+
+        Ok(Self::Local(Arc::new(SessionManager::new(
+            comm_engine,
+            config.global.max_sessions,
+        ))))
+    }
+
+    /// Check whether commands are executed by a daemon
+    pub fn is_daemon(&self) -> bool {
+        match self {
+            Self::Local(_) => false,
+            #[cfg(unix)]
+            Self::Daemon(_) => true,
+        }
+    }
+
+    /// Create a session, optionally starting it
+    pub async fn create_session(&self, config: SessionConfig, start: bool) -> TermComResult<String> {
+        match self {
+            Self::Local(manager) => {
+                let session_id = manager.create_session(config).await?;
+                if start {
+                    if let Err(e) = manager.start_session(&session_id).await {
+                        let _ = manager.remove_session(&session_id).await;
+                        return Err(e);
+                    }
+                }
+                Ok(session_id)
+            }
+            #[cfg(unix)]
+            Self::Daemon(client) => client.create_session(config, start).await,
+        }
+    }
+
+    /// Start a session
+    pub async fn start_session(&self, session: &str) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.start_session(&resolve(manager, session).await?).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.start_session(session).await,
+        }
+    }
+
+    /// Stop a session
+    pub async fn stop_session(&self, session: &str) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.stop_session(&resolve(manager, session).await?).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.stop_session(session).await,
+        }
+    }
+
+    /// Remove a session
+    pub async fn remove_session(&self, session: &str) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.remove_session(&resolve(manager, session).await?).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.remove_session(session).await,
+        }
+    }
+
+    /// Send data through a session
+    pub async fn send_data(&self, session: &str, data: Vec<u8>) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.send_data(&resolve(manager, session).await?, data).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.send_data(session, data).await,
+        }
+    }
+
+    /// Send data to one peer of a listening session
+    pub async fn send_to_peer(&self, session: &str, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.send_to_peer(&resolve(manager, session).await?, peer, data).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.send_to_peer(session, peer, data).await,
+        }
+    }
+
+    /// List the peers connected to a listening session
+    pub async fn list_peers(&self, session: &str) -> TermComResult<Vec<String>> {
+        match self {
+            Self::Local(manager) => manager.list_peers(&resolve(manager, session).await?).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.list_peers(session).await,
+        }
+    }
+
+    /// Drive the DTR and RTS lines of a serial session
+    pub async fn set_control_lines(&self, session: &str, lines: ControlLines) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.set_control_lines(&resolve(manager, session).await?, lines).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.set_control_lines(session, lines).await,
+        }
+    }
+
+    /// Read the modem status lines of a serial session
+    pub async fn modem_status(&self, session: &str) -> TermComResult<ModemStatus> {
+        match self {
+            Self::Local(manager) => manager.modem_status(&resolve(manager, session).await?).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.modem_status(session).await,
+        }
+    }
+
+    /// Send a break condition on a serial session
+    pub async fn send_break(&self, session: &str, duration: Duration) -> TermComResult<()> {
+        match self {
+            Self::Local(manager) => manager.send_break(&resolve(manager, session).await?, duration).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.send_break(session, duration).await,
+        }
+    }
+
+    /// Change the line settings of a serial session
+    pub async fn set_serial_settings(&self, session: &str, settings: SerialSettings) -> TermComResult<SerialSettings> {
+        match self {
+            Self::Local(manager) => manager.set_serial_settings(&resolve(manager, session).await?, settings).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.set_serial_settings(session, settings).await,
+        }
+    }
+
+    /// Run a reset sequence on a session and capture its boot log
+    pub async fn run_reset_sequence(&self, session: &str, sequence: &str) -> TermComResult<BootLog> {
+        match self {
+            Self::Local(manager) => manager.run_reset_sequence(&resolve(manager, session).await?, sequence).await,
+            #[cfg(unix)]
+            Self::Daemon(client) => client.run_reset_sequence(session, sequence).await,
+        }
+    }
+
+    /// List sessions matching a filter
+    pub async fn list_sessions(&self, filter: &SessionFilter) -> TermComResult<Vec<SessionSummary>> {
+        match self {
+            Self::Local(manager) => Ok(manager.get_sessions_summary_filtered(filter).await),
+            #[cfg(unix)]
+            Self::Daemon(client) => Ok(client
+                .list_sessions()
+                .await?
+                .into_iter()
+                .filter(|summary| filter.matches(summary))
+                .collect()),
+        }
+    }
+
+    /// Get session state
+    pub async fn session_state(&self, session: &str) -> TermComResult<SessionState> {
+        match self {
+            Self::Local(manager) => {
+                let session_id = resolve(manager, session).await?;
+                manager
+                    .get_session_state(&session_id)
+                    .await
+                    .ok_or_else(|| not_found(session))
+            }
+            #[cfg(unix)]
+            Self::Daemon(client) => client.session_state(session).await,
+        }
+    }
+
+    /// Get session message history
+    pub async fn message_history(&self, session: &str) -> TermComResult<Vec<Message>> {
+        match self {
+            Self::Local(manager) => {
+                let session_id = resolve(manager, session).await?;
+                manager
+                    .get_message_history(&session_id)
+                    .await
+                    .ok_or_else(|| not_found(session))
+            }
+            #[cfg(unix)]
+            Self::Daemon(client) => client.message_history(session).await,
+        }
+    }
+
+    /// Get session activity history
+    pub async fn activity_history(&self, session: &str) -> TermComResult<Vec<SessionActivity>> {
+        match self {
+            Self::Local(manager) => {
+                let session_id = resolve(manager, session).await?;
+                manager
+                    .get_activity_history(&session_id)
+                    .await
+                    .ok_or_else(|| not_found(session))
+            }
+            #[cfg(unix)]
+            Self::Daemon(client) => client.activity_history(session).await,
+        }
+    }
+
+    /// Subscribe to live messages of a session
+    ///
+    /// Messages are buffered according to `queue`; those the session skipped
+    /// because the subscriber fell behind are counted as dropped as well.
+    pub async fn subscribe(&self, session: &str, queue: QueueConfig) -> TermComResult<QueueReceiver<Message>> {
+        match self {
+            Self::Local(manager) => {
+                let mut subscription = manager.subscribe(&resolve(manager, session).await?).await?;
+                let (sender, receiver) = queue::bounded(queue);
+
+                tokio::spawn(async move {
+                    loop {
+                        match subscription.recv().await {
+                            Ok(message) => {
+                                if sender.send(message).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!("Subscription fell behind, skipped {} messages", skipped);
+                                sender.add_dropped(skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+
+                Ok(receiver)
+            }
+            #[cfg(unix)]
+            Self::Daemon(client) => client.subscribe(session, queue).await,
+        }
+    }
+
+    /// Get statistics across all sessions
+    pub async fn statistics(&self) -> TermComResult<GlobalStatistics> {
+        match self {
+            Self::Local(manager) => Ok(manager.get_global_statistics().await),
+            #[cfg(unix)]
+            Self::Daemon(client) => client.statistics().await,
+        }
+    }
+}
+
+async fn resolve(manager: &SessionManager, session: &str) -> TermComResult<String> {
+    manager
+        .resolve_session_id(session)
+        .await
+        .ok_or_else(|| not_found(session))
+}
+
+fn not_found(session: &str) -> TermComError {
+    TermComError::Session {
+        message: format!(
+            "Session '{}' not found (no daemon is running; start one with `termcom daemon start`)",
+            session
+        ),
+    }
+}
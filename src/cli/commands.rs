@@ -1,16 +1,22 @@
 use crate::cli::args::{
-    Args, Command, ConfigCommand, DataFormat, EchoServerCommand, SerialCommand, SessionCommand, TcpCommand,
+    Args, Command, ConfigCommand, DataFormat, Direction, EchoServerCommand, MonitorFormat, SerialCommand,
+    SessionCommand, TcpCommand, TimestampMode, UnixCommand,
 };
+use crate::cli::backend::SessionBackend;
+use crate::cli::monitor::MonitorRenderer;
 use crate::cli::output::{ConsoleWriter, OutputWriter};
-use crate::core::communication::CommunicationEngine;
-use crate::core::session::{SessionManager, SessionFilter, SessionType};
+use crate::core::communication::{ControlLines, Message};
+use crate::core::session::{
+    state::SessionActivity, BootLog, SessionConfig, SessionExport, SessionExportFormat, SessionFilter, SessionType,
+};
+use crate::core::timestamp;
 use crate::domain::config::{
-    DeviceConfig, TermComConfig, ConnectionConfig,
+    DeviceConfig, TermComConfig, ConnectionConfig, QueueConfig, SerialSettings,
 };
 use crate::domain::error::TermComError;
 use crate::infrastructure::config::ConfigManager;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use crate::infrastructure::serial::{detect::COMMON_BAUD_RATES, detect_baud_rate, list_ports, BaudDetectOptions};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Execute CLI command
@@ -30,22 +36,25 @@ pub async fn execute_command(args: Args) -> Result<(), TermComError> {
         setup_logging(&config.global, args.verbose)?;
     }
     
-    // Create communication engine and session manager
-    let comm_engine = Arc::new(CommunicationEngine::new(1000, 10));
-    let session_manager = Arc::new(RwLock::new(SessionManager::new(comm_engine.clone(), config.global.max_sessions)));
-    
     match args.command {
         Command::Serial(serial_args) => {
-            execute_serial_command(serial_args, &writer, &config, &comm_engine, &session_manager).await
+            let backend = SessionBackend::detect(&config).await?;
+            execute_serial_command(serial_args, &writer, &config, &backend).await
         }
         Command::Tcp(tcp_args) => {
-            execute_tcp_command(tcp_args, &writer, &config, &comm_engine, &session_manager).await
+            let backend = SessionBackend::detect(&config).await?;
+            execute_tcp_command(tcp_args, &writer, &config, &backend).await
+        }
+        Command::Unix(unix_args) => {
+            let backend = SessionBackend::detect(&config).await?;
+            execute_unix_command(unix_args, &writer, &config, &backend).await
         }
         Command::Session(session_args) => {
-            execute_session_command(session_args, &writer, &session_manager).await
+            let backend = SessionBackend::detect(&config).await?;
+            execute_session_command(session_args, &writer, &config, &config_manager, &backend).await
         }
         Command::Config(config_args) => {
-            execute_config_command(config_args, &writer, &config, &config_manager).await
+            execute_config_command(config_args, &writer, &config, &config_manager, args.config.as_deref()).await
         }
         Command::Tui => {
             writer.write_message("TUI mode not implemented yet")?;
@@ -54,6 +63,12 @@ pub async fn execute_command(args: Args) -> Result<(), TermComError> {
         Command::EchoServer(echo_args) => {
             execute_echo_server_command(echo_args, &writer).await
         }
+        Command::Daemon(daemon_args) => {
+            execute_daemon_command(daemon_args, &writer, &config, args.config.as_deref(), args.verbose).await
+        }
+        Command::Pty(pty_args) => {
+            execute_pty_command(pty_args, &writer).await
+        }
         Command::Version => {
             writer.write_message(&format!("termcom {}", env!("CARGO_PKG_VERSION")))?;
             Ok(())
@@ -64,83 +79,179 @@ pub async fn execute_command(args: Args) -> Result<(), TermComError> {
 async fn execute_serial_command(
     args: crate::cli::args::SerialArgs,
     writer: &ConsoleWriter,
-    _config: &TermComConfig,
-    _comm_engine: &Arc<CommunicationEngine>,
-    session_manager: &Arc<RwLock<SessionManager>>,
+    config: &TermComConfig,
+    backend: &SessionBackend,
 ) -> Result<(), TermComError> {
-    match args.command {
-        SerialCommand::Connect { name, session } => {
-            let session_id = session.unwrap_or_else(|| Uuid::new_v4().to_string());
-            let device_name = name.unwrap_or_else(|| format!("serial-{}", args.port));
-            
-            let device_config = DeviceConfig {
-                name: device_name.clone(),
-                description: format!("Serial device on {}", args.port),
-                connection: ConnectionConfig::Serial {
-                    port: args.port,
+    let port = || {
+        args.port.clone().ok_or_else(|| {
+            TermComError::InvalidInput("--port is required for this serial command".to_string())
+        })
+    };
+    let rs485 = args.rs485_config();
+    let device_config = |name: Option<String>| -> Result<DeviceConfig, TermComError> {
+        let port = port()?;
+        Ok(DeviceConfig {
+            description: format!("Serial device on {}", port),
+            framing: args.framing.clone(),
+            ..DeviceConfig::new(
+                name.unwrap_or_else(|| format!("serial-{}", port)),
+                ConnectionConfig::Serial {
+                    port: port.clone(),
                     baud_rate: args.baud,
                     data_bits: args.data_bits,
                     stop_bits: args.stop_bits,
-                    parity: args.parity.into(),
-                    flow_control: args.flow_control.into(),
+                    parity: args.parity.clone().into(),
+                    flow_control: args.flow_control.clone().into(),
+                    rs485,
                 },
-                commands: Vec::new(),
-            };
-            
-            let session_config = crate::core::session::SessionConfig {
-                name: session_id.clone(),
-                session_type: SessionType::Interactive,
-                device_config,
-                auto_reconnect: false,
-                max_reconnect_attempts: 3,
-                reconnect_delay_ms: 1000,
-                timeout_ms: 0,
-                max_history_size: 1000,
-                log_activities: true,
-                tags: Vec::new(),
-                properties: std::collections::HashMap::new(),
-            };
+            )
+        })
+    };
+    
+    match args.command {
+        SerialCommand::Connect { name, session, auto_reconnect } => {
+            let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+            let mut device_config = device_config(name)?;
+            device_config.auto_reconnect = auto_reconnect;
+            let device_name = device_config.name.clone();
             
-            let manager = session_manager.write().await;
-            manager.create_session(session_config).await?;
+            let session_config = create_interactive_session_config(&session_name, device_config);
+            let session_id = backend.create_session(session_config, true).await?;
             
-            writer.write_message(&format!("Serial session '{}' created for device '{}'", session_id, device_name))?;
+            writer.write_message(&format!("Serial session '{}' ({}) connected to device '{}'", session_name, session_id, device_name))?;
+            write_backend_hint(writer, backend)?;
             Ok(())
         }
         SerialCommand::Send { data, session, format } => {
             let data_bytes = parse_data(&data, format)?;
             
             if let Some(session_id) = session {
-                let manager = session_manager.read().await;
-                if manager.has_session(&session_id).await {
-                    writer.write_message(&format!("Sent {} bytes to session '{}'", data_bytes.len(), session_id))?;
-                } else {
-                    writer.write_error(&format!("Session '{}' not found", session_id))?;
-                }
+                let size = data_bytes.len();
+                backend.send_data(&session_id, data_bytes).await?;
+                writer.write_message(&format!("Sent {} bytes to session '{}'", size, session_id))?;
             } else {
                 writer.write_error("Session ID required for send command")?;
             }
             Ok(())
         }
-        SerialCommand::List => {
-            let ports = serialport::available_ports()
-                .map_err(|e| TermComError::Communication { message: format!("Failed to list serial ports: {}", e) })?;
+        SerialCommand::Signal { session, dtr, rts } => {
+            let lines = ControlLines {
+                dtr: dtr.map(bool::from),
+                rts: rts.map(bool::from),
+            };
+            let (session_id, temporary) = open_serial_session(backend, session, device_config(None)?).await?;
+            
+            let result = async {
+                if !lines.is_empty() {
+                    backend.set_control_lines(&session_id, lines).await?;
+                    writer.write_message(&format!("Set {} on session '{}'", lines, session_id))?;
+                }
+                
+                match backend.modem_status(&session_id).await {
+                    Ok(status) => writer.write_message(&format!("Modem status: {}", status))?,
+                    // The lines were set; some adapters just cannot report status
+                    Err(e) if !lines.is_empty() => writer.write_error(&format!("Modem status unavailable: {}", e))?,
+                    Err(e) => return Err(e),
+                }
+                Ok(())
+            }
+            .await;
+            
+            close_serial_session(backend, &session_id, temporary).await?;
+            result
+        }
+        SerialCommand::Break { session, ms } => {
+            let (session_id, temporary) = open_serial_session(backend, session, device_config(None)?).await?;
+            
+            let result = backend.send_break(&session_id, Duration::from_millis(ms)).await;
+            if result.is_ok() {
+                writer.write_message(&format!("Sent {} ms break on session '{}'", ms, session_id))?;
+            }
+            
+            close_serial_session(backend, &session_id, temporary).await?;
+            result
+        }
+        SerialCommand::Set { session, baud, data_bits, stop_bits, parity, flow_control } => {
+            let settings = SerialSettings {
+                baud_rate: baud,
+                data_bits,
+                stop_bits,
+                parity: parity.map(Into::into),
+                flow_control: flow_control.map(Into::into),
+            };
+            let current = backend.set_serial_settings(&session, settings).await?;
             
-            writer.write_message("Available serial ports:")?;
-            for port in ports {
-                writer.write_message(&format!("  {}", port.port_name))?;
+            if settings.is_empty() {
+                writer.write_message(&format!("Session '{}' runs at {}", session, current))?;
+            } else {
+                writer.write_message(&format!("Session '{}' now runs at {}", session, current))?;
             }
             Ok(())
         }
-        SerialCommand::Monitor { session, output: _ } => {
+        SerialCommand::Reset { sequence, session, profile, output } => {
+            let mut device = device_config(profile.clone())?;
+            if let Some(profile) = profile {
+                let profile_device = config.find_device(&profile).ok_or_else(|| TermComError::Config {
+                    message: format!("Device '{}' not found in configuration", profile),
+                })?;
+                device.reset_sequences = profile_device.reset_sequences.clone();
+            }
+            let (session_id, temporary) = open_serial_session(backend, session, device).await?;
+            
+            let result = backend.run_reset_sequence(&session_id, &sequence).await;
+            
+            close_serial_session(backend, &session_id, temporary).await?;
+            write_boot_log(writer, &result?, output)
+        }
+        SerialCommand::Detect { rates, probe, format, expect, listen_ms, open, session } => {
+            let options = BaudDetectOptions {
+                rates: if rates.is_empty() { COMMON_BAUD_RATES.to_vec() } else { rates },
+                probe: probe.map(|probe| parse_data(&probe, format)).transpose()?,
+                expect,
+                listen: Duration::from_millis(listen_ms),
+            };
+            let mut device = device_config(None)?;
+            let port = port()?;
+            
+            writer.write_message(&format!("Trying {} baud rates on {}...", options.rates.len(), port))?;
+            let detection = detect_baud_rate(&device.connection, &options).await?;
+            for candidate in &detection.candidates {
+                writer.write_message(&format!(
+                    "  {:>7} baud: {} bytes, {:.0}% printable{}",
+                    candidate.baud_rate,
+                    candidate.bytes,
+                    candidate.printable_ratio * 100.0,
+                    if candidate.matched { ", found expected text" } else { "" }
+                ))?;
+            }
+            
+            let Some(baud_rate) = detection.baud_rate() else {
+                return Err(TermComError::Communication {
+                    message: format!("No baud rate matched the device on {}", port),
+                });
+            };
+            writer.write_message(&format!("Detected {} baud", baud_rate))?;
+            
+            if open {
+                SerialSettings::baud_rate(baud_rate).apply(&mut device.connection)?;
+                let device_name = device.name.clone();
+                let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+                let session_config = create_interactive_session_config(&session_name, device);
+                let session_id = backend.create_session(session_config, true).await?;
+                
+                writer.write_message(&format!("Serial session '{}' ({}) connected to device '{}' at {} baud", session_name, session_id, device_name, baud_rate))?;
+                write_backend_hint(writer, backend)?;
+            }
+            Ok(())
+        }
+        SerialCommand::List => {
+            writer.write_ports(&list_ports()?)?;
+            Ok(())
+        }
+        SerialCommand::Monitor { session, output, format, timestamps, direction } => {
             if let Some(session_id) = session {
-                let manager = session_manager.read().await;
-                if manager.has_session(&session_id).await {
-                    writer.write_message(&format!("Monitoring session '{}' (Press Ctrl+C to stop)", session_id))?;
-                    // TODO: Implement actual monitoring
-                } else {
-                    writer.write_error(&format!("Session '{}' not found", session_id))?;
-                }
+                let renderer = MonitorRenderer::new(format, timestamps, direction);
+                monitor_session(backend, writer, &session_id, output.as_deref(), renderer, config.global.queue()).await?;
             } else {
                 writer.write_error("Session ID required for monitor command")?;
             }
@@ -152,107 +263,158 @@ async fn execute_serial_command(
 async fn execute_tcp_command(
     args: crate::cli::args::TcpArgs,
     writer: &ConsoleWriter,
-    _config: &TermComConfig,
-    _comm_engine: &Arc<CommunicationEngine>,
-    session_manager: &Arc<RwLock<SessionManager>>,
+    config: &TermComConfig,
+    backend: &SessionBackend,
 ) -> Result<(), TermComError> {
     match args.command {
-        TcpCommand::Connect { host, port, name, session, timeout } => {
-            let session_id = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+        TcpCommand::Connect { host, port, name, session, timeout, framing } => {
+            let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
             let device_name = name.unwrap_or_else(|| format!("tcp-{}:{}", host, port));
             
             let device_config = DeviceConfig {
-                name: device_name.clone(),
                 description: format!("TCP client connection to {}:{}", host, port),
-                connection: ConnectionConfig::Tcp {
-                    host: host.clone(),
-                    port,
-                    timeout_ms: timeout * 1000,
-                    keep_alive: true,
-                },
-                commands: Vec::new(),
-            };
-            
-            let session_config = crate::core::session::SessionConfig {
-                name: session_id.clone(),
-                session_type: SessionType::Interactive,
-                device_config,
-                auto_reconnect: false,
-                max_reconnect_attempts: 3,
-                reconnect_delay_ms: 1000,
-                timeout_ms: 0,
-                max_history_size: 1000,
-                log_activities: true,
-                tags: Vec::new(),
-                properties: std::collections::HashMap::new(),
+                framing,
+                ..DeviceConfig::new(
+                    device_name.clone(),
+                    ConnectionConfig::Tcp {
+                        host: host.clone(),
+                        port,
+                        timeout_ms: timeout * 1000,
+                        keep_alive: true,
+                    },
+                )
             };
             
-            let manager = session_manager.write().await;
-            manager.create_session(session_config).await?;
+            let session_config = create_interactive_session_config(&session_name, device_config);
+            let session_id = backend.create_session(session_config, true).await?;
             
-            writer.write_message(&format!("TCP session '{}' created for {}:{}", session_id, host, port))?;
+            writer.write_message(&format!("TCP session '{}' ({}) connected to {}:{}", session_name, session_id, host, port))?;
+            write_backend_hint(writer, backend)?;
             Ok(())
         }
-        TcpCommand::Server { bind, port, name, session } => {
-            let session_id = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+        TcpCommand::Server { bind, port, name, session, max_clients } => {
+            let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
             let device_name = name.unwrap_or_else(|| format!("tcp-server-{}:{}", bind, port));
             
             let device_config = DeviceConfig {
-                name: device_name.clone(),
                 description: format!("TCP server listening on {}:{}", bind, port),
-                connection: ConnectionConfig::Tcp {
-                    host: bind.clone(),
-                    port,
-                    timeout_ms: 5000,
-                    keep_alive: true,
-                },
-                commands: Vec::new(),
+                ..DeviceConfig::new(
+                    device_name.clone(),
+                    ConnectionConfig::TcpServer {
+                        bind: bind.clone(),
+                        port,
+                        max_clients,
+                    },
+                )
+            };
+            
+            let session_config = create_interactive_session_config(&session_name, device_config);
+            let session_id = backend.create_session(session_config, true).await?;
+            
+            writer.write_message(&format!("TCP server session '{}' ({}) listening on {}:{}", session_name, session_id, bind, port))?;
+            write_backend_hint(writer, backend)?;
+            Ok(())
+        }
+        TcpCommand::Send { data, session, format, peer } => {
+            let data_bytes = parse_data(&data, format)?;
+            
+            if let Some(session_id) = session {
+                let size = data_bytes.len();
+                match peer {
+                    Some(peer) => {
+                        backend.send_to_peer(&session_id, &peer, data_bytes).await?;
+                        writer.write_message(&format!("Sent {} bytes to {} on session '{}'", size, peer, session_id))?;
+                    }
+                    None => {
+                        backend.send_data(&session_id, data_bytes).await?;
+                        writer.write_message(&format!("Sent {} bytes to session '{}'", size, session_id))?;
+                    }
+                }
+            } else {
+                writer.write_error("Session ID required for send command")?;
+            }
+            Ok(())
+        }
+        TcpCommand::Peers { session } => {
+            let peers = backend.list_peers(&session).await?;
+            if peers.is_empty() {
+                writer.write_message(&format!("No clients connected to session '{}'", session))?;
+            } else {
+                for peer in peers {
+                    writer.write_message(&peer)?;
+                }
+            }
+            Ok(())
+        }
+        TcpCommand::Monitor { session, output, format, timestamps, direction } => {
+            if let Some(session_id) = session {
+                let renderer = MonitorRenderer::new(format, timestamps, direction);
+                monitor_session(backend, writer, &session_id, output.as_deref(), renderer, config.global.queue()).await?;
+            } else {
+                writer.write_error("Session ID required for monitor command")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn execute_unix_command(
+    args: crate::cli::args::UnixArgs,
+    writer: &ConsoleWriter,
+    config: &TermComConfig,
+    backend: &SessionBackend,
+) -> Result<(), TermComError> {
+    match args.command {
+        UnixCommand::Connect { path, datagram, name, session } => {
+            let mode = UnixCommand::socket_mode(false, datagram);
+            let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+            let device_name = name.unwrap_or_else(|| format!("unix-{}", path));
+            
+            let device_config = DeviceConfig {
+                description: format!("Unix {} socket client for {}", mode, path),
+                ..DeviceConfig::new(device_name.clone(), ConnectionConfig::Unix { path: path.clone(), mode })
             };
             
-            let session_config = crate::core::session::SessionConfig {
-                name: session_id.clone(),
-                session_type: SessionType::Interactive,
-                device_config,
-                auto_reconnect: false,
-                max_reconnect_attempts: 3,
-                reconnect_delay_ms: 1000,
-                timeout_ms: 0,
-                max_history_size: 1000,
-                log_activities: true,
-                tags: Vec::new(),
-                properties: std::collections::HashMap::new(),
+            let session_config = create_interactive_session_config(&session_name, device_config);
+            let session_id = backend.create_session(session_config, true).await?;
+            
+            writer.write_message(&format!("Unix socket session '{}' ({}) connected to {}", session_name, session_id, path))?;
+            write_backend_hint(writer, backend)?;
+            Ok(())
+        }
+        UnixCommand::Listen { path, datagram, name, session } => {
+            let mode = UnixCommand::socket_mode(true, datagram);
+            let session_name = session.unwrap_or_else(|| Uuid::new_v4().to_string());
+            let device_name = name.unwrap_or_else(|| format!("unix-listen-{}", path));
+            
+            let device_config = DeviceConfig {
+                description: format!("Unix {} socket listening on {}", mode, path),
+                ..DeviceConfig::new(device_name.clone(), ConnectionConfig::Unix { path: path.clone(), mode })
             };
             
-            let manager = session_manager.write().await;
-            manager.create_session(session_config).await?;
+            let session_config = create_interactive_session_config(&session_name, device_config);
+            let session_id = backend.create_session(session_config, true).await?;
             
-            writer.write_message(&format!("TCP server session '{}' created on {}:{}", session_id, bind, port))?;
+            writer.write_message(&format!("Unix socket session '{}' ({}) listening on {}", session_name, session_id, path))?;
+            write_backend_hint(writer, backend)?;
             Ok(())
         }
-        TcpCommand::Send { data, session, format } => {
+        UnixCommand::Send { data, session, format } => {
             let data_bytes = parse_data(&data, format)?;
             
             if let Some(session_id) = session {
-                let manager = session_manager.read().await;
-                if manager.has_session(&session_id).await {
-                    writer.write_message(&format!("Sent {} bytes to session '{}'", data_bytes.len(), session_id))?;
-                } else {
-                    writer.write_error(&format!("Session '{}' not found", session_id))?;
-                }
+                let size = data_bytes.len();
+                backend.send_data(&session_id, data_bytes).await?;
+                writer.write_message(&format!("Sent {} bytes to session '{}'", size, session_id))?;
             } else {
                 writer.write_error("Session ID required for send command")?;
             }
             Ok(())
         }
-        TcpCommand::Monitor { session, output: _ } => {
+        UnixCommand::Monitor { session, output, format, timestamps, direction } => {
             if let Some(session_id) = session {
-                let manager = session_manager.read().await;
-                if manager.has_session(&session_id).await {
-                    writer.write_message(&format!("Monitoring session '{}' (Press Ctrl+C to stop)", session_id))?;
-                    // TODO: Implement actual monitoring
-                } else {
-                    writer.write_error(&format!("Session '{}' not found", session_id))?;
-                }
+                let renderer = MonitorRenderer::new(format, timestamps, direction);
+                monitor_session(backend, writer, &session_id, output.as_deref(), renderer, config.global.queue()).await?;
             } else {
                 writer.write_error("Session ID required for monitor command")?;
             }
@@ -261,15 +423,72 @@ async fn execute_tcp_command(
     }
 }
 
+/// Stream session messages to the console and optional log file until Ctrl+C
+async fn monitor_session(
+    backend: &SessionBackend,
+    writer: &ConsoleWriter,
+    session: &str,
+    output: Option<&str>,
+    renderer: MonitorRenderer,
+    queue: QueueConfig,
+) -> Result<(), TermComError> {
+    use std::io::Write;
+    use tokio::signal;
+    
+    let mut receiver = backend.subscribe(session, queue).await?;
+    let mut log_file = match output {
+        Some(path) => Some(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| TermComError::Output(format!("Failed to open {}: {}", path, e)))?,
+        ),
+        None => None,
+    };
+    
+    writer.write_message(&format!("Monitoring session '{}' (Press Ctrl+C to stop)", session))?;
+    
+    loop {
+        tokio::select! {
+            message = receiver.recv() => {
+                let Some(message) = message else {
+                    writer.write_message("Session closed, stopping monitor")?;
+                    break;
+                };
+                
+                if let Some(rendered) = renderer.render(&message) {
+                    println!("{}", rendered);
+                    if let Some(file) = log_file.as_mut() {
+                        writeln!(file, "{}", rendered)
+                            .map_err(|e| TermComError::Output(format!("Failed to write monitor log: {}", e)))?;
+                    }
+                }
+            }
+            
+            _ = signal::ctrl_c() => {
+                writer.write_message("\nReceived Ctrl+C, stopping monitor...")?;
+                break;
+            }
+        }
+    }
+    
+    if receiver.dropped() > 0 {
+        writer.write_message(&format!("{} messages were dropped because the monitor fell behind", receiver.dropped()))?;
+    }
+    
+    Ok(())
+}
+
 async fn execute_session_command(
     args: crate::cli::args::SessionArgs,
     writer: &ConsoleWriter,
-    session_manager: &Arc<RwLock<SessionManager>>,
+    config: &TermComConfig,
+    config_manager: &ConfigManager,
+    backend: &SessionBackend,
 ) -> Result<(), TermComError> {
     match args.command {
         SessionCommand::List { r#type, status, device } => {
-            let manager = session_manager.read().await;
-            
             let mut filter = SessionFilter::new();
             if let Some(session_type) = r#type {
                 filter = filter.with_session_type(session_type.into());
@@ -281,48 +500,99 @@ async fn execute_session_command(
                 filter = filter.with_device_name(&device_name);
             }
             
-            let sessions = manager.get_sessions_summary_filtered(&filter).await;
+            let sessions = backend.list_sessions(&filter).await?;
             writer.write_sessions(&sessions)?;
             Ok(())
         }
-        SessionCommand::Show { id, messages: _, activities: _ } => {
-            let manager = session_manager.read().await;
-            if let Some(state) = manager.get_session_state(&id).await {
-                writer.write_session_detail(&state)?;
-            } else {
-                writer.write_error(&format!("Session '{}' not found", id))?;
+        SessionCommand::Show { id, messages, activities } => {
+            match backend.session_state(&id).await {
+                Ok(state) => writer.write_session_detail(&state)?,
+                Err(e) => {
+                    writer.write_error(&e.to_string())?;
+                    return Ok(());
+                }
             }
-            Ok(())
+            
+            let messages = if messages { backend.message_history(&id).await? } else { Vec::new() };
+            let activities = if activities { backend.activity_history(&id).await? } else { Vec::new() };
+            write_history(writer, &messages, &activities)
         }
         SessionCommand::Start { id } => {
-            let manager = session_manager.write().await;
-            manager.start_session(&id).await?;
+            backend.start_session(&id).await?;
             writer.write_message(&format!("Session '{}' started", id))?;
             Ok(())
         }
         SessionCommand::Stop { id } => {
-            let manager = session_manager.write().await;
-            manager.stop_session(&id).await?;
+            backend.stop_session(&id).await?;
             writer.write_message(&format!("Session '{}' stopped", id))?;
             Ok(())
         }
         SessionCommand::Remove { id } => {
-            let manager = session_manager.write().await;
-            manager.remove_session(&id).await?;
+            backend.remove_session(&id).await?;
             writer.write_message(&format!("Session '{}' removed", id))?;
             Ok(())
         }
-        SessionCommand::Create { config: _, name: _, r#type: _ } => {
-            writer.write_message("Session creation from config not implemented yet")?;
+        SessionCommand::Create { device, name, r#type } => {
+            let device_config = resolve_device_profile(&device, config, config_manager)?;
+            let device_name = device_config.name.clone();
+            let session_name = name.unwrap_or_else(|| device_name.clone());
+            let session_config = SessionConfig::from_device(session_name.clone(), r#type.into(), device_config);
+            
+            let session_id = backend.create_session(session_config, true).await?;
+            writer.write_message(&format!(
+                "Session '{}' ({}) created from device '{}' and started",
+                session_name, session_id, device_name
+            ))?;
+            write_backend_hint(writer, backend)?;
             Ok(())
         }
-        SessionCommand::Export { id: _, output: _, format: _ } => {
-            writer.write_message("Session export not implemented yet")?;
+        SessionCommand::Reset { id, sequence, output } => {
+            let log = backend.run_reset_sequence(&id, &sequence).await?;
+            write_boot_log(writer, &log, output)
+        }
+        SessionCommand::Export { id, output, format } => {
+            let state = backend.session_state(&id).await?;
+            let messages = backend.message_history(&id).await?;
+            let activities = backend.activity_history(&id).await?;
+            
+            let export = SessionExport::new(state, messages, activities);
+            let content = export.encode(format.into())?;
+            std::fs::write(&output, content)
+                .map_err(|e| TermComError::Output(format!("Failed to write {}: {}", output, e)))?;
+            
+            writer.write_message(&format!(
+                "Exported session '{}' ({} messages, {} activities) to {}",
+                id,
+                export.messages.len(),
+                export.activities.len(),
+                output
+            ))?;
             Ok(())
         }
+        SessionCommand::Import { file, format, messages, activities } => {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| TermComError::InvalidInput(format!("Failed to read {}: {}", file, e)))?;
+            let format = format
+                .map(Into::into)
+                .or_else(|| SessionExportFormat::from_path(std::path::Path::new(&file)));
+            let export = SessionExport::decode(&content, format)?;
+            
+            writer.write_session_detail(&export.state)?;
+            writer.write_message(&format!(
+                "Exported at {} with {} messages and {} activities",
+                timestamp::format_millis(export.exported_at),
+                export.messages.len(),
+                export.activities.len()
+            ))?;
+            
+            write_history(
+                writer,
+                if messages { &export.messages } else { &[] },
+                if activities { &export.activities } else { &[] },
+            )
+        }
         SessionCommand::Stats => {
-            let manager = session_manager.read().await;
-            let stats = manager.get_global_statistics().await;
+            let stats = backend.statistics().await?;
             
             writer.write_message("Session Statistics:")?;
             writer.write_message(&format!("  Total sessions: {}", stats.total_sessions))?;
@@ -331,17 +601,129 @@ async fn execute_session_command(
             writer.write_message(&format!("  Total bytes sent: {}", stats.total_bytes_sent))?;
             writer.write_message(&format!("  Total bytes received: {}", stats.total_bytes_received))?;
             writer.write_message(&format!("  Total errors: {}", stats.total_errors))?;
+            writer.write_message(&format!("  Dropped messages: {}", stats.total_dropped))?;
             
             Ok(())
         }
     }
 }
 
+fn create_interactive_session_config(name: &str, device_config: DeviceConfig) -> SessionConfig {
+    SessionConfig {
+        name: name.to_string(),
+        session_type: SessionType::Interactive,
+        auto_reconnect: device_config.auto_reconnect,
+        max_reconnect_attempts: device_config.max_reconnect_attempts,
+        reconnect_delay_ms: device_config.reconnect_delay_ms,
+        device_config,
+        timeout_ms: 0,
+        max_history_size: 1000,
+        log_activities: true,
+        tags: Vec::new(),
+        properties: std::collections::HashMap::new(),
+    }
+}
+
+/// Look up a device profile by name, or load it from a TOML file path
+fn resolve_device_profile(
+    device: &str,
+    config: &TermComConfig,
+    config_manager: &ConfigManager,
+) -> Result<DeviceConfig, TermComError> {
+    let path = std::path::Path::new(device);
+    if path.is_file() || path.extension().is_some_and(|extension| extension == "toml") {
+        return config_manager.load_device_from_path(path);
+    }
+    
+    config.find_device(device).cloned().ok_or_else(|| {
+        let available: Vec<&str> = config.devices.iter().map(|d| d.name.as_str()).collect();
+        TermComError::Config {
+            message: if available.is_empty() {
+                format!("Device '{}' not found; no devices are configured", device)
+            } else {
+                format!("Device '{}' not found; available devices: {}", device, available.join(", "))
+            },
+        }
+    })
+}
+
+/// Use an existing session, or open the port just for one command
+///
+/// Returns the session ID and whether the session is temporary.
+async fn open_serial_session(
+    backend: &SessionBackend,
+    session: Option<String>,
+    device_config: DeviceConfig,
+) -> Result<(String, bool), TermComError> {
+    match session {
+        Some(session) => Ok((session, false)),
+        None => {
+            let session_config = create_interactive_session_config(&Uuid::new_v4().to_string(), device_config);
+            Ok((backend.create_session(session_config, true).await?, true))
+        }
+    }
+}
+
+async fn close_serial_session(backend: &SessionBackend, session_id: &str, temporary: bool) -> Result<(), TermComError> {
+    if temporary {
+        backend.remove_session(session_id).await?;
+    }
+    Ok(())
+}
+
+/// Print messages as the monitor shows them, then activities
+fn write_history(writer: &ConsoleWriter, messages: &[Message], activities: &[SessionActivity]) -> Result<(), TermComError> {
+    let renderer = MonitorRenderer::new(MonitorFormat::Text, TimestampMode::Absolute, Direction::Both);
+    for message in messages {
+        if let Some(rendered) = renderer.render(message) {
+            writer.write_message(&rendered)?;
+        }
+    }
+    
+    for activity in activities {
+        writer.write_message(&format!(
+            "[{}] {}: {}",
+            timestamp::format_millis(activity.timestamp),
+            activity.activity_type,
+            activity.description
+        ))?;
+    }
+    Ok(())
+}
+
+fn write_boot_log(writer: &ConsoleWriter, log: &BootLog, output: Option<String>) -> Result<(), TermComError> {
+    let path = output.unwrap_or_else(|| log.default_file_name());
+    log.save(&path)?;
+    
+    writer.write_message(&format!(
+        "Ran '{}' on '{}': captured {} bytes in {} ms to {}",
+        log.sequence,
+        log.device_name,
+        log.output.len(),
+        log.duration.as_millis(),
+        path
+    ))?;
+    if let Some(ref until) = log.until {
+        if !log.matched {
+            writer.write_error(&format!("'{}' did not appear in the output before the timeout", until))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_backend_hint(writer: &ConsoleWriter, backend: &SessionBackend) -> Result<(), TermComError> {
+    if !backend.is_daemon() {
+        writer.write_message("No daemon is running; the session ends when this command exits. Start one with `termcom daemon start`.")?;
+    }
+    Ok(())
+}
+
 async fn execute_config_command(
     args: crate::cli::args::ConfigArgs,
     writer: &ConsoleWriter,
     config: &TermComConfig,
     config_manager: &ConfigManager,
+    config_path: Option<&str>,
 ) -> Result<(), TermComError> {
     match args.command {
         ConfigCommand::Show => {
@@ -383,19 +765,52 @@ async fn execute_config_command(
             writer.write_devices(&config.devices)?;
             Ok(())
         }
-        ConfigCommand::AddDevice { name, description: _, connection } => {
-            writer.write_message(&format!("Adding device '{}' with {:?} connection", name, connection))?;
-            writer.write_message("Device addition to configuration not fully implemented yet")?;
+        ConfigCommand::AddDevice(add) => {
+            let replace = add.replace;
+            let device = add.into_device_config()?;
+            let name = device.name.clone();
+            let path = edit_target(config_manager, config_path, false)?;
+            config_manager.update_config_at_path(&path, |config| config.add_device(device, replace))?;
+            writer.write_message(&format!("Device '{}' saved to '{}'", name, path.display()))?;
+            Ok(())
+        }
+        ConfigCommand::RemoveDevice { name } => {
+            let path = edit_target(config_manager, config_path, false)?;
+            config_manager.update_config_at_path(&path, |config| config.remove_device(&name))?;
+            writer.write_message(&format!("Device '{}' removed from '{}'", name, path.display()))?;
+            Ok(())
+        }
+        ConfigCommand::Set { key, value } => {
+            let path = edit_target(config_manager, config_path, !key.starts_with("devices."))?;
+            config_manager.update_config_at_path(&path, |config| config.set_value(&key, &value))?;
+            writer.write_message(&format!("Set '{}' to '{}' in '{}'", key, value, path.display()))?;
             Ok(())
         }
     }
 }
 
+/// Pick the config file an edit is written to
+///
+/// An explicit `--config` file always wins. Otherwise global settings go to
+/// the global file and devices to the project file, matching where
+/// `load_config` reads them from.
+fn edit_target(
+    config_manager: &ConfigManager,
+    config_path: Option<&str>,
+    global: bool,
+) -> Result<std::path::PathBuf, TermComError> {
+    match config_path {
+        Some(path) => Ok(std::path::PathBuf::from(path)),
+        None if global => Ok(config_manager.get_global_config_path_ref().clone()),
+        None => config_manager.project_config_target(),
+    }
+}
+
 fn parse_data(data: &str, format: DataFormat) -> Result<Vec<u8>, TermComError> {
     match format {
         DataFormat::Text => Ok(data.as_bytes().to_vec()),
         DataFormat::Hex => {
-            let cleaned = data.replace(' ', "").replace('\n', "");
+            let cleaned = data.replace([' ', '\n'], "");
             hex::decode(&cleaned)
                 .map_err(|e| TermComError::InvalidInput(format!("Invalid hex data: {}", e)))
         }
@@ -564,4 +979,180 @@ async fn execute_echo_server_command(
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(unix)]
+async fn execute_daemon_command(
+    args: crate::cli::args::DaemonArgs,
+    writer: &ConsoleWriter,
+    config: &TermComConfig,
+    config_path: Option<&str>,
+    verbose: bool,
+) -> Result<(), TermComError> {
+    use crate::cli::args::DaemonCommand;
+    use crate::daemon::{DaemonClient, DaemonServer};
+    use std::path::PathBuf;
+    use std::time::Duration;
+    
+    let socket_path = args.socket
+        .map(PathBuf::from)
+        .unwrap_or_else(crate::daemon::default_socket_path);
+    
+    match args.command {
+        DaemonCommand::Start { foreground, log_file } => {
+            if let Some(client) = DaemonClient::connect(&socket_path).await {
+                let pid = client.ping().await?;
+                return Err(TermComError::Session {
+                    message: format!("Daemon is already running (pid {}) on {}", pid, socket_path.display()),
+                });
+            }
+            
+            if foreground {
+                let server = DaemonServer::bind(
+                    &socket_path,
+                    config.global.history_limit,
+                    config.global.max_sessions,
+                    config.global.queue(),
+                ).await?;
+                
+                // Stop gracefully on Ctrl+C so sessions are closed and the socket is removed
+                let shutdown = server.shutdown_handle();
+                tokio::spawn(async move {
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        let _ = shutdown.send(()).await;
+                    }
+                });
+                
+                writer.write_message(&format!("Daemon listening on {} (Press Ctrl+C to stop)", socket_path.display()))?;
+                server.run().await?;
+                writer.write_message("Daemon stopped")?;
+                return Ok(());
+            }
+            
+            // Created here first so the log file does not create it with default permissions
+            if let Some(socket_dir) = socket_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                crate::daemon::server::prepare_socket_dir(socket_dir)?;
+            }
+            let log_path = log_file
+                .map(PathBuf::from)
+                .unwrap_or_else(|| socket_path.with_file_name("daemon.log"));
+            if let Some(parent) = log_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let log = std::fs::OpenOptions::new().create(true).append(true).open(&log_path)?;
+            
+            let mut command = std::process::Command::new(std::env::current_exe()?);
+            if let Some(config_path) = config_path {
+                command.args(["--config", config_path]);
+            }
+            if verbose {
+                command.arg("--verbose");
+            }
+            command
+                .arg("daemon")
+                .arg("--socket")
+                .arg(&socket_path)
+                .args(["start", "--foreground"])
+                .stdin(std::process::Stdio::null())
+                .stdout(log.try_clone()?)
+                .stderr(log);
+            
+            // Detach from the terminal's process group so Ctrl+C in the shell does not reach the daemon
+            std::os::unix::process::CommandExt::process_group(&mut command, 0);
+            command.spawn()?;
+            
+            // Wait for the daemon to accept connections
+            let client = DaemonClient::new(&socket_path);
+            for _ in 0..50 {
+                if let Ok(pid) = client.ping().await {
+                    writer.write_message(&format!("Daemon started (pid {}) on {}", pid, socket_path.display()))?;
+                    writer.write_message(&format!("Logging to {}", log_path.display()))?;
+                    return Ok(());
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            
+            Err(TermComError::Session {
+                message: format!("Daemon did not start; see {}", log_path.display()),
+            })
+        }
+        DaemonCommand::Stop => {
+            match DaemonClient::connect(&socket_path).await {
+                Some(client) => {
+                    client.shutdown().await?;
+                    writer.write_message("Daemon stopped")?;
+                }
+                None => writer.write_message(&format!("No daemon running on {}", socket_path.display()))?,
+            }
+            Ok(())
+        }
+        DaemonCommand::Status => {
+            match DaemonClient::connect(&socket_path).await {
+                Some(client) => {
+                    let pid = client.ping().await?;
+                    let sessions = client.list_sessions().await?;
+                    let active = sessions.iter().filter(|s| s.status == crate::core::session::SessionStatus::Active).count();
+                    
+                    writer.write_message(&format!("Daemon running (pid {}) on {}", pid, socket_path.display()))?;
+                    writer.write_message(&format!("  Sessions: {} ({} active)", sessions.len(), active))?;
+                }
+                None => writer.write_message(&format!("No daemon running on {}", socket_path.display()))?,
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn execute_daemon_command(
+    _args: crate::cli::args::DaemonArgs,
+    _writer: &ConsoleWriter,
+    _config: &TermComConfig,
+    _config_path: Option<&str>,
+    _verbose: bool,
+) -> Result<(), TermComError> {
+    Err(TermComError::InvalidInput("Daemon mode requires Unix domain sockets and is not supported on this platform".to_string()))
+}
+
+#[cfg(unix)]
+async fn execute_pty_command(
+    args: crate::cli::args::PtyArgs,
+    writer: &ConsoleWriter,
+) -> Result<(), TermComError> {
+    use crate::cli::args::PtyCommand;
+    use crate::infrastructure::pty::VirtualSerialPair;
+    use std::path::Path;
+    
+    match args.command {
+        PtyCommand::Pair { link_a, link_b } => {
+            let pair = VirtualSerialPair::open_with_links(
+                link_a.as_deref().map(Path::new),
+                link_b.as_deref().map(Path::new),
+            )?;
+            
+            let describe = |path: &Path, link: Option<&String>| match link {
+                Some(link) => format!("{} -> {}", link, path.display()),
+                None => path.display().to_string(),
+            };
+            writer.write_message(&format!(
+                "Virtual serial pair: {} <=> {}",
+                describe(pair.first_path(), link_a.as_ref()),
+                describe(pair.second_path(), link_b.as_ref()),
+            ))?;
+            writer.write_message("Press Ctrl+C to close the pair")?;
+            
+            tokio::signal::ctrl_c().await?;
+            drop(pair);
+            writer.write_message("Virtual serial pair closed")?;
+        }
+    }
+    
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn execute_pty_command(
+    _args: crate::cli::args::PtyArgs,
+    _writer: &ConsoleWriter,
+) -> Result<(), TermComError> {
+    Err(TermComError::InvalidInput("Pseudo-terminals are not supported on this platform".to_string()))
+}
@@ -1,5 +1,7 @@
 // CLI module - Command line interface
 pub mod args;
+pub mod backend;
 pub mod commands;
+pub mod monitor;
 pub mod output;
 
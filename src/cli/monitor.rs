@@ -0,0 +1,225 @@
+use crate::cli::args::{Direction, MonitorFormat, TimestampMode};
+use crate::core::communication::{message::MessageType, Message};
+use crate::core::timestamp;
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+
+/// Number of bytes shown per hexdump row
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Renders session messages for the live monitor
+#[derive(Debug, Clone)]
+pub struct MonitorRenderer {
+    format: MonitorFormat,
+    timestamps: TimestampMode,
+    direction: Direction,
+    started: SystemTime,
+}
+
+impl MonitorRenderer {
+    /// Create a renderer whose relative timestamps count from now
+    pub fn new(format: MonitorFormat, timestamps: TimestampMode, direction: Direction) -> Self {
+        Self {
+            format,
+            timestamps,
+            direction,
+            started: SystemTime::now(),
+        }
+    }
+
+    /// Set the reference point for relative timestamps
+    pub fn with_start(mut self, started: SystemTime) -> Self {
+        self.started = started;
+        self
+    }
+
+    /// Check whether a message passes the direction filter
+    pub fn accepts(&self, message: &Message) -> bool {
+        match self.direction {
+            Direction::Both => true,
+            Direction::Rx => direction_label(&message.message_type) == "RX",
+            Direction::Tx => direction_label(&message.message_type) == "TX",
+        }
+    }
+
+    /// Render a message, returning `None` if it is filtered out
+    ///
+    /// Hexdump output spans several lines; all other formats produce one.
+    pub fn render(&self, message: &Message) -> Option<String> {
+        if !self.accepts(message) {
+            return None;
+        }
+
+        let rendered = match self.format {
+            MonitorFormat::Json => self.render_json(message),
+            MonitorFormat::Text => format!("{}{}", self.header(message), message.data_as_escaped_text()),
+            MonitorFormat::Hex => format!("{}{}", self.header(message), message.data_as_hex()),
+            MonitorFormat::Hexdump => {
+                let header = self.header(message);
+                let mut lines = vec![header.trim_end().to_string()];
+                lines.extend(hexdump(&message.data));
+                lines.join("\n")
+            }
+        };
+
+        Some(rendered)
+    }
+
+    fn header(&self, message: &Message) -> String {
+        let direction = direction_label(&message.message_type);
+        let size = message.data.len();
+
+        match self.timestamp(message) {
+            Some(timestamp) => format!("[{}] {} ({} bytes) ", timestamp, direction, size),
+            None => format!("{} ({} bytes) ", direction, size),
+        }
+    }
+
+    fn timestamp(&self, message: &Message) -> Option<String> {
+        match self.timestamps {
+            TimestampMode::Absolute => Some(timestamp::format_millis(message.timestamp)),
+            TimestampMode::Relative => Some(format!("+{:.3}s", self.elapsed(message).as_secs_f64())),
+            TimestampMode::None => None,
+        }
+    }
+
+    fn elapsed(&self, message: &Message) -> Duration {
+        message.timestamp.duration_since(self.started).unwrap_or_default()
+    }
+
+    fn render_json(&self, message: &Message) -> String {
+        let mut value = json!({
+            "direction": direction_label(&message.message_type).to_lowercase(),
+            "session_id": message.session_id,
+            "device": message.device_name,
+            "sequence": message.metadata.sequence,
+            "size": message.data.len(),
+            "hex": hex::encode(&message.data),
+            "text": String::from_utf8_lossy(&message.data),
+        });
+
+        match self.timestamps {
+            TimestampMode::Absolute => {
+                value["timestamp"] = json!(timestamp::format_millis(message.timestamp));
+            }
+            TimestampMode::Relative => {
+                value["elapsed"] = json!(self.elapsed(message).as_secs_f64());
+            }
+            TimestampMode::None => {}
+        }
+
+        value.to_string()
+    }
+}
+
+/// Get the direction label shown for a message type
+fn direction_label(message_type: &MessageType) -> &'static str {
+    match message_type {
+        MessageType::Sent | MessageType::Command => "TX",
+        MessageType::Received | MessageType::Response => "RX",
+        MessageType::System => "--",
+        MessageType::Error => "!!",
+    }
+}
+
+/// Render bytes as `hexdump -C` style rows
+fn hexdump(data: &[u8]) -> Vec<String> {
+    data.chunks(HEXDUMP_WIDTH)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut hex = String::with_capacity(HEXDUMP_WIDTH * 3 + 1);
+            for i in 0..HEXDUMP_WIDTH {
+                if i == HEXDUMP_WIDTH / 2 {
+                    hex.push(' ');
+                }
+                match chunk.get(i) {
+                    Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+                    None => hex.push_str("   "),
+                }
+            }
+
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| if (0x20..=0x7e).contains(&byte) { byte as char } else { '.' })
+                .collect();
+
+            format!("{:08x}  {} |{}|", row * HEXDUMP_WIDTH, hex, ascii)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn message_at(message_type: MessageType, data: &[u8], time: SystemTime) -> Message {
+        let mut message = Message::new(
+            "session-1".to_string(),
+            "device-1".to_string(),
+            message_type,
+            data.to_vec(),
+            "test".to_string(),
+        );
+        message.timestamp = time;
+        message
+    }
+
+    #[test]
+    fn test_text_rendering() {
+        let renderer = MonitorRenderer::new(MonitorFormat::Text, TimestampMode::None, Direction::Both);
+        let message = message_at(MessageType::Received, b"OK\r\n\x01", SystemTime::now());
+
+        assert_eq!(renderer.render(&message).unwrap(), "RX (5 bytes) OK\\r\\n\\x01");
+    }
+
+    #[test]
+    fn test_relative_timestamps() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let renderer = MonitorRenderer::new(MonitorFormat::Hex, TimestampMode::Relative, Direction::Both)
+            .with_start(start);
+        let message = message_at(MessageType::Sent, b"AT", start + Duration::from_millis(1500));
+
+        assert_eq!(renderer.render(&message).unwrap(), "[+1.500s] TX (2 bytes) 41 54");
+    }
+
+    #[test]
+    fn test_direction_filter() {
+        let renderer = MonitorRenderer::new(MonitorFormat::Text, TimestampMode::None, Direction::Rx);
+        let now = SystemTime::now();
+
+        assert!(renderer.render(&message_at(MessageType::Received, b"rx", now)).is_some());
+        assert!(renderer.render(&message_at(MessageType::Sent, b"tx", now)).is_none());
+        assert!(renderer.render(&message_at(MessageType::Command, b"cmd", now)).is_none());
+    }
+
+    #[test]
+    fn test_hexdump_rendering() {
+        let renderer = MonitorRenderer::new(MonitorFormat::Hexdump, TimestampMode::None, Direction::Both);
+        let data: Vec<u8> = (0x41..0x52).collect();
+        let rendered = renderer.render(&message_at(MessageType::Received, &data, SystemTime::now())).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "RX (17 bytes)");
+        assert_eq!(
+            lines[1],
+            "00000000  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|"
+        );
+        assert!(lines[2].starts_with("00000010  51 "));
+        assert!(lines[2].ends_with("|Q|"));
+    }
+
+    #[test]
+    fn test_json_rendering() {
+        let renderer = MonitorRenderer::new(MonitorFormat::Json, TimestampMode::Absolute, Direction::Both);
+        let message = message_at(MessageType::Sent, b"hi\n", UNIX_EPOCH);
+        let value: serde_json::Value = serde_json::from_str(&renderer.render(&message).unwrap()).unwrap();
+
+        assert_eq!(value["direction"], "tx");
+        assert_eq!(value["hex"], "68690a");
+        assert_eq!(value["text"], "hi\n");
+        assert_eq!(value["size"], 3);
+        assert_eq!(value["timestamp"], "1970-01-01T00:00:00.000Z");
+    }
+}
@@ -2,6 +2,7 @@ use crate::cli::args::OutputFormat;
 use crate::core::communication::TransportType;
 use crate::core::session::{SessionState, SessionSummary};
 use crate::domain::config::{DeviceConfig, TermComConfig, ConnectionConfig};
+use crate::infrastructure::serial::{PortInfo, PortKind};
 use serde_json;
 use std::io::{self, Write};
 use tabled::{Table, Tabled};
@@ -12,6 +13,7 @@ pub trait OutputWriter {
     fn write_session_detail(&self, session: &SessionState) -> Result<(), OutputError>;
     fn write_config(&self, config: &TermComConfig) -> Result<(), OutputError>;
     fn write_devices(&self, devices: &[DeviceConfig]) -> Result<(), OutputError>;
+    fn write_ports(&self, ports: &[PortInfo]) -> Result<(), OutputError>;
     fn write_message(&self, message: &str) -> Result<(), OutputError>;
     fn write_error(&self, error: &str) -> Result<(), OutputError>;
 }
@@ -105,6 +107,9 @@ impl OutputWriter for ConsoleWriter {
                 println!("    Bytes sent: {}", session.statistics.bytes_sent);
                 println!("    Bytes received: {}", session.statistics.bytes_received);
                 println!("    Errors: {}", session.statistics.error_count);
+                if session.statistics.messages_dropped > 0 {
+                    println!("    Dropped messages: {}", session.statistics.messages_dropped);
+                }
                 
                 if session.statistics.avg_response_time_ms > 0.0 {
                     println!("    Average response time: {:.2}ms", session.statistics.avg_response_time_ms);
@@ -222,6 +227,54 @@ impl OutputWriter for ConsoleWriter {
         Ok(())
     }
 
+    fn write_ports(&self, ports: &[PortInfo]) -> Result<(), OutputError> {
+        match self.format {
+            OutputFormat::Text => {
+                if ports.is_empty() {
+                    println!("No serial ports found");
+                }
+                for port in ports {
+                    println!("Port: {}", port.port_name);
+                    println!("  Type: {}", port_kind(port.kind));
+                    if let (Some(vid), Some(pid)) = (port.vid, port.pid) {
+                        println!("  USB ID: {:04x}:{:04x}", vid, pid);
+                    }
+                    if let Some(ref serial_number) = port.serial_number {
+                        println!("  Serial number: {}", serial_number);
+                    }
+                    if let Some(ref manufacturer) = port.manufacturer {
+                        println!("  Manufacturer: {}", manufacturer);
+                    }
+                    if let Some(ref product) = port.product {
+                        println!("  Product: {}", product);
+                    }
+                    if let Some(usb) = port.usb_match() {
+                        println!("  Select with: {}", usb);
+                    }
+                    for alias in &port.aliases {
+                        println!("  Alias: {}", alias);
+                    }
+                    println!();
+                }
+            }
+            OutputFormat::Json => {
+                let output = serde_json::to_string_pretty(ports)?;
+                println!("{}", output);
+            }
+            OutputFormat::Table => {
+                if !ports.is_empty() {
+                    let table_data: Vec<PortTableRow> = ports.iter().map(PortTableRow::from).collect();
+                    let table = Table::new(table_data);
+                    println!("{}", table);
+                }
+            }
+            OutputFormat::Csv => {
+                print!("{}", ports_csv(ports));
+            }
+        }
+        Ok(())
+    }
+
     fn write_message(&self, message: &str) -> Result<(), OutputError> {
         match self.format {
             OutputFormat::Json => {
@@ -327,6 +380,79 @@ impl From<&DeviceConfig> for DeviceTableRow {
     }
 }
 
+/// Table row for serial ports
+#[derive(Tabled)]
+struct PortTableRow {
+    port: String,
+    #[tabled(rename = "type")]
+    kind: String,
+    usb_id: String,
+    serial_number: String,
+    manufacturer: String,
+    product: String,
+    aliases: String,
+}
+
+impl From<&PortInfo> for PortTableRow {
+    fn from(port: &PortInfo) -> Self {
+        Self {
+            port: port.port_name.clone(),
+            kind: port_kind(port.kind).to_string(),
+            usb_id: usb_id(port),
+            serial_number: port.serial_number.clone().unwrap_or_default(),
+            manufacturer: port.manufacturer.clone().unwrap_or_default(),
+            product: port.product.clone().unwrap_or_default(),
+            aliases: port.aliases.join("\n"),
+        }
+    }
+}
+
+fn port_kind(kind: PortKind) -> &'static str {
+    match kind {
+        PortKind::Usb => "USB",
+        PortKind::Pci => "PCI",
+        PortKind::Bluetooth => "Bluetooth",
+        PortKind::Unknown => "Unknown",
+    }
+}
+
+fn usb_id(port: &PortInfo) -> String {
+    match (port.vid, port.pid) {
+        (Some(vid), Some(pid)) => format!("{:04x}:{:04x}", vid, pid),
+        _ => String::new(),
+    }
+}
+
+/// Serial ports as CSV; aliases are separated by spaces
+fn ports_csv(ports: &[PortInfo]) -> String {
+    let mut csv = "port_name,type,vid,pid,serial_number,manufacturer,product,aliases\n".to_string();
+    for port in ports {
+        let fields = [
+            port.port_name.clone(),
+            port_kind(port.kind).to_lowercase(),
+            port.vid.map(|vid| format!("{:04x}", vid)).unwrap_or_default(),
+            port.pid.map(|pid| format!("{:04x}", pid)).unwrap_or_default(),
+            port.serial_number.clone().unwrap_or_default(),
+            port.manufacturer.clone().unwrap_or_default(),
+            port.product.clone().unwrap_or_default(),
+            port.aliases.join(" "),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
 /// File output writer
 pub struct FileWriter {
     path: String,
@@ -347,10 +473,7 @@ impl FileWriter {
 
 /// Helper function to get transport type from connection config
 fn get_transport_type(connection: &ConnectionConfig) -> TransportType {
-    match connection {
-        ConnectionConfig::Serial { .. } => TransportType::Serial,
-        ConnectionConfig::Tcp { .. } => TransportType::Tcp,
-    }
+    TransportType::for_connection(connection)
 }
 
 impl OutputWriter for FileWriter {
@@ -420,6 +543,17 @@ impl OutputWriter for FileWriter {
         self.write_to_file(&content)
     }
 
+    fn write_ports(&self, ports: &[PortInfo]) -> Result<(), OutputError> {
+        let content = match self.format {
+            OutputFormat::Json => serde_json::to_string_pretty(ports)?,
+            OutputFormat::Csv => ports_csv(ports),
+            _ => {
+                return Err(OutputError::TableError("File output only supports JSON and CSV formats".to_string()));
+            }
+        };
+        self.write_to_file(&content)
+    }
+
     fn write_message(&self, message: &str) -> Result<(), OutputError> {
         self.write_to_file(message)
     }
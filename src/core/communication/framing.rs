@@ -0,0 +1,561 @@
+use crate::domain::{
+    config::{Endianness, FramingConfig},
+    error::{TermComError, TermComResult},
+};
+use tracing::warn;
+
+/// Longest frame collected while waiting for it to end
+///
+/// Data that grows past this without completing a frame is discarded, so a
+/// device sending garbage or a wrong framing setting cannot exhaust memory.
+pub const MAX_FRAME_SIZE: usize = 64 * 1024;
+
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+/// Splits a received byte stream into frames and wraps sent data into frames
+pub trait Framer: Send {
+    /// Feed received bytes and take the frames they complete
+    ///
+    /// Bytes of an unfinished frame are kept for the next call.
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>>;
+
+    /// Wrap a payload into a frame ready to be sent
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>>;
+
+    /// Forget a partly received frame, for example after the connection dropped
+    fn reset(&mut self);
+}
+
+/// Create a framer for the given configuration
+///
+/// The configuration should have passed [`FramingConfig::validate`].
+pub fn framer(config: &FramingConfig) -> Box<dyn Framer> {
+    match config {
+        FramingConfig::Raw => Box::new(RawFramer),
+        FramingConfig::Line { ending } => Box::new(DelimiterFramer::new(ending.as_bytes().to_vec())),
+        FramingConfig::Delimiter { delimiter } => Box::new(DelimiterFramer::new(delimiter.clone())),
+        FramingConfig::FixedLength { length } => Box::new(FixedLengthFramer {
+            length: (*length).max(1),
+            buffer: Vec::new(),
+        }),
+        FramingConfig::LengthPrefixed { offset, width, endianness } => Box::new(LengthPrefixedFramer {
+            offset: *offset,
+            width: usize::from(*width).clamp(1, 8),
+            endianness: *endianness,
+            buffer: Vec::new(),
+        }),
+        FramingConfig::Slip => Box::new(SlipFramer { frame: Vec::new(), escaped: false }),
+        FramingConfig::Cobs => Box::new(CobsFramer { buffer: Vec::new() }),
+        FramingConfig::StxEtx { stx, etx, escape } => Box::new(StxEtxFramer {
+            stx: *stx,
+            etx: *etx,
+            escape: *escape,
+            frame: None,
+            escaped: false,
+        }),
+    }
+}
+
+fn framing_error(message: String) -> TermComError {
+    TermComError::Communication { message }
+}
+
+/// Discard a buffer that grew too long to ever become a frame
+fn discard_oversized(buffer: &mut Vec<u8>) -> bool {
+    if buffer.len() <= MAX_FRAME_SIZE {
+        return false;
+    }
+    warn!("Discarding {} received bytes without a frame boundary", buffer.len());
+    buffer.clear();
+    true
+}
+
+/// Every read is a message of its own
+struct RawFramer;
+
+impl Framer for RawFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        if data.is_empty() {
+            Vec::new()
+        } else {
+            vec![data.to_vec()]
+        }
+    }
+
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        Ok(payload.to_vec())
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Frames ending in a byte sequence, which is removed; empty frames are skipped
+struct DelimiterFramer {
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl DelimiterFramer {
+    fn new(delimiter: Vec<u8>) -> Self {
+        Self { delimiter, buffer: Vec::new() }
+    }
+}
+
+impl Framer for DelimiterFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(data);
+
+        let mut frames = Vec::new();
+        let mut start = 0;
+        while let Some(position) = self.buffer[start..]
+            .windows(self.delimiter.len())
+            .position(|window| window == self.delimiter.as_slice())
+        {
+            if position > 0 {
+                frames.push(self.buffer[start..start + position].to_vec());
+            }
+            start += position + self.delimiter.len();
+        }
+        self.buffer.drain(..start);
+        discard_oversized(&mut self.buffer);
+
+        frames
+    }
+
+    /// Data that already ends with the delimiter is sent as is
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        let mut frame = payload.to_vec();
+        if !payload.ends_with(&self.delimiter) {
+            frame.extend_from_slice(&self.delimiter);
+        }
+        Ok(frame)
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+struct FixedLengthFramer {
+    length: usize,
+    buffer: Vec<u8>,
+}
+
+impl Framer for FixedLengthFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(data);
+
+        let complete = self.buffer.len() - self.buffer.len() % self.length;
+        let frames = self.buffer[..complete]
+            .chunks_exact(self.length)
+            .map(<[u8]>::to_vec)
+            .collect();
+        self.buffer.drain(..complete);
+
+        frames
+    }
+
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        if payload.len() != self.length {
+            return Err(framing_error(format!(
+                "Frames are {} bytes long, but {} bytes were given",
+                self.length,
+                payload.len()
+            )));
+        }
+        Ok(payload.to_vec())
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// Header bytes, a length field, then as many payload bytes as it says
+///
+/// Messages hold the header bytes and the payload, without the length field.
+struct LengthPrefixedFramer {
+    offset: usize,
+    width: usize,
+    endianness: Endianness,
+    buffer: Vec<u8>,
+}
+
+impl LengthPrefixedFramer {
+    fn read_length(&self, field: &[u8]) -> u64 {
+        let fold = |length: u64, byte: &u8| length << 8 | u64::from(*byte);
+        match self.endianness {
+            Endianness::Big => field.iter().fold(0, fold),
+            Endianness::Little => field.iter().rev().fold(0, fold),
+        }
+    }
+}
+
+impl Framer for LengthPrefixedFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(data);
+
+        let header = self.offset + self.width;
+        let mut frames = Vec::new();
+        while self.buffer.len() >= header {
+            let length = self.read_length(&self.buffer[self.offset..header]);
+            if length > MAX_FRAME_SIZE as u64 {
+                // The stream cannot be resynchronized, so start over
+                warn!("Discarding received data announcing a {} byte frame", length);
+                self.buffer.clear();
+                break;
+            }
+
+            let end = header + length as usize;
+            if self.buffer.len() < end {
+                break;
+            }
+            let mut frame = self.buffer[..self.offset].to_vec();
+            frame.extend_from_slice(&self.buffer[header..end]);
+            self.buffer.drain(..end);
+            frames.push(frame);
+        }
+
+        frames
+    }
+
+    /// The first `offset` bytes of the payload are the header
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        let Some(body) = payload.get(self.offset..) else {
+            return Err(framing_error(format!(
+                "Frames start with a {} byte header, but only {} bytes were given",
+                self.offset,
+                payload.len()
+            )));
+        };
+
+        let length = body.len() as u64;
+        if self.width < 8 && length >> (8 * self.width) != 0 {
+            return Err(framing_error(format!(
+                "{} bytes do not fit a {} byte length field",
+                length, self.width
+            )));
+        }
+
+        let field = match self.endianness {
+            Endianness::Big => length.to_be_bytes()[8 - self.width..].to_vec(),
+            Endianness::Little => length.to_le_bytes()[..self.width].to_vec(),
+        };
+        let mut frame = Vec::with_capacity(payload.len() + self.width);
+        frame.extend_from_slice(&payload[..self.offset]);
+        frame.extend_from_slice(&field);
+        frame.extend_from_slice(body);
+        Ok(frame)
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// SLIP (RFC 1055); empty frames between END bytes are skipped
+struct SlipFramer {
+    frame: Vec<u8>,
+    escaped: bool,
+}
+
+impl Framer for SlipFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for &byte in data {
+            if self.escaped {
+                self.escaped = false;
+                // RFC 1055 keeps the byte after an unknown escape as is
+                self.frame.push(match byte {
+                    SLIP_ESC_END => SLIP_END,
+                    SLIP_ESC_ESC => SLIP_ESC,
+                    other => other,
+                });
+            } else if byte == SLIP_ESC {
+                self.escaped = true;
+            } else if byte == SLIP_END {
+                if !self.frame.is_empty() {
+                    frames.push(std::mem::take(&mut self.frame));
+                }
+            } else {
+                self.frame.push(byte);
+            }
+            discard_oversized(&mut self.frame);
+        }
+        frames
+    }
+
+    /// Frames start with END as well, which flushes any line noise at the receiver
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        let mut frame = Vec::with_capacity(payload.len() + 2);
+        frame.push(SLIP_END);
+        for &byte in payload {
+            match byte {
+                SLIP_END => frame.extend_from_slice(&[SLIP_ESC, SLIP_ESC_END]),
+                SLIP_ESC => frame.extend_from_slice(&[SLIP_ESC, SLIP_ESC_ESC]),
+                _ => frame.push(byte),
+            }
+        }
+        frame.push(SLIP_END);
+        Ok(frame)
+    }
+
+    fn reset(&mut self) {
+        self.frame.clear();
+        self.escaped = false;
+    }
+}
+
+/// COBS frames ending in a zero byte; malformed frames are discarded
+struct CobsFramer {
+    buffer: Vec<u8>,
+}
+
+impl Framer for CobsFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for &byte in data {
+            if byte != 0 {
+                self.buffer.push(byte);
+                discard_oversized(&mut self.buffer);
+                continue;
+            }
+            if self.buffer.is_empty() {
+                continue;
+            }
+
+            match cobs_decode(&self.buffer) {
+                Some(frame) => frames.push(frame),
+                None => warn!("Discarding malformed COBS frame of {} bytes", self.buffer.len()),
+            }
+            self.buffer.clear();
+        }
+        frames
+    }
+
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        let mut frame = cobs_encode(payload);
+        frame.push(0);
+        Ok(frame)
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+fn cobs_encode(payload: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(payload.len() + payload.len() / 254 + 2);
+    let mut code_index = 0;
+    let mut code = 1u8;
+    encoded.push(0);
+
+    for &byte in payload {
+        if byte != 0 {
+            encoded.push(byte);
+            code += 1;
+        }
+        if byte == 0 || code == 0xFF {
+            encoded[code_index] = code;
+            code_index = encoded.len();
+            encoded.push(0);
+            code = 1;
+        }
+    }
+    encoded[code_index] = code;
+
+    encoded
+}
+
+/// Decode one COBS frame without its trailing zero
+fn cobs_decode(encoded: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+
+    while index < encoded.len() {
+        let code = usize::from(encoded[index]);
+        if code == 0 || index + code > encoded.len() {
+            return None;
+        }
+        decoded.extend_from_slice(&encoded[index + 1..index + code]);
+        index += code;
+        if code < 0xFF && index < encoded.len() {
+            decoded.push(0);
+        }
+    }
+
+    Some(decoded)
+}
+
+/// STX ... ETX frames with byte stuffing
+///
+/// Bytes outside a frame are ignored, and an unescaped STX inside a frame
+/// starts over, since the previous frame was evidently cut off.
+struct StxEtxFramer {
+    stx: u8,
+    etx: u8,
+    escape: u8,
+    /// The frame being received, once its STX has arrived
+    frame: Option<Vec<u8>>,
+    escaped: bool,
+}
+
+impl Framer for StxEtxFramer {
+    fn decode(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for &byte in data {
+            let Some(frame) = self.frame.as_mut() else {
+                if byte == self.stx {
+                    self.frame = Some(Vec::new());
+                }
+                continue;
+            };
+
+            if self.escaped {
+                self.escaped = false;
+                frame.push(byte);
+            } else if byte == self.escape {
+                self.escaped = true;
+            } else if byte == self.etx {
+                frames.extend(self.frame.take());
+            } else if byte == self.stx {
+                frame.clear();
+            } else {
+                frame.push(byte);
+            }
+
+            if self.frame.as_mut().is_some_and(discard_oversized) {
+                self.frame = None;
+            }
+        }
+        frames
+    }
+
+    fn encode(&self, payload: &[u8]) -> TermComResult<Vec<u8>> {
+        let mut frame = Vec::with_capacity(payload.len() + 2);
+        frame.push(self.stx);
+        for &byte in payload {
+            if byte == self.stx || byte == self.etx || byte == self.escape {
+                frame.push(self.escape);
+            }
+            frame.push(byte);
+        }
+        frame.push(self.etx);
+        Ok(frame)
+    }
+
+    fn reset(&mut self) {
+        self.frame = None;
+        self.escaped = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::LineEnding;
+
+    /// Feed data one byte at a time, as a slow link would deliver it
+    fn decode_bytewise(framer: &mut dyn Framer, data: &[u8]) -> Vec<Vec<u8>> {
+        data.iter().flat_map(|byte| framer.decode(std::slice::from_ref(byte))).collect()
+    }
+
+    fn round_trip(config: FramingConfig, payloads: &[&[u8]]) {
+        let mut framer = framer(&config);
+        let stream: Vec<u8> = payloads.iter().flat_map(|payload| framer.encode(payload).unwrap()).collect();
+
+        let expected: Vec<Vec<u8>> = payloads.iter().map(|payload| payload.to_vec()).collect();
+        assert_eq!(framer.decode(&stream), expected, "{}", config);
+        assert_eq!(decode_bytewise(framer.as_mut(), &stream), expected, "{}", config);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let binary: &[u8] = &[0x00, 0x02, 0x03, 0x10, 0xC0, 0xDB, 0xFF, 0x00];
+        let long = vec![0x55; 600];
+
+        round_trip(FramingConfig::Line { ending: LineEnding::Crlf }, &[b"OK", b"+CSQ: 21,0"]);
+        round_trip(FramingConfig::Delimiter { delimiter: vec![0x7E] }, &[b"first", b"second"]);
+        round_trip(FramingConfig::FixedLength { length: 4 }, &[b"abcd", b"efgh"]);
+        round_trip(FramingConfig::LengthPrefixed { offset: 1, width: 2, endianness: Endianness::Little }, &[b"\x01hello", b"\x02", &long]);
+        round_trip(FramingConfig::LengthPrefixed { offset: 0, width: 4, endianness: Endianness::Big }, &[b"", binary]);
+        for config in [
+            FramingConfig::Slip,
+            FramingConfig::Cobs,
+            FramingConfig::StxEtx { stx: 0x02, etx: 0x03, escape: 0x10 },
+        ] {
+            round_trip(config, &[binary, b"plain", &long]);
+        }
+    }
+
+    #[test]
+    fn test_known_encodings() {
+        let encode = |config: FramingConfig, payload: &[u8]| framer(&config).encode(payload).unwrap();
+
+        assert_eq!(encode(FramingConfig::Cobs, &[0x11, 0x22, 0x00, 0x33]), [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+        assert_eq!(encode(FramingConfig::Cobs, &[0x00]), [0x01, 0x01, 0x00]);
+        let block: Vec<u8> = (1..=254).collect();
+        assert_eq!(encode(FramingConfig::Cobs, &block)[..2], [0xFF, 0x01]);
+        assert_eq!(encode(FramingConfig::Slip, &[0x01, 0xC0, 0xDB]), [0xC0, 0x01, 0xDB, 0xDC, 0xDB, 0xDD, 0xC0]);
+        assert_eq!(
+            encode(FramingConfig::StxEtx { stx: 0x02, etx: 0x03, escape: 0x10 }, &[0x41, 0x03, 0x10]),
+            [0x02, 0x41, 0x10, 0x03, 0x10, 0x10, 0x03]
+        );
+        assert_eq!(
+            encode(FramingConfig::LengthPrefixed { offset: 1, width: 2, endianness: Endianness::Big }, b"\x07abc"),
+            b"\x07\x00\x03abc"
+        );
+        assert_eq!(encode(FramingConfig::Line { ending: LineEnding::Cr }, b"ATZ"), b"ATZ\r");
+        assert_eq!(encode(FramingConfig::Line { ending: LineEnding::Cr }, b"ATZ\r"), b"ATZ\r");
+    }
+
+    #[test]
+    fn test_partial_and_merged_reads() {
+        let mut lines = framer(&FramingConfig::Line { ending: LineEnding::Lf });
+        assert!(lines.decode(b"temp=2").is_empty());
+        assert_eq!(lines.decode(b"1\n\nhum=40\nbat"), vec![b"temp=21".to_vec(), b"hum=40".to_vec()]);
+        lines.reset();
+        assert_eq!(lines.decode(b"ok\n"), vec![b"ok".to_vec()]);
+
+        let mut fixed = framer(&FramingConfig::FixedLength { length: 3 });
+        assert_eq!(fixed.decode(b"abcdefg"), vec![b"abc".to_vec(), b"def".to_vec()]);
+        assert_eq!(fixed.decode(b"hi"), vec![b"ghi".to_vec()]);
+    }
+
+    #[test]
+    fn test_noise_and_malformed_frames() {
+        // Bytes before STX are ignored, and a repeated STX starts over
+        let mut stx_etx = framer(&FramingConfig::StxEtx { stx: 0x02, etx: 0x03, escape: 0x10 });
+        assert_eq!(stx_etx.decode(b"noise\x02cut\x02whole\x03"), vec![b"whole".to_vec()]);
+
+        // A code byte pointing past the end of the frame
+        let mut cobs = framer(&FramingConfig::Cobs);
+        assert_eq!(cobs.decode(&[0x05, 0x11, 0x00, 0x02, 0x22, 0x00]), vec![vec![0x22]]);
+
+        let mut slip = framer(&FramingConfig::Slip);
+        assert_eq!(slip.decode(&[0xC0, 0xC0, 0x01, 0xC0]), vec![vec![0x01]]);
+
+        // Endless data without a delimiter does not grow without bound
+        let mut lines = framer(&FramingConfig::Line { ending: LineEnding::Lf });
+        assert!(lines.decode(&vec![b'x'; MAX_FRAME_SIZE + 1]).is_empty());
+        assert_eq!(lines.decode(b"ok\n"), vec![b"ok".to_vec()]);
+
+        let mut length = framer(&FramingConfig::LengthPrefixed { offset: 0, width: 4, endianness: Endianness::Big });
+        assert!(length.decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0x00]).is_empty());
+        assert_eq!(length.decode(&[0x00, 0x00, 0x00, 0x01, 0x2A]), vec![vec![0x2A]]);
+    }
+
+    #[test]
+    fn test_unencodable_payloads() {
+        let fixed = framer(&FramingConfig::FixedLength { length: 4 });
+        assert!(fixed.encode(b"abc").is_err());
+
+        let length = framer(&FramingConfig::LengthPrefixed { offset: 2, width: 1, endianness: Endianness::Big });
+        assert!(length.encode(b"\x01").is_err());
+        assert!(length.encode(&[0u8; 258]).is_err());
+        assert!(length.encode(&[0u8; 257]).is_ok());
+    }
+}
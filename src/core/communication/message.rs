@@ -1,6 +1,27 @@
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
+/// Message property holding the address of the peer that sent the data
+pub const PEER_ADDRESS_PROPERTY: &str = "peer_addr";
+
+/// Message property naming the connection event a system message reports
+pub const EVENT_PROPERTY: &str = "event";
+
+/// Event reported when a peer connects to a listening session
+pub const PEER_CONNECTED_EVENT: &str = "peer_connected";
+
+/// Event reported when a peer disconnects from a listening session
+pub const PEER_DISCONNECTED_EVENT: &str = "peer_disconnected";
+
+/// Event reported when the device behind a session goes away
+pub const DISCONNECTED_EVENT: &str = "disconnected";
+
+/// Message property holding the exit code of a process that ended
+pub const EXIT_CODE_PROPERTY: &str = "exit_code";
+
+/// Message property naming the output stream (stdout or stderr) data came from
+pub const STREAM_PROPERTY: &str = "stream";
+
 /// Unified message representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -113,6 +134,30 @@ impl Message {
         )
     }
     
+    /// Create a system message reporting a connection event
+    ///
+    /// The event name is stored in the [`EVENT_PROPERTY`] property and the
+    /// description in the message data.
+    pub fn event(
+        session_id: String,
+        device_name: String,
+        event: &str,
+        description: String,
+        transport: String,
+    ) -> Self {
+        let mut message = Self::system(session_id, device_name, description, transport);
+        message.add_property(EVENT_PROPERTY.to_string(), event.to_string());
+        message
+    }
+    
+    /// Get the connection event reported by a system message
+    pub fn event_name(&self) -> Option<&str> {
+        match self.message_type {
+            MessageType::System => self.metadata.properties.get(EVENT_PROPERTY).map(String::as_str),
+            _ => None,
+        }
+    }
+    
     /// Create an error message
     pub fn error(
         session_id: String,
@@ -178,6 +223,25 @@ impl Message {
             .join(" ")
     }
     
+    /// Get message data as text, escaping anything that is not printable ASCII
+    ///
+    /// Carriage returns, line feeds and tabs are shown as `\r`, `\n` and
+    /// `\t`; other bytes as `\xNN`. The result always fits on one line.
+    pub fn data_as_escaped_text(&self) -> String {
+        let mut text = String::with_capacity(self.data.len());
+        for &byte in &self.data {
+            match byte {
+                b'\r' => text.push_str("\\r"),
+                b'\n' => text.push_str("\\n"),
+                b'\t' => text.push_str("\\t"),
+                b'\\' => text.push_str("\\\\"),
+                0x20..=0x7e => text.push(byte as char),
+                _ => text.push_str(&format!("\\x{:02x}", byte)),
+            }
+        }
+        text
+    }
+    
     /// Add a tag to the message
     pub fn add_tag(&mut self, tag: String) {
         if !self.metadata.tags.contains(&tag) {
@@ -226,10 +290,8 @@ impl Message {
         }
         
         // Check tags
-        if !pattern.tags.is_empty() {
-            if !pattern.tags.iter().all(|tag| self.metadata.tags.contains(tag)) {
-                return false;
-            }
+        if !pattern.tags.is_empty() && !pattern.tags.iter().all(|tag| self.metadata.tags.contains(tag)) {
+            return false;
         }
         
         true
@@ -298,6 +360,25 @@ mod tests {
         assert_eq!(msg.metadata.size, 5);
     }
     
+    #[test]
+    fn test_event_message() {
+        let msg = Message::event(
+            "session1".to_string(),
+            "device1".to_string(),
+            PEER_CONNECTED_EVENT,
+            "Client 10.0.0.2:4000 connected".to_string(),
+            "tcp_server".to_string(),
+        );
+        
+        assert!(matches!(msg.message_type, MessageType::System));
+        assert_eq!(msg.event_name(), Some(PEER_CONNECTED_EVENT));
+        assert_eq!(msg.data_as_string().unwrap(), "Client 10.0.0.2:4000 connected");
+        
+        let mut data = Message::received("s".to_string(), "d".to_string(), Vec::new(), "t".to_string());
+        data.add_property(EVENT_PROPERTY.to_string(), PEER_CONNECTED_EVENT.to_string());
+        assert_eq!(data.event_name(), None);
+    }
+    
     #[test]
     fn test_message_data_conversion() {
         let msg = Message::sent(
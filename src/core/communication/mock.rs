@@ -0,0 +1,768 @@
+use crate::core::communication::{
+    message::{Message, DISCONNECTED_EVENT},
+    transport::{ControlLines, ModemStatus, SessionInfo, SessionStatus, Transport, TransportType},
+};
+use crate::domain::{config::{ConnectionConfig, DeviceConfig}, error::{TermComError, TermComResult}};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Name the mock transport is registered under
+pub const MOCK_TRANSPORT: &str = "mock";
+
+/// Reply sent whenever sent data contains `request`
+#[derive(Debug, Clone)]
+struct ResponseRule {
+    request: Vec<u8>,
+    response: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct MockSession {
+    device_name: String,
+    status: SessionStatus,
+    created_at: std::time::SystemTime,
+    last_activity: std::time::SystemTime,
+    sent: Vec<Vec<u8>>,
+    dtr: bool,
+    rts: bool,
+    breaks: Vec<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    sessions: HashMap<String, MockSession>,
+    rules: Vec<ResponseRule>,
+    queued_responses: VecDeque<Vec<u8>>,
+    latency: Duration,
+    connect_failure: Option<String>,
+    send_failure: Option<String>,
+    modem_status: ModemStatus,
+}
+
+impl MockState {
+    fn session_id(&self, device_name: &str) -> Option<String> {
+        self.sessions
+            .iter()
+            .find(|(_, session)| session.device_name == device_name)
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Replies for sent data: every matching rule, or else the next queued response
+    fn responses_to(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        let matched: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|rule| contains(data, &rule.request))
+            .map(|rule| rule.response.clone())
+            .collect();
+
+        if matched.is_empty() {
+            self.queued_responses.pop_front().into_iter().collect()
+        } else {
+            matched
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// In-memory transport for testing code built on termcom without hardware
+///
+/// Register it with [`CommunicationEngineBuilder::transport`] and connect
+/// devices created with [`MockTransport::device`]. The transport is scripted
+/// and inspected through a [`MockHandle`], which stays usable after the
+/// transport has been moved into the engine.
+///
+/// [`CommunicationEngineBuilder::transport`]: crate::core::communication::CommunicationEngineBuilder::transport
+pub struct MockTransport {
+    handle: MockHandle,
+    receiver: tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>,
+    max_sessions: usize,
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        Self {
+            handle: MockHandle {
+                state: Arc::new(Mutex::new(MockState::default())),
+                sender,
+            },
+            receiver: tokio::sync::Mutex::new(receiver),
+            max_sessions: 10,
+        }
+    }
+
+    /// Limit the number of simultaneous sessions
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    /// Get a handle for scripting the transport
+    pub fn handle(&self) -> MockHandle {
+        self.handle.clone()
+    }
+
+    /// Device profile connected through the mock transport
+    pub fn device(name: &str) -> DeviceConfig {
+        DeviceConfig {
+            description: "Mock device".to_string(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Custom {
+                    transport: MOCK_TRANSPORT.to_string(),
+                    options: toml::Table::new(),
+                },
+            )
+        }
+    }
+
+    fn session_info(id: &str, session: &MockSession) -> SessionInfo {
+        let bytes_sent = session.sent.iter().map(|data| data.len() as u64).sum();
+
+        SessionInfo {
+            id: id.to_string(),
+            device_name: session.device_name.clone(),
+            transport_type: TransportType::Custom(MOCK_TRANSPORT.to_string()),
+            status: session.status.clone(),
+            created_at: session.created_at,
+            last_activity: session.last_activity,
+            bytes_sent,
+            bytes_received: 0,
+            messages_sent: session.sent.len() as u64,
+            messages_received: 0,
+            dropped_messages: 0,
+        }
+    }
+}
+
+/// Scripts a [`MockTransport`] and inspects what was sent through it
+///
+/// Devices are addressed by name. Responses are delivered after the
+/// configured latency as received data of the session that sent the
+/// triggering data.
+#[derive(Clone)]
+pub struct MockHandle {
+    state: Arc<Mutex<MockState>>,
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+impl MockHandle {
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Reply with `response` whenever sent data contains `request`
+    pub fn respond(&self, request: impl Into<Vec<u8>>, response: impl Into<Vec<u8>>) {
+        self.state().rules.push(ResponseRule {
+            request: request.into(),
+            response: response.into(),
+        });
+    }
+
+    /// Reply with `response` to the next send that no rule answers
+    pub fn queue_response(&self, response: impl Into<Vec<u8>>) {
+        self.state().queued_responses.push_back(response.into());
+    }
+
+    /// Delay every response and injected message by `latency`
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Make the next session that connects or reconnects fail with `message`
+    pub fn fail_next_connect(&self, message: impl Into<String>) {
+        self.state().connect_failure = Some(message.into());
+    }
+
+    /// Make the next send fail with `message`
+    pub fn fail_next_send(&self, message: impl Into<String>) {
+        self.state().send_failure = Some(message.into());
+    }
+
+    /// Deliver data from a device as if it had been received
+    pub fn inject(&self, device_name: &str, data: impl Into<Vec<u8>>) -> TermComResult<()> {
+        let session_id = self.find_session(device_name)?;
+        self.deliver(Message::received(session_id, device_name.to_string(), data.into(), MOCK_TRANSPORT.to_string()));
+        Ok(())
+    }
+
+    /// Report a transport error on a device's session
+    pub fn inject_error(&self, device_name: &str, error: impl Into<String>) -> TermComResult<()> {
+        let session_id = self.find_session(device_name)?;
+        self.deliver(Message::error(session_id, device_name.to_string(), error.into(), MOCK_TRANSPORT.to_string()));
+        Ok(())
+    }
+
+    /// Drop a device's connection; later sends to it fail
+    pub fn disconnect(&self, device_name: &str) -> TermComResult<()> {
+        let session_id = {
+            let mut state = self.state();
+            let session_id = state.session_id(device_name).ok_or_else(|| not_connected(device_name))?;
+            if let Some(session) = state.sessions.get_mut(&session_id) {
+                session.status = SessionStatus::Disconnected;
+            }
+            session_id
+        };
+
+        self.deliver(Message::event(
+            session_id,
+            device_name.to_string(),
+            DISCONNECTED_EVENT,
+            format!("Mock device '{}' disconnected", device_name),
+            MOCK_TRANSPORT.to_string(),
+        ));
+        Ok(())
+    }
+
+    /// Report `status` as the modem status lines of every device
+    pub fn set_modem_status(&self, status: ModemStatus) {
+        self.state().modem_status = status;
+    }
+
+    /// Current DTR and RTS levels of a device; both start asserted
+    pub fn control_lines(&self, device_name: &str) -> Option<ControlLines> {
+        let state = self.state();
+        state
+            .session_id(device_name)
+            .and_then(|id| state.sessions.get(&id))
+            .map(|session| ControlLines { dtr: Some(session.dtr), rts: Some(session.rts) })
+    }
+
+    /// Durations of the breaks sent to a device
+    pub fn breaks(&self, device_name: &str) -> Vec<Duration> {
+        let state = self.state();
+        state
+            .session_id(device_name)
+            .and_then(|id| state.sessions.get(&id))
+            .map(|session| session.breaks.clone())
+            .unwrap_or_default()
+    }
+
+    /// Everything sent to a device, one entry per send
+    pub fn sent(&self, device_name: &str) -> Vec<Vec<u8>> {
+        let state = self.state();
+        state
+            .session_id(device_name)
+            .and_then(|id| state.sessions.get(&id))
+            .map(|session| session.sent.clone())
+            .unwrap_or_default()
+    }
+
+    /// Everything sent to a device, concatenated
+    pub fn sent_bytes(&self, device_name: &str) -> Vec<u8> {
+        self.sent(device_name).concat()
+    }
+
+    /// Panic unless exactly `expected` has been sent to a device
+    #[track_caller]
+    pub fn assert_sent(&self, device_name: &str, expected: impl AsRef<[u8]>) {
+        let sent = self.sent_bytes(device_name);
+        let expected = expected.as_ref();
+        assert!(
+            sent == expected,
+            "Data sent to mock device '{}' differs\n  expected: {:?}\n    actual: {:?}",
+            device_name,
+            String::from_utf8_lossy(expected),
+            String::from_utf8_lossy(&sent),
+        );
+    }
+
+    /// Forget what has been sent to every device
+    pub fn clear_sent(&self) {
+        for session in self.state().sessions.values_mut() {
+            session.sent.clear();
+        }
+    }
+
+    /// Check whether a device has a connected session
+    pub fn is_connected(&self, device_name: &str) -> bool {
+        let state = self.state();
+        state
+            .session_id(device_name)
+            .and_then(|id| state.sessions.get(&id))
+            .is_some_and(|session| matches!(session.status, SessionStatus::Connected))
+    }
+
+    fn find_session(&self, device_name: &str) -> TermComResult<String> {
+        self.state().session_id(device_name).ok_or_else(|| not_connected(device_name))
+    }
+
+    fn deliver(&self, message: Message) {
+        let latency = self.state().latency;
+        if latency.is_zero() {
+            let _ = self.sender.send(message);
+            return;
+        }
+
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(latency).await;
+            let _ = sender.send(message);
+        });
+    }
+}
+
+fn not_connected(device_name: &str) -> TermComError {
+    TermComError::Communication {
+        message: format!("Mock device '{}' is not connected", device_name),
+    }
+}
+
+fn connected_session<'a>(state: &'a mut MockState, session_id: &str) -> TermComResult<&'a mut MockSession> {
+    let session = state.sessions.get_mut(session_id).ok_or_else(|| TermComError::Communication {
+        message: format!("Session '{}' not found", session_id),
+    })?;
+    if !matches!(session.status, SessionStatus::Connected) {
+        return Err(not_connected(&session.device_name));
+    }
+    Ok(session)
+}
+
+#[async_trait::async_trait]
+impl Transport for MockTransport {
+    fn transport_type(&self) -> TransportType {
+        TransportType::Custom(MOCK_TRANSPORT.to_string())
+    }
+
+    async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<String> {
+        let mut state = self.handle.state();
+
+        if let Some(message) = state.connect_failure.take() {
+            return Err(TermComError::Communication { message });
+        }
+        if state.sessions.len() >= self.max_sessions {
+            return Err(TermComError::Communication {
+                message: format!("Maximum number of sessions ({}) reached", self.max_sessions),
+            });
+        }
+        if state.session_id(&device_config.name).is_some() {
+            return Err(TermComError::Communication {
+                message: format!("Device '{}' is already connected", device_config.name),
+            });
+        }
+
+        let session_id = format!("mock_{}", uuid::Uuid::new_v4().simple());
+        let now = std::time::SystemTime::now();
+        state.sessions.insert(session_id.clone(), MockSession {
+            device_name: device_config.name.clone(),
+            status: SessionStatus::Connected,
+            created_at: now,
+            last_activity: now,
+            sent: Vec::new(),
+            dtr: true,
+            rts: true,
+            breaks: Vec::new(),
+        });
+
+        debug!("Created mock session '{}' for device '{}'", session_id, device_config.name);
+        Ok(session_id)
+    }
+
+    async fn close_session(&self, session_id: &str) -> TermComResult<()> {
+        self.handle.state().sessions.remove(session_id).map(|_| ()).ok_or_else(|| TermComError::Communication {
+            message: format!("Session '{}' not found", session_id),
+        })
+    }
+
+    async fn send_data(&self, session_id: &str, data: Vec<u8>) -> TermComResult<()> {
+        let (device_name, responses) = {
+            let mut state = self.handle.state();
+
+            if let Some(message) = state.send_failure.take() {
+                return Err(TermComError::Communication { message });
+            }
+
+            let session = connected_session(&mut state, session_id)?;
+            session.sent.push(data.clone());
+            session.last_activity = std::time::SystemTime::now();
+
+            let device_name = session.device_name.clone();
+            (device_name, state.responses_to(&data))
+        };
+
+        for response in responses {
+            self.handle.deliver(Message::received(
+                session_id.to_string(),
+                device_name.clone(),
+                response,
+                MOCK_TRANSPORT.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn send_command(&self, session_id: &str, command: &str) -> TermComResult<()> {
+        self.send_data(session_id, command.as_bytes().to_vec()).await
+    }
+
+    async fn set_control_lines(&self, session_id: &str, lines: ControlLines) -> TermComResult<()> {
+        let mut state = self.handle.state();
+        let session = connected_session(&mut state, session_id)?;
+        session.dtr = lines.dtr.unwrap_or(session.dtr);
+        session.rts = lines.rts.unwrap_or(session.rts);
+        Ok(())
+    }
+
+    async fn modem_status(&self, session_id: &str) -> TermComResult<ModemStatus> {
+        let mut state = self.handle.state();
+        connected_session(&mut state, session_id)?;
+        Ok(state.modem_status)
+    }
+
+    async fn send_break(&self, session_id: &str, duration: Duration) -> TermComResult<()> {
+        {
+            let mut state = self.handle.state();
+            connected_session(&mut state, session_id)?.breaks.push(duration);
+        }
+        tokio::time::sleep(duration).await;
+        Ok(())
+    }
+
+    async fn reconnect(&self, session_id: &str) -> TermComResult<()> {
+        let mut state = self.handle.state();
+        if let Some(message) = state.connect_failure.take() {
+            return Err(TermComError::Communication { message });
+        }
+
+        let session = state.sessions.get_mut(session_id).ok_or_else(|| TermComError::Communication {
+            message: format!("Session '{}' not found", session_id),
+        })?;
+        session.status = SessionStatus::Connected;
+        session.last_activity = std::time::SystemTime::now();
+        Ok(())
+    }
+
+    async fn receive_message(&self) -> Option<Message> {
+        self.receiver.lock().await.recv().await
+    }
+
+    async fn is_session_connected(&self, session_id: &str) -> bool {
+        self.handle.state().sessions.contains_key(session_id)
+    }
+
+    async fn get_session_info(&self, session_id: &str) -> Option<SessionInfo> {
+        let state = self.handle.state();
+        state.sessions.get(session_id).map(|session| Self::session_info(session_id, session))
+    }
+
+    async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let state = self.handle.state();
+        state.sessions.iter().map(|(id, session)| Self::session_info(id, session)).collect()
+    }
+
+    async fn close_all_sessions(&self) -> TermComResult<()> {
+        self.handle.state().sessions.clear();
+        Ok(())
+    }
+
+    async fn get_session_count(&self) -> usize {
+        self.handle.state().sessions.len()
+    }
+
+    fn get_max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::communication::{message::MessageType, CommunicationEngine};
+    use crate::core::session::{state::ActivityType, SessionConfig, SessionManager, SessionStatus as State};
+    use crate::domain::config::{ResetSequence, ResetStep};
+    use std::sync::Arc;
+
+    async fn mock_session_manager() -> (SessionManager, MockHandle) {
+        let transport = MockTransport::new();
+        let handle = transport.handle();
+        let engine = Arc::new(CommunicationEngine::builder().without_builtin_transports().transport(transport).build());
+        engine.start().await.unwrap();
+        (SessionManager::new(engine, 10), handle)
+    }
+
+    async fn start_device(manager: &SessionManager, name: &str) -> String {
+        let config = SessionConfig::from_device(name.to_string(), crate::core::session::SessionType::Automated, MockTransport::device(name));
+        let session_id = manager.create_session(config).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+        session_id
+    }
+
+    async fn received(manager: &SessionManager, session_id: &str, expected_len: usize) -> Vec<u8> {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let data: Vec<u8> = manager.get_message_history(session_id).await.unwrap()
+                    .into_iter()
+                    .filter(|message| matches!(message.message_type, MessageType::Received))
+                    .flat_map(|message| message.data)
+                    .collect();
+                if data.len() >= expected_len {
+                    return data;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_canned_responses_and_sent_assertions() {
+        let (manager, mock) = mock_session_manager().await;
+        mock.respond("AT+GMR", "1.2.3\r\nOK\r\n");
+        mock.queue_response("READY\r\n");
+        let session_id = start_device(&manager, "modem").await;
+
+        manager.send_data(&session_id, b"ATZ\r".to_vec()).await.unwrap();
+        manager.send_data(&session_id, b"AT+GMR\r".to_vec()).await.unwrap();
+        manager.send_data(&session_id, b"AT\r".to_vec()).await.unwrap();
+
+        assert_eq!(received(&manager, &session_id, 17).await, b"READY\r\n1.2.3\r\nOK\r\n");
+        assert_eq!(mock.sent("modem"), vec![b"ATZ\r".to_vec(), b"AT+GMR\r".to_vec(), b"AT\r".to_vec()]);
+        mock.assert_sent("modem", "ATZ\rAT+GMR\rAT\r");
+
+        mock.clear_sent();
+        assert!(mock.sent("modem").is_empty());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Data sent to mock device 'modem' differs")]
+    async fn test_assert_sent_reports_mismatch() {
+        let (manager, mock) = mock_session_manager().await;
+        let session_id = start_device(&manager, "modem").await;
+        manager.send_data(&session_id, b"AT\r".to_vec()).await.unwrap();
+
+        mock.assert_sent("modem", "ATI\r");
+    }
+
+    #[tokio::test]
+    async fn test_injected_failures() {
+        let (manager, mock) = mock_session_manager().await;
+
+        mock.fail_next_connect("port busy");
+        let config = SessionConfig::from_device("flaky".to_string(), crate::core::session::SessionType::Automated, MockTransport::device("flaky"));
+        let flaky = manager.create_session(config).await.unwrap();
+        assert!(manager.start_session(&flaky).await.unwrap_err().to_string().contains("port busy"));
+
+        let session_id = start_device(&manager, "sensor").await;
+        mock.fail_next_send("write timeout");
+        assert!(manager.send_data(&session_id, b"x".to_vec()).await.is_err());
+        manager.send_data(&session_id, b"y".to_vec()).await.unwrap();
+        mock.assert_sent("sensor", "y");
+
+        mock.inject_error("sensor", "framing error").unwrap();
+        mock.disconnect("sensor").unwrap();
+        assert!(!mock.is_connected("sensor"));
+
+        let state = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let state = manager.get_session_state(&session_id).await.unwrap();
+                if state.status == State::Disconnected {
+                    return state;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(state.statistics.error_count >= 1);
+
+        let activities = manager.get_activity_history(&session_id).await.unwrap();
+        assert!(activities.iter().any(|activity| matches!(activity.activity_type, ActivityType::ConnectionLost)));
+        assert!(manager.send_data(&session_id, b"z".to_vec()).await.is_err());
+        assert!(mock.inject("nobody", "x").is_err());
+    }
+
+    async fn wait_for_activity(manager: &SessionManager, session_id: &str, description: &str) -> Vec<crate::core::session::state::SessionActivity> {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let activities = manager.get_activity_history(session_id).await.unwrap();
+                if activities.iter().any(|activity| activity.description.contains(description)) {
+                    return activities;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_auto_reconnect_keeps_history() {
+        let (manager, mock) = mock_session_manager().await;
+        let mut device = MockTransport::device("usb-board");
+        device.auto_reconnect = true;
+        let mut config = SessionConfig::from_device("usb-board".to_string(), crate::core::session::SessionType::Automated, device);
+        config.reconnect_delay_ms = 10;
+        let session_id = manager.create_session(config).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+
+        mock.inject("usb-board", "before unplug\r\n").unwrap();
+        received(&manager, &session_id, 15).await;
+
+        mock.fail_next_connect("No serial port matches usb:0403:6001");
+        mock.disconnect("usb-board").unwrap();
+        let activities = wait_for_activity(&manager, &session_id, "re-established after 2 attempt(s)").await;
+
+        let attempts: Vec<_> = activities
+            .iter()
+            .filter(|activity| activity.activity_type == ActivityType::ReconnectAttempt)
+            .map(|activity| activity.description.as_str())
+            .collect();
+        assert_eq!(attempts, vec![
+            "Reconnect attempt 1 of 3 failed: Communication error: No serial port matches usb:0403:6001",
+            "Reconnect attempt 2 of 3 succeeded",
+        ]);
+        assert!(mock.is_connected("usb-board"));
+        assert_eq!(manager.get_session_state(&session_id).await.unwrap().status, State::Active);
+
+        // The history carries on across the reconnect
+        mock.inject("usb-board", "after\r\n").unwrap();
+        assert_eq!(received(&manager, &session_id, 22).await, b"before unplug\r\nafter\r\n");
+        manager.send_data(&session_id, b"AT\r".to_vec()).await.unwrap();
+        mock.assert_sent("usb-board", "AT\r");
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up_after_max_attempts() {
+        let (manager, mock) = mock_session_manager().await;
+        let mut config = SessionConfig::from_device("board".to_string(), crate::core::session::SessionType::Automated, MockTransport::device("board"));
+        config.auto_reconnect = true;
+        config.max_reconnect_attempts = 1;
+        config.reconnect_delay_ms = 10;
+        let session_id = manager.create_session(config).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+
+        mock.fail_next_connect("unplugged");
+        mock.disconnect("board").unwrap();
+        let activities = wait_for_activity(&manager, &session_id, "Gave up reconnecting after 1 attempts").await;
+
+        assert_eq!(activities.iter().filter(|activity| activity.activity_type == ActivityType::ReconnectAttempt).count(), 1);
+        assert_eq!(manager.get_session_state(&session_id).await.unwrap().status, State::Disconnected);
+        assert!(!mock.is_connected("board"));
+    }
+
+    #[tokio::test]
+    async fn test_control_lines_and_breaks() {
+        let (manager, mock) = mock_session_manager().await;
+        mock.set_modem_status(ModemStatus { cts: true, dsr: true, ri: false, cd: false });
+        let session_id = start_device(&manager, "esp32").await;
+
+        // Hold GPIO0 low while pulsing EN to enter the bootloader
+        manager.set_control_lines(&session_id, ControlLines { dtr: Some(false), rts: Some(true) }).await.unwrap();
+        manager.set_control_lines(&session_id, ControlLines::rts(false)).await.unwrap();
+        manager.send_break(&session_id, Duration::from_millis(10)).await.unwrap();
+
+        assert_eq!(mock.control_lines("esp32"), Some(ControlLines { dtr: Some(false), rts: Some(false) }));
+        assert_eq!(mock.breaks("esp32"), vec![Duration::from_millis(10)]);
+        assert!(manager.modem_status(&session_id).await.unwrap().cts);
+
+        // Activities are recorded asynchronously
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let changes: Vec<_> = manager.get_activity_history(&session_id).await.unwrap()
+            .into_iter()
+            .filter(|activity| activity.activity_type == ActivityType::SignalChanged)
+            .map(|activity| activity.description)
+            .collect();
+        assert_eq!(changes, vec!["Set DTR off, RTS on", "Set RTS off", "Sent break for 10 ms"]);
+    }
+
+    #[tokio::test]
+    async fn test_reset_sequence_captures_boot_log() {
+        let (manager, mock) = mock_session_manager().await;
+        let mut device = MockTransport::device("esp32");
+        let step = |dtr, rts, delay_ms| ResetStep { dtr, rts, delay_ms };
+        device.reset_sequences = vec![
+            ResetSequence {
+                name: "quick".to_string(),
+                description: String::new(),
+                steps: vec![step(Some(false), Some(true), 10), step(None, Some(false), 0)],
+                until: Some("ready".to_string()),
+                timeout_ms: 2000,
+            },
+            ResetSequence {
+                name: "silent".to_string(),
+                description: String::new(),
+                steps: vec![step(Some(true), None, 0)],
+                until: Some("ready".to_string()),
+                timeout_ms: 100,
+            },
+        ];
+        let config = SessionConfig::from_device("esp32".to_string(), crate::core::session::SessionType::Automated, device);
+        let session_id = manager.create_session(config).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+
+        // The pattern is split across two reads
+        let boot = mock.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            boot.inject("esp32", "rst:0x1 boot: rea").unwrap();
+            boot.inject("esp32", "dy\r\n").unwrap();
+        });
+
+        let log = manager.run_reset_sequence(&session_id, "quick").await.unwrap();
+        assert!(log.matched);
+        assert_eq!(log.text(), "rst:0x1 boot: ready\r\n");
+        assert_eq!(log.device_name, "esp32");
+        assert!(log.duration < Duration::from_secs(2));
+        assert_eq!(mock.control_lines("esp32"), Some(ControlLines { dtr: Some(false), rts: Some(false) }));
+
+        // Other sessions can be created while a capture is running
+        let other = SessionConfig::from_device(
+            "other".to_string(),
+            crate::core::session::SessionType::Automated,
+            MockTransport::device("other"),
+        );
+        let (log, created) = tokio::join!(
+            manager.run_reset_sequence(&session_id, "silent"),
+            tokio::time::timeout(Duration::from_millis(50), manager.create_session(other)),
+        );
+        assert!(created.unwrap().is_ok());
+        let log = log.unwrap();
+        assert!(!log.matched);
+        assert!(log.output.is_empty());
+        assert!(log.duration >= Duration::from_millis(100));
+
+        let error = manager.run_reset_sequence(&session_id, "jtag").await.unwrap_err().to_string();
+        assert!(error.contains("quick, silent, esp32-bootloader"), "{}", error);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let resets: Vec<_> = manager.get_activity_history(&session_id).await.unwrap()
+            .into_iter()
+            .filter(|activity| activity.activity_type == ActivityType::Custom("reset_sequence".to_string()))
+            .map(|activity| activity.description)
+            .collect();
+        assert_eq!(resets, vec![
+            "Ran 'quick': captured 21 bytes, found 'ready'",
+            "Ran 'silent': captured 0 bytes, did not find 'ready'",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_latency_delays_responses() {
+        let (manager, mock) = mock_session_manager().await;
+        mock.set_latency(Duration::from_millis(100));
+        mock.respond("PING", "PONG");
+        let session_id = start_device(&manager, "slow").await;
+
+        let started = std::time::Instant::now();
+        manager.send_data(&session_id, b"PING".to_vec()).await.unwrap();
+        mock.inject("slow", "!").unwrap();
+
+        assert_eq!(received(&manager, &session_id, 5).await, b"PONG!");
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -1,8 +1,12 @@
 // Communication module - Communication engine abstraction
 pub mod engine;
+pub mod framing;
 pub mod message;
+pub mod mock;
 pub mod transport;
 
-pub use engine::CommunicationEngine;
+pub use engine::{CommunicationEngine, CommunicationEngineBuilder};
+pub use framing::Framer;
 pub use message::{Message, MessagePattern};
-pub use transport::TransportType;
\ No newline at end of file
+pub use mock::{MockHandle, MockTransport};
+pub use transport::{ControlLines, ModemStatus, Transport, TransportType};
\ No newline at end of file
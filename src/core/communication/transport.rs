@@ -1,13 +1,56 @@
-use crate::domain::{config::DeviceConfig, error::TermComResult};
+use crate::domain::{config::{ConnectionConfig, DeviceConfig}, error::{TermComError, TermComResult}};
 use crate::core::communication::message::Message;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Transport type enumeration
+///
+/// Built-in transports have their own variants; transports registered by
+/// library users are identified by name through `Custom`. Transports are
+/// registered and looked up by the name `Display` gives.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TransportType {
     Serial,
     Tcp,
+    TcpServer,
+    Udp,
+    Pty,
+    Process,
+    Unix,
+    Custom(String),
+}
+
+impl TransportType {
+    /// Get the transport type that handles a connection configuration
+    pub fn for_connection(connection: &ConnectionConfig) -> Self {
+        match connection {
+            ConnectionConfig::Serial { .. } => TransportType::Serial,
+            ConnectionConfig::Tcp { .. } => TransportType::Tcp,
+            ConnectionConfig::TcpServer { .. } => TransportType::TcpServer,
+            ConnectionConfig::Udp { .. } => TransportType::Udp,
+            ConnectionConfig::Pty { .. } => TransportType::Pty,
+            ConnectionConfig::Process { .. } => TransportType::Process,
+            ConnectionConfig::Unix { .. } => TransportType::Unix,
+            ConnectionConfig::Custom { transport, .. } => TransportType::from_name(transport),
+        }
+    }
+    
+    /// Get the transport type registered under a name
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "serial" => TransportType::Serial,
+            "tcp" => TransportType::Tcp,
+            "tcp_server" => TransportType::TcpServer,
+            "udp" => TransportType::Udp,
+            "pty" => TransportType::Pty,
+            "process" => TransportType::Process,
+            "unix" => TransportType::Unix,
+            _ => TransportType::Custom(name.to_string()),
+        }
+    }
 }
 
 impl std::fmt::Display for TransportType {
@@ -15,6 +58,12 @@ impl std::fmt::Display for TransportType {
         match self {
             TransportType::Serial => write!(f, "serial"),
             TransportType::Tcp => write!(f, "tcp"),
+            TransportType::TcpServer => write!(f, "tcp_server"),
+            TransportType::Udp => write!(f, "udp"),
+            TransportType::Pty => write!(f, "pty"),
+            TransportType::Process => write!(f, "process"),
+            TransportType::Unix => write!(f, "unix"),
+            TransportType::Custom(name) => write!(f, "{}", name),
         }
     }
 }
@@ -37,8 +86,67 @@ pub trait Transport: Send + Sync {
     /// Send a command to a session
     async fn send_command(&self, session_id: &str, command: &str) -> TermComResult<()>;
     
-    /// Receive the next message from any session
-    async fn receive_message(&mut self) -> Option<Message>;
+    /// Send data to a single peer of a session that serves several peers
+    async fn send_to_peer(&self, session_id: &str, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        let _ = (session_id, peer, data);
+        Err(TermComError::Communication {
+            message: format!("The {} transport does not address individual peers", self.transport_type()),
+        })
+    }
+    
+    /// List the peers connected to a session
+    async fn list_peers(&self, session_id: &str) -> Vec<String> {
+        let _ = session_id;
+        Vec::new()
+    }
+    
+    /// Drive the output control lines of a session's port
+    async fn set_control_lines(&self, session_id: &str, lines: ControlLines) -> TermComResult<()> {
+        let _ = (session_id, lines);
+        Err(TermComError::Communication {
+            message: format!("The {} transport has no control lines", self.transport_type()),
+        })
+    }
+    
+    /// Read the modem status lines of a session's port
+    async fn modem_status(&self, session_id: &str) -> TermComResult<ModemStatus> {
+        let _ = session_id;
+        Err(TermComError::Communication {
+            message: format!("The {} transport has no modem status lines", self.transport_type()),
+        })
+    }
+    
+    /// Hold a session's line in the break condition for `duration`
+    async fn send_break(&self, session_id: &str, duration: Duration) -> TermComResult<()> {
+        let _ = (session_id, duration);
+        Err(TermComError::Communication {
+            message: format!("The {} transport cannot send a break", self.transport_type()),
+        })
+    }
+    
+    /// Apply the line settings of `connection` to a session's open port
+    async fn reconfigure(&self, session_id: &str, connection: &ConnectionConfig) -> TermComResult<()> {
+        let _ = (session_id, connection);
+        Err(TermComError::Communication {
+            message: format!("The {} transport cannot change line settings", self.transport_type()),
+        })
+    }
+    
+    /// Reopen a session whose connection was lost, keeping its ID
+    async fn reconnect(&self, session_id: &str) -> TermComResult<()> {
+        let _ = session_id;
+        Err(TermComError::Communication {
+            message: format!("The {} transport cannot reconnect", self.transport_type()),
+        })
+    }
+    
+    /// Receive the next message read from any session
+    ///
+    /// Data received from devices is returned as `Received` messages and
+    /// connection events as `System` messages carrying an event property;
+    /// the engine records sent data itself. Returns `None` once the
+    /// transport can no longer deliver messages.
+    async fn receive_message(&self) -> Option<Message>;
     
     /// Check if a session is connected
     async fn is_session_connected(&self, session_id: &str) -> bool;
@@ -59,6 +167,79 @@ pub trait Transport: Send + Sync {
     fn get_max_sessions(&self) -> usize;
 }
 
+/// Requested state of the DTR and RTS output lines
+///
+/// Lines left as `None` keep their current state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlLines {
+    /// Data Terminal Ready
+    pub dtr: Option<bool>,
+    /// Request To Send
+    pub rts: Option<bool>,
+}
+
+impl ControlLines {
+    /// Set DTR and leave RTS unchanged
+    pub fn dtr(level: bool) -> Self {
+        Self { dtr: Some(level), rts: None }
+    }
+    
+    /// Set RTS and leave DTR unchanged
+    pub fn rts(level: bool) -> Self {
+        Self { dtr: None, rts: Some(level) }
+    }
+    
+    /// Check whether no line is changed
+    pub fn is_empty(&self) -> bool {
+        self.dtr.is_none() && self.rts.is_none()
+    }
+}
+
+impl std::fmt::Display for ControlLines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<_> = [("DTR", self.dtr), ("RTS", self.rts)]
+            .into_iter()
+            .filter_map(|(name, level)| level.map(|level| format!("{} {}", name, on_off(level))))
+            .collect();
+        
+        if lines.is_empty() {
+            write!(f, "no lines")
+        } else {
+            write!(f, "{}", lines.join(", "))
+        }
+    }
+}
+
+/// State of the modem status input lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModemStatus {
+    /// Clear To Send
+    pub cts: bool,
+    /// Data Set Ready
+    pub dsr: bool,
+    /// Ring Indicator
+    pub ri: bool,
+    /// Carrier Detect
+    pub cd: bool,
+}
+
+impl std::fmt::Display for ModemStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CTS {}, DSR {}, RI {}, CD {}",
+            on_off(self.cts),
+            on_off(self.dsr),
+            on_off(self.ri),
+            on_off(self.cd)
+        )
+    }
+}
+
+fn on_off(level: bool) -> &'static str {
+    if level { "on" } else { "off" }
+}
+
 /// Session information structure
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
@@ -72,6 +253,8 @@ pub struct SessionInfo {
     pub bytes_received: u64,
     pub messages_sent: u64,
     pub messages_received: u64,
+    /// Messages the transport discarded because they were not received in time
+    pub dropped_messages: u64,
 }
 
 /// Session status enumeration
@@ -97,8 +280,11 @@ impl std::fmt::Display for SessionStatus {
 }
 
 /// Transport registry for managing multiple transport types
+///
+/// Transports are keyed by name, so registering a transport under the name
+/// of an existing one replaces it.
 pub struct TransportRegistry {
-    transports: HashMap<TransportType, Box<dyn Transport>>,
+    transports: HashMap<String, Arc<dyn Transport>>,
 }
 
 impl TransportRegistry {
@@ -109,48 +295,64 @@ impl TransportRegistry {
         }
     }
     
-    /// Register a transport
-    pub fn register_transport(&mut self, transport: Box<dyn Transport>) {
-        let transport_type = transport.transport_type();
-        self.transports.insert(transport_type, transport);
+    /// Register a transport under the name of its transport type
+    ///
+    /// Returns the transport previously registered under that name, if any.
+    pub fn register_transport(&mut self, transport: Box<dyn Transport>) -> Option<Arc<dyn Transport>> {
+        let name = transport.transport_type().to_string();
+        self.transports.insert(name, Arc::from(transport))
     }
     
     /// Get a transport by type
-    pub fn get_transport(&mut self, transport_type: &TransportType) -> Option<&mut Box<dyn Transport>> {
-        self.transports.get_mut(transport_type)
+    pub fn get_transport(&self, transport_type: &TransportType) -> Option<&Arc<dyn Transport>> {
+        self.get_transport_by_name(&transport_type.to_string())
+    }
+    
+    /// Get a transport by the name it was registered under
+    pub fn get_transport_by_name(&self, name: &str) -> Option<&Arc<dyn Transport>> {
+        self.transports.get(name)
+    }
+    
+    /// Get shared handles to all registered transports
+    pub fn transports(&self) -> Vec<Arc<dyn Transport>> {
+        self.transports.values().cloned().collect()
     }
     
     /// Get all available transport types
     pub fn available_transports(&self) -> Vec<TransportType> {
-        self.transports.keys().cloned().collect()
+        self.transports.keys().map(|name| TransportType::from_name(name)).collect()
+    }
+    
+    /// Get the names of all registered transports in sorted order
+    pub fn transport_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.transports.keys().cloned().collect();
+        names.sort();
+        names
     }
     
     /// Check if a transport type is available
     pub fn has_transport(&self, transport_type: &TransportType) -> bool {
-        self.transports.contains_key(transport_type)
+        self.transports.contains_key(&transport_type.to_string())
     }
     
     /// Create a session using the appropriate transport
-    pub async fn create_session(&mut self, device_config: &DeviceConfig) -> TermComResult<(String, TransportType)> {
-        let transport_type = match &device_config.connection {
-            crate::domain::config::ConnectionConfig::Serial { .. } => TransportType::Serial,
-            crate::domain::config::ConnectionConfig::Tcp { .. } => TransportType::Tcp,
-        };
+    pub async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<(String, TransportType)> {
+        let transport_type = TransportType::for_connection(&device_config.connection);
         
         if let Some(transport) = self.get_transport(&transport_type) {
             let session_id = transport.create_session(device_config).await?;
             Ok((session_id, transport_type))
         } else {
             Err(crate::domain::error::TermComError::Communication {
-                message: format!("Transport type {:?} not available", transport_type),
+                message: format!("Transport '{}' is not registered", transport_type),
             })
         }
     }
     
     /// Close a session by finding the appropriate transport
-    pub async fn close_session(&mut self, session_id: &str) -> TermComResult<()> {
+    pub async fn close_session(&self, session_id: &str) -> TermComResult<()> {
         // Try to find the session in all transports
-        for transport in self.transports.values_mut() {
+        for transport in self.transports.values() {
             if transport.is_session_connected(session_id).await {
                 return transport.close_session(session_id).await;
             }
@@ -162,9 +364,9 @@ impl TransportRegistry {
     }
     
     /// Send data to a session
-    pub async fn send_data(&mut self, session_id: &str, data: Vec<u8>) -> TermComResult<()> {
+    pub async fn send_data(&self, session_id: &str, data: Vec<u8>) -> TermComResult<()> {
         // Try to find the session in all transports
-        for transport in self.transports.values_mut() {
+        for transport in self.transports.values() {
             if transport.is_session_connected(session_id).await {
                 return transport.send_data(session_id, data).await;
             }
@@ -175,9 +377,69 @@ impl TransportRegistry {
         })
     }
     
+    /// Send data to a single peer of a session
+    pub async fn send_to_peer(&self, session_id: &str, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        for transport in self.transports.values() {
+            if transport.is_session_connected(session_id).await {
+                return transport.send_to_peer(session_id, peer, data).await;
+            }
+        }
+        
+        Err(TermComError::Communication {
+            message: format!("Session '{}' not found in any transport", session_id),
+        })
+    }
+    
+    /// List the peers connected to a session
+    pub async fn list_peers(&self, session_id: &str) -> Vec<String> {
+        for transport in self.transports.values() {
+            if transport.is_session_connected(session_id).await {
+                return transport.list_peers(session_id).await;
+            }
+        }
+        Vec::new()
+    }
+    
+    /// Drive the output control lines of a session's port
+    pub async fn set_control_lines(&self, session_id: &str, lines: ControlLines) -> TermComResult<()> {
+        self.transport_for(session_id).await?.set_control_lines(session_id, lines).await
+    }
+    
+    /// Read the modem status lines of a session's port
+    pub async fn modem_status(&self, session_id: &str) -> TermComResult<ModemStatus> {
+        self.transport_for(session_id).await?.modem_status(session_id).await
+    }
+    
+    /// Send a break condition on a session's line
+    pub async fn send_break(&self, session_id: &str, duration: Duration) -> TermComResult<()> {
+        self.transport_for(session_id).await?.send_break(session_id, duration).await
+    }
+    
+    /// Apply new line settings to a session's open port
+    pub async fn reconfigure(&self, session_id: &str, connection: &ConnectionConfig) -> TermComResult<()> {
+        self.transport_for(session_id).await?.reconfigure(session_id, connection).await
+    }
+    
+    /// Reopen a session whose connection was lost
+    pub async fn reconnect(&self, session_id: &str) -> TermComResult<()> {
+        self.transport_for(session_id).await?.reconnect(session_id).await
+    }
+    
+    async fn transport_for(&self, session_id: &str) -> TermComResult<&Arc<dyn Transport>> {
+        for transport in self.transports.values() {
+            if transport.is_session_connected(session_id).await {
+                return Ok(transport);
+            }
+        }
+        
+        Err(TermComError::Communication {
+            message: format!("Session '{}' not found in any transport", session_id),
+        })
+    }
+    
     /// Get session information from all transports
-    pub async fn get_session_info(&mut self, session_id: &str) -> Option<SessionInfo> {
-        for transport in self.transports.values_mut() {
+    pub async fn get_session_info(&self, session_id: &str) -> Option<SessionInfo> {
+        for transport in self.transports.values() {
             if let Some(info) = transport.get_session_info(session_id).await {
                 return Some(info);
             }
@@ -186,10 +448,10 @@ impl TransportRegistry {
     }
     
     /// List all sessions from all transports
-    pub async fn list_all_sessions(&mut self) -> Vec<SessionInfo> {
+    pub async fn list_all_sessions(&self) -> Vec<SessionInfo> {
         let mut all_sessions = Vec::new();
         
-        for transport in self.transports.values_mut() {
+        for transport in self.transports.values() {
             all_sessions.extend(transport.list_sessions().await);
         }
         
@@ -197,10 +459,10 @@ impl TransportRegistry {
     }
     
     /// Close all sessions in all transports
-    pub async fn close_all_sessions(&mut self) -> TermComResult<()> {
+    pub async fn close_all_sessions(&self) -> TermComResult<()> {
         let mut errors = Vec::new();
         
-        for transport in self.transports.values_mut() {
+        for transport in self.transports.values() {
             if let Err(e) = transport.close_all_sessions().await {
                 errors.push(e.to_string());
             }
@@ -264,7 +526,7 @@ mod tests {
             Ok(())
         }
         
-        async fn receive_message(&mut self) -> Option<Message> {
+        async fn receive_message(&self) -> Option<Message> {
             None
         }
         
@@ -284,6 +546,7 @@ mod tests {
                 bytes_received: 0,
                 messages_sent: 0,
                 messages_received: 0,
+                dropped_messages: 0,
             })
         }
         
@@ -308,6 +571,23 @@ mod tests {
     fn test_transport_type_display() {
         assert_eq!(TransportType::Serial.to_string(), "serial");
         assert_eq!(TransportType::Tcp.to_string(), "tcp");
+        assert_eq!(TransportType::Custom("can".to_string()).to_string(), "can");
+    }
+    
+    #[test]
+    fn test_transport_type_from_name() {
+        assert_eq!(TransportType::from_name("tcp_server"), TransportType::TcpServer);
+        assert_eq!(TransportType::from_name("can"), TransportType::Custom("can".to_string()));
+        
+        for transport_type in [TransportType::Serial, TransportType::Udp, TransportType::Unix] {
+            assert_eq!(TransportType::from_name(&transport_type.to_string()), transport_type);
+        }
+        
+        let connection = ConnectionConfig::Custom {
+            transport: "can".to_string(),
+            options: toml::Table::new(),
+        };
+        assert_eq!(TransportType::for_connection(&connection), TransportType::Custom("can".to_string()));
     }
     
     #[test]
@@ -318,7 +598,17 @@ mod tests {
         assert_eq!(SessionStatus::Connecting.to_string(), "Connecting");
         assert_eq!(SessionStatus::Closing.to_string(), "Closing");
     }
-    
+
+    #[test]
+    fn test_signal_line_display() {
+        assert_eq!(ControlLines { dtr: Some(false), rts: Some(true) }.to_string(), "DTR off, RTS on");
+        assert_eq!(ControlLines::rts(false).to_string(), "RTS off");
+        assert_eq!(ControlLines::default().to_string(), "no lines");
+
+        let status = ModemStatus { cts: true, dsr: false, ri: false, cd: true };
+        assert_eq!(status.to_string(), "CTS on, DSR off, RI off, CD on");
+    }
+
     #[tokio::test]
     async fn test_transport_registry() {
         let mut registry = TransportRegistry::new();
@@ -331,4 +621,34 @@ mod tests {
         assert!(registry.has_transport(&TransportType::Tcp));
         assert_eq!(registry.available_transports().len(), 2);
     }
+    
+    #[tokio::test]
+    async fn test_registry_routes_custom_transports_by_name() {
+        let mut registry = TransportRegistry::new();
+        let can = TransportType::Custom("can".to_string());
+        assert!(registry.register_transport(Box::new(MockTransport::new(can.clone()))).is_none());
+        assert!(registry.register_transport(Box::new(MockTransport::new(can.clone()))).is_some());
+        registry.register_transport(Box::new(MockTransport::new(TransportType::Serial)));
+        
+        assert_eq!(registry.transport_names(), vec!["can", "serial"]);
+        assert!(registry.get_transport_by_name("can").is_some());
+        
+        let mut device = DeviceConfig::new(
+            "bus",
+            ConnectionConfig::Custom {
+                transport: "can".to_string(),
+                options: toml::Table::new(),
+            },
+        );
+        let (session_id, transport_type) = registry.create_session(&device).await.unwrap();
+        assert!(session_id.starts_with("can_"));
+        assert_eq!(transport_type, can);
+        
+        device.connection = ConnectionConfig::Custom {
+            transport: "modbus".to_string(),
+            options: toml::Table::new(),
+        };
+        let error = registry.create_session(&device).await.unwrap_err();
+        assert!(error.to_string().contains("Transport 'modbus' is not registered"));
+    }
 }
\ No newline at end of file
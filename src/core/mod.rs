@@ -2,4 +2,5 @@
 pub mod communication;
 pub mod session;
 pub mod config;
-pub mod memory;
\ No newline at end of file
+pub mod memory;
+pub mod timestamp;
@@ -0,0 +1,78 @@
+use crate::core::timestamp;
+use crate::domain::error::{TermComError, TermComResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Output captured from a device after running a reset sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BootLog {
+    /// Device the sequence ran on
+    pub device_name: String,
+    /// Reset sequence name
+    pub sequence: String,
+    /// When the first step was applied
+    pub started_at: SystemTime,
+    /// Time from the first step to the end of the capture
+    pub duration: Duration,
+    /// Text the capture waited for
+    pub until: Option<String>,
+    /// Whether `until` showed up before the timeout
+    pub matched: bool,
+    /// Raw bytes received during the capture
+    pub output: Vec<u8>,
+}
+
+impl BootLog {
+    /// Write the captured output to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> TermComResult<()> {
+        let path = path.as_ref();
+        std::fs::write(path, &self.output)
+            .map_err(|e| TermComError::Output(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// File name such as `esp32-esp32-bootloader-2024-02-29T23-59-59.250Z.log`
+    pub fn default_file_name(&self) -> String {
+        let name = format!(
+            "{}-{}-{}.log",
+            self.device_name,
+            self.sequence,
+            timestamp::format_millis(self.started_at)
+        );
+
+        name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+            .collect()
+    }
+
+    /// Captured output as text, with invalid UTF-8 replaced
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.output).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_boot_log_file() {
+        let log = BootLog {
+            device_name: "lab/esp32".to_string(),
+            sequence: "esp32-bootloader".to_string(),
+            started_at: UNIX_EPOCH + Duration::from_millis(1_709_251_199_250),
+            duration: Duration::from_millis(420),
+            until: Some("waiting for download".to_string()),
+            matched: true,
+            output: b"rst:0x1\r\nwaiting for download\r\n".to_vec(),
+        };
+
+        assert_eq!(log.default_file_name(), "lab-esp32-esp32-bootloader-2024-02-29T23-59-59.250Z.log");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(log.default_file_name());
+        log.save(&path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), log.output);
+    }
+}
@@ -0,0 +1,1037 @@
+use crate::core::communication::{message::MessageType, Message};
+use crate::core::session::state::{ActivityType, SessionActivity, SessionState};
+use crate::core::timestamp;
+use crate::domain::error::{TermComError, TermComResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Version of the export layout written by this build
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Identifier written at the top of every export
+const EXPORT_FORMAT_NAME: &str = "termcom-session-export";
+
+/// Column header of CSV exports
+const CSV_HEADER: &str = "record,timestamp,direction,type,sequence,size,duration,id,transport,hex,text,details";
+
+/// Session export file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionExportFormat {
+    /// Single JSON document
+    Json,
+    /// One CSV row per state, message and activity record
+    Csv,
+    /// Plain text log with one line per message or activity
+    Log,
+}
+
+impl SessionExportFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "log" | "txt" => Some(Self::Log),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from exported content
+    pub fn detect(content: &str) -> Option<Self> {
+        let content = content.trim_start();
+        if content.starts_with('{') {
+            Some(Self::Json)
+        } else if content.starts_with(CSV_HEADER) {
+            Some(Self::Csv)
+        } else if content.starts_with(&format!("# {}", EXPORT_FORMAT_NAME)) {
+            Some(Self::Log)
+        } else {
+            None
+        }
+    }
+}
+
+/// Snapshot of a session for archiving and bug reports
+///
+/// An export holds the session state together with its message and
+/// activity history. Every format decodes back into an equal export.
+#[derive(Debug, Clone)]
+pub struct SessionExport {
+    /// Time the export was taken
+    pub exported_at: SystemTime,
+    /// Session state at export time
+    pub state: SessionState,
+    /// Message history, oldest first
+    pub messages: Vec<Message>,
+    /// Activity history, oldest first
+    pub activities: Vec<SessionActivity>,
+}
+
+/// JSON document layout
+#[derive(Serialize, Deserialize)]
+struct JsonExport {
+    format: String,
+    version: u32,
+    exported_at: String,
+    state: SessionState,
+    messages: Vec<MessageRecord>,
+    activities: Vec<ActivityRecord>,
+}
+
+/// Message as written to an export
+#[derive(Debug, Serialize, Deserialize)]
+struct MessageRecord {
+    id: String,
+    timestamp: String,
+    direction: String,
+    r#type: String,
+    sequence: u64,
+    size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<String>,
+    transport: String,
+    hex: String,
+    text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+}
+
+/// Activity as written to an export
+#[derive(Debug, Serialize, Deserialize)]
+struct ActivityRecord {
+    timestamp: String,
+    r#type: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<String>,
+}
+
+/// Message tags and properties stored in the CSV and log `details` field
+#[derive(Serialize, Deserialize)]
+struct MessageDetails {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+}
+
+/// Entry of the chronological CSV and log listing
+enum Entry<'a> {
+    Message(&'a Message),
+    Activity(&'a SessionActivity),
+}
+
+impl SessionExport {
+    /// Create an export of a session taken now
+    ///
+    /// Messages are attributed to the exported session, replacing the
+    /// transport-level session ID they were recorded with.
+    pub fn new(state: SessionState, messages: Vec<Message>, activities: Vec<SessionActivity>) -> Self {
+        let messages = messages
+            .into_iter()
+            .map(|mut message| {
+                message.session_id = state.session_id.clone();
+                message.device_name = state.device_name.clone();
+                message
+            })
+            .collect();
+
+        Self {
+            exported_at: SystemTime::now(),
+            state,
+            messages,
+            activities,
+        }
+    }
+
+    /// Encode the export in the given format
+    pub fn encode(&self, format: SessionExportFormat) -> TermComResult<String> {
+        match format {
+            SessionExportFormat::Json => self.encode_json(),
+            SessionExportFormat::Csv => self.encode_csv(),
+            SessionExportFormat::Log => self.encode_log(),
+        }
+    }
+
+    /// Decode an export, detecting the format if none is given
+    pub fn decode(content: &str, format: Option<SessionExportFormat>) -> TermComResult<Self> {
+        let format = format
+            .or_else(|| SessionExportFormat::detect(content))
+            .ok_or_else(|| invalid("Unrecognized session export format"))?;
+
+        match format {
+            SessionExportFormat::Json => Self::decode_json(content),
+            SessionExportFormat::Csv => Self::decode_csv(content),
+            SessionExportFormat::Log => Self::decode_log(content),
+        }
+    }
+
+    fn encode_json(&self) -> TermComResult<String> {
+        let document = JsonExport {
+            format: EXPORT_FORMAT_NAME.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: timestamp::format_nanos(self.exported_at),
+            state: self.state.clone(),
+            messages: self.messages.iter().map(MessageRecord::from_message).collect(),
+            activities: self.activities.iter().map(ActivityRecord::from_activity).collect(),
+        };
+
+        serde_json::to_string_pretty(&document)
+            .map_err(|e| TermComError::Output(format!("Failed to encode session export: {}", e)))
+    }
+
+    fn decode_json(content: &str) -> TermComResult<Self> {
+        let document: JsonExport = serde_json::from_str(content)
+            .map_err(|e| invalid(&format!("Invalid JSON session export: {}", e)))?;
+
+        if document.format != EXPORT_FORMAT_NAME {
+            return Err(invalid(&format!("Unexpected export format '{}'", document.format)));
+        }
+        check_version(document.version)?;
+
+        let state = document.state;
+        let messages = document
+            .messages
+            .into_iter()
+            .map(|record| record.into_message(&state))
+            .collect::<TermComResult<_>>()?;
+        let activities = document
+            .activities
+            .into_iter()
+            .map(ActivityRecord::into_activity)
+            .collect::<TermComResult<_>>()?;
+
+        Ok(Self {
+            exported_at: timestamp::parse(&document.exported_at)?,
+            state,
+            messages,
+            activities,
+        })
+    }
+
+    fn encode_csv(&self) -> TermComResult<String> {
+        let mut csv = String::new();
+        csv.push_str(CSV_HEADER);
+        csv.push('\n');
+
+        let state = &self.state;
+        push_csv_row(
+            &mut csv,
+            &[
+                "state",
+                &timestamp::format_nanos(self.exported_at),
+                "",
+                &state.status.to_string(),
+                &EXPORT_VERSION.to_string(),
+                "",
+                "",
+                &state.session_id,
+                &state.metadata.transport_type,
+                "",
+                &state.device_name,
+                &encode_state(state)?,
+            ],
+        );
+
+        for entry in self.entries() {
+            match entry {
+                Entry::Message(message) => {
+                    let record = MessageRecord::from_message(message);
+                    push_csv_row(
+                        &mut csv,
+                        &[
+                            "message",
+                            &record.timestamp,
+                            &record.direction,
+                            &record.r#type,
+                            &record.sequence.to_string(),
+                            &record.size.to_string(),
+                            record.duration.as_deref().unwrap_or(""),
+                            &record.id,
+                            &record.transport,
+                            &record.hex,
+                            &message.data_as_escaped_text(),
+                            &record.encode_details()?,
+                        ],
+                    );
+                }
+                Entry::Activity(activity) => {
+                    let record = ActivityRecord::from_activity(activity);
+                    push_csv_row(
+                        &mut csv,
+                        &[
+                            "activity",
+                            &record.timestamp,
+                            "",
+                            &record.r#type,
+                            "",
+                            &record.size.map(|size| size.to_string()).unwrap_or_default(),
+                            record.duration.as_deref().unwrap_or(""),
+                            "",
+                            "",
+                            "",
+                            &record.description,
+                            "",
+                        ],
+                    );
+                }
+            }
+        }
+
+        Ok(csv)
+    }
+
+    fn decode_csv(content: &str) -> TermComResult<Self> {
+        let rows = parse_csv(content)?;
+        let mut rows = rows.into_iter();
+
+        match rows.next() {
+            Some(header) if header.join(",") == CSV_HEADER => {}
+            _ => return Err(invalid("Missing CSV session export header")),
+        }
+
+        let mut export: Option<Self> = None;
+        for (index, row) in rows.enumerate() {
+            let line = index + 2;
+            if row.len() != 12 {
+                return Err(invalid(&format!("Expected 12 CSV columns on row {}, found {}", line, row.len())));
+            }
+
+            match row[0].as_str() {
+                "state" => {
+                    check_version(row[4].parse().map_err(|_| invalid("Invalid export version"))?)?;
+                    export = Some(Self {
+                        exported_at: timestamp::parse(&row[1])?,
+                        state: decode_state(&row[11])?,
+                        messages: Vec::new(),
+                        activities: Vec::new(),
+                    });
+                }
+                "message" => {
+                    let export = export.as_mut().ok_or_else(|| invalid("Message row before state row"))?;
+                    let details = MessageDetails::decode(&row[11])?;
+                    let record = MessageRecord {
+                        timestamp: row[1].clone(),
+                        direction: row[2].clone(),
+                        r#type: row[3].clone(),
+                        sequence: parse_field(&row[4], "sequence", line)?,
+                        size: parse_field(&row[5], "size", line)?,
+                        duration: non_empty(&row[6]),
+                        id: row[7].clone(),
+                        transport: row[8].clone(),
+                        hex: row[9].clone(),
+                        text: row[10].clone(),
+                        tags: details.tags,
+                        properties: details.properties,
+                    };
+                    export.messages.push(record.into_message(&export.state)?);
+                }
+                "activity" => {
+                    let export = export.as_mut().ok_or_else(|| invalid("Activity row before state row"))?;
+                    let record = ActivityRecord {
+                        timestamp: row[1].clone(),
+                        r#type: row[3].clone(),
+                        description: row[10].clone(),
+                        size: match row[5].as_str() {
+                            "" => None,
+                            size => Some(parse_field(size, "size", line)?),
+                        },
+                        duration: non_empty(&row[6]),
+                    };
+                    export.activities.push(record.into_activity()?);
+                }
+                other => return Err(invalid(&format!("Unknown CSV record type '{}' on row {}", other, line))),
+            }
+        }
+
+        export.ok_or_else(|| invalid("CSV session export has no state row"))
+    }
+
+    fn encode_log(&self) -> TermComResult<String> {
+        let state = &self.state;
+        let mut log = String::new();
+
+        log.push_str(&format!("# {} v{}\n", EXPORT_FORMAT_NAME, EXPORT_VERSION));
+        log.push_str(&format!("# exported_at {}\n", timestamp::format_nanos(self.exported_at)));
+        log.push_str(&format!(
+            "# session {} device {} transport {} status {}\n",
+            state.session_id,
+            quote(&state.device_name),
+            state.metadata.transport_type,
+            quote(&state.status.to_string())
+        ));
+        log.push_str(&format!(
+            "# statistics sent {} bytes in {} messages, received {} bytes in {} messages, {} errors\n",
+            state.statistics.bytes_sent,
+            state.statistics.messages_sent,
+            state.statistics.bytes_received,
+            state.statistics.messages_received,
+            state.statistics.error_count
+        ));
+        log.push_str(&format!("# state {}\n", encode_state(state)?));
+
+        for entry in self.entries() {
+            match entry {
+                Entry::Message(message) => {
+                    let record = MessageRecord::from_message(message);
+                    let mut line = format!(
+                        "{} MSG {} type={} seq={} size={}",
+                        record.timestamp,
+                        record.direction.to_uppercase(),
+                        record.r#type,
+                        record.sequence,
+                        record.size
+                    );
+                    if let Some(duration) = &record.duration {
+                        line.push_str(&format!(" duration={}", duration));
+                    }
+                    line.push_str(&format!(" id={} transport={} hex={}", record.id, quote(&record.transport), record.hex));
+                    let details = record.encode_details()?;
+                    if !details.is_empty() {
+                        line.push_str(&format!(" details={}", quote(&details)));
+                    }
+                    line.push_str(&format!(" text={}\n", quote(&record.text)));
+                    log.push_str(&line);
+                }
+                Entry::Activity(activity) => {
+                    let record = ActivityRecord::from_activity(activity);
+                    let mut line = format!("{} ACT type={}", record.timestamp, quote(&record.r#type));
+                    if let Some(size) = record.size {
+                        line.push_str(&format!(" size={}", size));
+                    }
+                    if let Some(duration) = &record.duration {
+                        line.push_str(&format!(" duration={}", duration));
+                    }
+                    line.push_str(&format!(" description={}\n", quote(&record.description)));
+                    log.push_str(&line);
+                }
+            }
+        }
+
+        Ok(log)
+    }
+
+    fn decode_log(content: &str) -> TermComResult<Self> {
+        let mut lines = content.lines().enumerate();
+
+        match lines.next() {
+            Some((_, header)) => {
+                let version = header
+                    .strip_prefix(&format!("# {} v", EXPORT_FORMAT_NAME))
+                    .and_then(|version| version.trim().parse().ok())
+                    .ok_or_else(|| invalid("Missing log session export header"))?;
+                check_version(version)?;
+            }
+            None => return Err(invalid("Empty session export")),
+        }
+
+        let mut exported_at = None;
+        let mut state = None;
+        let mut messages = Vec::new();
+        let mut activities = Vec::new();
+
+        for (index, line) in lines {
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(comment) = line.strip_prefix("# ") {
+                if let Some(value) = comment.strip_prefix("exported_at ") {
+                    exported_at = Some(timestamp::parse(value)?);
+                } else if let Some(value) = comment.strip_prefix("state ") {
+                    state = Some(decode_state(value)?);
+                }
+                continue;
+            }
+
+            let state = state.as_ref().ok_or_else(|| invalid("Log entry before session state"))?;
+            let (time, rest) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid(&format!("Malformed log line {}", line_number)))?;
+
+            if let Some(rest) = rest.strip_prefix("MSG ") {
+                let (direction, fields) = rest.split_once(' ').unwrap_or((rest, ""));
+                let mut fields = parse_fields(fields, line_number)?;
+                let details = MessageDetails::decode(&fields.remove("details").unwrap_or_default())?;
+                let record = MessageRecord {
+                    timestamp: time.to_string(),
+                    direction: direction.to_lowercase(),
+                    r#type: take_field(&mut fields, "type", line_number)?,
+                    sequence: parse_field(&take_field(&mut fields, "seq", line_number)?, "seq", line_number)?,
+                    size: parse_field(&take_field(&mut fields, "size", line_number)?, "size", line_number)?,
+                    duration: fields.remove("duration"),
+                    id: take_field(&mut fields, "id", line_number)?,
+                    transport: take_field(&mut fields, "transport", line_number)?,
+                    hex: take_field(&mut fields, "hex", line_number)?,
+                    text: fields.remove("text").unwrap_or_default(),
+                    tags: details.tags,
+                    properties: details.properties,
+                };
+                messages.push(record.into_message(state)?);
+            } else if let Some(rest) = rest.strip_prefix("ACT ") {
+                let mut fields = parse_fields(rest, line_number)?;
+                let record = ActivityRecord {
+                    timestamp: time.to_string(),
+                    r#type: take_field(&mut fields, "type", line_number)?,
+                    description: fields.remove("description").unwrap_or_default(),
+                    size: match fields.remove("size") {
+                        Some(size) => Some(parse_field(&size, "size", line_number)?),
+                        None => None,
+                    },
+                    duration: fields.remove("duration"),
+                };
+                activities.push(record.into_activity()?);
+            } else {
+                return Err(invalid(&format!("Unknown log entry on line {}", line_number)));
+            }
+        }
+
+        Ok(Self {
+            exported_at: exported_at.ok_or_else(|| invalid("Log session export has no export time"))?,
+            state: state.ok_or_else(|| invalid("Log session export has no session state"))?,
+            messages,
+            activities,
+        })
+    }
+
+    /// Messages and activities merged in chronological order
+    fn entries(&self) -> Vec<Entry<'_>> {
+        let mut entries: Vec<(SystemTime, Entry<'_>)> = self
+            .messages
+            .iter()
+            .map(|message| (message.timestamp, Entry::Message(message)))
+            .chain(
+                self.activities
+                    .iter()
+                    .map(|activity| (activity.timestamp, Entry::Activity(activity))),
+            )
+            .collect();
+
+        // Stable sort keeps each history in its recorded order on ties
+        entries.sort_by_key(|(time, _)| *time);
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+impl MessageRecord {
+    fn from_message(message: &Message) -> Self {
+        Self {
+            id: message.id.clone(),
+            timestamp: timestamp::format_nanos(message.timestamp),
+            direction: direction_name(&message.message_type).to_string(),
+            r#type: message_type_name(&message.message_type).to_string(),
+            sequence: message.metadata.sequence,
+            size: message.data.len(),
+            duration: message
+                .metadata
+                .duration_ms
+                .map(|ms| format_duration(Duration::from_millis(ms))),
+            transport: message.metadata.transport.clone(),
+            hex: hex::encode(&message.data),
+            text: String::from_utf8_lossy(&message.data).into_owned(),
+            tags: message.metadata.tags.clone(),
+            properties: message
+                .metadata
+                .properties
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Rebuild the message; the hex payload is authoritative over the text
+    fn into_message(self, state: &SessionState) -> TermComResult<Message> {
+        let data = hex::decode(&self.hex)
+            .map_err(|e| invalid(&format!("Invalid hex payload for message {}: {}", self.id, e)))?;
+        if data.len() != self.size {
+            return Err(invalid(&format!(
+                "Message {} declares {} bytes but carries {}",
+                self.id,
+                self.size,
+                data.len()
+            )));
+        }
+
+        let mut message = Message::new(
+            state.session_id.clone(),
+            state.device_name.clone(),
+            parse_message_type(&self.r#type)?,
+            data,
+            self.transport,
+        );
+        message.id = self.id;
+        message.timestamp = timestamp::parse(&self.timestamp)?;
+        message.metadata.sequence = self.sequence;
+        message.metadata.duration_ms = match self.duration {
+            Some(duration) => Some(parse_duration(&duration)?.as_millis() as u64),
+            None => None,
+        };
+        message.metadata.tags = self.tags;
+        message.metadata.properties = self.properties.into_iter().collect::<HashMap<_, _>>();
+
+        Ok(message)
+    }
+
+    fn encode_details(&self) -> TermComResult<String> {
+        if self.tags.is_empty() && self.properties.is_empty() {
+            return Ok(String::new());
+        }
+
+        let details = MessageDetails {
+            tags: self.tags.clone(),
+            properties: self.properties.clone(),
+        };
+        serde_json::to_string(&details)
+            .map_err(|e| TermComError::Output(format!("Failed to encode message details: {}", e)))
+    }
+}
+
+impl MessageDetails {
+    fn decode(value: &str) -> TermComResult<Self> {
+        if value.is_empty() {
+            return Ok(Self {
+                tags: Vec::new(),
+                properties: BTreeMap::new(),
+            });
+        }
+
+        serde_json::from_str(value).map_err(|e| invalid(&format!("Invalid message details: {}", e)))
+    }
+}
+
+impl ActivityRecord {
+    fn from_activity(activity: &SessionActivity) -> Self {
+        Self {
+            timestamp: timestamp::format_nanos(activity.timestamp),
+            r#type: activity_type_name(&activity.activity_type),
+            description: activity.description.clone(),
+            size: activity.data_size,
+            duration: activity.duration.map(format_duration),
+        }
+    }
+
+    fn into_activity(self) -> TermComResult<SessionActivity> {
+        Ok(SessionActivity {
+            timestamp: timestamp::parse(&self.timestamp)?,
+            activity_type: parse_activity_type(&self.r#type),
+            description: self.description,
+            data_size: self.size,
+            duration: match self.duration {
+                Some(duration) => Some(parse_duration(&duration)?),
+                None => None,
+            },
+        })
+    }
+}
+
+fn direction_name(message_type: &MessageType) -> &'static str {
+    match message_type {
+        MessageType::Sent | MessageType::Command => "tx",
+        MessageType::Received | MessageType::Response => "rx",
+        MessageType::System | MessageType::Error => "none",
+    }
+}
+
+fn message_type_name(message_type: &MessageType) -> &'static str {
+    match message_type {
+        MessageType::Sent => "sent",
+        MessageType::Received => "received",
+        MessageType::System => "system",
+        MessageType::Error => "error",
+        MessageType::Command => "command",
+        MessageType::Response => "response",
+    }
+}
+
+fn parse_message_type(name: &str) -> TermComResult<MessageType> {
+    match name {
+        "sent" => Ok(MessageType::Sent),
+        "received" => Ok(MessageType::Received),
+        "system" => Ok(MessageType::System),
+        "error" => Ok(MessageType::Error),
+        "command" => Ok(MessageType::Command),
+        "response" => Ok(MessageType::Response),
+        other => Err(invalid(&format!("Unknown message type '{}'", other))),
+    }
+}
+
+/// Get the export name of an activity type; custom types are `custom:<name>`
+fn activity_type_name(activity_type: &ActivityType) -> String {
+    match activity_type {
+        ActivityType::Created => "created".to_string(),
+        ActivityType::Connected => "connected".to_string(),
+        ActivityType::DataSent => "data_sent".to_string(),
+        ActivityType::DataReceived => "data_received".to_string(),
+        ActivityType::CommandExecuted => "command_executed".to_string(),
+        ActivityType::ResponseReceived => "response_received".to_string(),
+        ActivityType::ConnectionLost => "connection_lost".to_string(),
+        ActivityType::Closed => "closed".to_string(),
+        ActivityType::Error => "error".to_string(),
+        ActivityType::SignalChanged => "signal_changed".to_string(),
+        ActivityType::ReconnectAttempt => "reconnect_attempt".to_string(),
+        ActivityType::Custom(name) => format!("custom:{}", name),
+    }
+}
+
+fn parse_activity_type(name: &str) -> ActivityType {
+    match name {
+        "created" => ActivityType::Created,
+        "connected" => ActivityType::Connected,
+        "data_sent" => ActivityType::DataSent,
+        "data_received" => ActivityType::DataReceived,
+        "command_executed" => ActivityType::CommandExecuted,
+        "response_received" => ActivityType::ResponseReceived,
+        "connection_lost" => ActivityType::ConnectionLost,
+        "closed" => ActivityType::Closed,
+        "error" => ActivityType::Error,
+        "signal_changed" => ActivityType::SignalChanged,
+        "reconnect_attempt" => ActivityType::ReconnectAttempt,
+        other => ActivityType::Custom(other.strip_prefix("custom:").unwrap_or(other).to_string()),
+    }
+}
+
+/// Format a duration as decimal seconds with nanosecond precision
+fn format_duration(duration: Duration) -> String {
+    format!("{}.{:09}", duration.as_secs(), duration.subsec_nanos())
+}
+
+fn parse_duration(value: &str) -> TermComResult<Duration> {
+    let error = || invalid(&format!("Invalid duration '{}'", value));
+    let (secs, nanos) = value.split_once('.').unwrap_or((value, "0"));
+    if nanos.is_empty() || nanos.len() > 9 {
+        return Err(error());
+    }
+
+    let secs = secs.parse().map_err(|_| error())?;
+    let nanos = format!("{:0<9}", nanos).parse().map_err(|_| error())?;
+    Ok(Duration::new(secs, nanos))
+}
+
+fn encode_state(state: &SessionState) -> TermComResult<String> {
+    serde_json::to_string(state).map_err(|e| TermComError::Output(format!("Failed to encode session state: {}", e)))
+}
+
+fn decode_state(value: &str) -> TermComResult<SessionState> {
+    serde_json::from_str(value).map_err(|e| invalid(&format!("Invalid session state: {}", e)))
+}
+
+fn check_version(version: u32) -> TermComResult<()> {
+    if version > EXPORT_VERSION {
+        return Err(invalid(&format!(
+            "Session export version {} is newer than supported version {}",
+            version, EXPORT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn parse_field<T: std::str::FromStr>(value: &str, name: &str, line: usize) -> TermComResult<T> {
+    value
+        .parse()
+        .map_err(|_| invalid(&format!("Invalid {} '{}' on line {}", name, value, line)))
+}
+
+fn take_field(fields: &mut HashMap<String, String>, name: &str, line: usize) -> TermComResult<String> {
+    fields
+        .remove(name)
+        .ok_or_else(|| invalid(&format!("Missing {} on line {}", name, line)))
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn invalid(message: &str) -> TermComError {
+    TermComError::InvalidData(message.to_string())
+}
+
+/// Quote a log field, escaping quotes, backslashes and control characters
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\r' => quoted.push_str("\\r"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parse `key=value` log fields where values are bare words or quoted strings
+fn parse_fields(input: &str, line: usize) -> TermComResult<HashMap<String, String>> {
+    let malformed = || invalid(&format!("Malformed field on log line {}", line));
+    let mut fields = HashMap::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let key: String = chars.by_ref().take_while(|&c| c != '=').collect();
+        if key.is_empty() || key.contains(' ') {
+            return Err(malformed());
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next().ok_or_else(malformed)? {
+                    '"' => break,
+                    '\\' => match chars.next().ok_or_else(malformed)? {
+                        'r' => value.push('\r'),
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'u' => {
+                            if chars.next() != Some('{') {
+                                return Err(malformed());
+                            }
+                            let code: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                            let c = u32::from_str_radix(&code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(malformed)?;
+                            value.push(c);
+                        }
+                        c => value.push(c),
+                    },
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ' ' {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+
+        fields.insert(key, value);
+    }
+
+    Ok(fields)
+}
+
+fn push_csv_row(csv: &mut String, fields: &[&str]) {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    csv.push_str(&row.join(","));
+    csv.push('\n');
+}
+
+/// Parse RFC 4180 CSV, allowing quoted fields to span lines
+fn parse_csv(content: &str) -> TermComResult<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(invalid("Unterminated quoted CSV field"));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session::state::SessionStatus;
+    use std::time::UNIX_EPOCH;
+
+    fn create_export() -> SessionExport {
+        let mut state = SessionState::new("session-1".to_string(), "Device, \"quoted\"".to_string(), "serial".to_string());
+        state.update_status(SessionStatus::Error("Port vanished\nunexpectedly".to_string()));
+        state.add_tag("firmware".to_string());
+        state.record_activity(SessionActivity::data_sent("Sent 4 bytes".to_string(), 4));
+
+        let base = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+
+        let mut sent = Message::sent("transport-1".to_string(), "device".to_string(), b"AT\r\n".to_vec(), "serial".to_string());
+        sent.timestamp = base;
+        sent.set_sequence(1);
+
+        let mut received = Message::response(
+            "transport-1".to_string(),
+            "device".to_string(),
+            vec![0x00, 0xff, b'"', b',', b'\n', 0xc3, 0xa9],
+            "serial".to_string(),
+            Some(250),
+        );
+        received.timestamp = base + Duration::from_millis(250);
+        received.set_sequence(2);
+        received.add_tag("boot".to_string());
+        received.add_property("peer".to_string(), "10.0.0.1:5000".to_string());
+
+        let mut connected = SessionActivity::new(ActivityType::Connected, "Connected to /dev/ttyUSB0".to_string());
+        connected.timestamp = base - Duration::from_secs(1);
+        let mut response = SessionActivity::response_received("Reply \"OK\"".to_string(), Duration::new(0, 250_000_001));
+        response.timestamp = base + Duration::from_millis(250);
+        let mut custom = SessionActivity::custom("Signal change".to_string(), "DTR=off, RTS=on".to_string()).with_data_size(0);
+        custom.timestamp = base + Duration::from_secs(2);
+
+        SessionExport::new(state, vec![sent, received], vec![connected, response, custom])
+    }
+
+    fn assert_same(decoded: &SessionExport, original: &SessionExport) {
+        assert_eq!(decoded.exported_at, original.exported_at);
+        assert_eq!(
+            serde_json::to_value(&decoded.state).unwrap(),
+            serde_json::to_value(&original.state).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&decoded.messages).unwrap(),
+            serde_json::to_value(&original.messages).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&decoded.activities).unwrap(),
+            serde_json::to_value(&original.activities).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_messages_attributed_to_session() {
+        let export = create_export();
+        assert!(export.messages.iter().all(|m| m.session_id == "session-1"));
+        assert!(export.messages.iter().all(|m| m.device_name == "Device, \"quoted\""));
+    }
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let export = create_export();
+
+        for format in [SessionExportFormat::Json, SessionExportFormat::Csv, SessionExportFormat::Log] {
+            let encoded = export.encode(format).unwrap();
+            assert_eq!(SessionExportFormat::detect(&encoded), Some(format));
+
+            let decoded = SessionExport::decode(&encoded, None).unwrap();
+            assert_same(&decoded, &export);
+        }
+    }
+
+    #[test]
+    fn test_json_layout() {
+        let encoded = create_export().encode(SessionExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+
+        assert_eq!(value["format"], EXPORT_FORMAT_NAME);
+        assert_eq!(value["version"], EXPORT_VERSION);
+        assert_eq!(value["messages"][0]["direction"], "tx");
+        assert_eq!(value["messages"][0]["hex"], "41540d0a");
+        assert_eq!(value["messages"][0]["text"], "AT\r\n");
+        assert_eq!(value["messages"][0]["timestamp"], "2023-11-14T22:13:20.123456789Z");
+        assert_eq!(value["messages"][1]["direction"], "rx");
+        assert_eq!(value["messages"][1]["duration"], "0.250000000");
+        assert_eq!(value["activities"][2]["type"], "custom:Signal change");
+    }
+
+    #[test]
+    fn test_csv_layout() {
+        let encoded = create_export().encode(SessionExportFormat::Csv).unwrap();
+        let rows = parse_csv(&encoded).unwrap();
+
+        assert_eq!(rows[0].join(","), CSV_HEADER);
+        assert_eq!(rows[1][0], "state");
+        // Activities and messages are listed chronologically
+        let kinds: Vec<&str> = rows[2..].iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(kinds, ["activity", "message", "message", "activity", "activity"]);
+        assert_eq!(rows[3][10], "AT\\r\\n");
+        assert!(rows.iter().all(|row| row.len() == 12));
+    }
+
+    #[test]
+    fn test_log_layout() {
+        let encoded = create_export().encode(SessionExportFormat::Log).unwrap();
+        let lines: Vec<&str> = encoded.lines().collect();
+
+        assert_eq!(lines[0], "# termcom-session-export v1");
+        assert!(lines.iter().any(|line| line.starts_with("# state {")));
+        assert!(lines[5].starts_with("2023-11-14T22:13:19.123456789Z ACT type=\"connected\""));
+        assert!(lines[6].starts_with("2023-11-14T22:13:20.123456789Z MSG TX type=sent seq=1 size=4 id="));
+        assert!(lines[6].ends_with(" hex=41540d0a text=\"AT\\r\\n\""));
+        assert!(lines[7].contains(" MSG RX type=response seq=2 size=7 duration=0.250000000 "));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(SessionExport::decode("", None).is_err());
+        assert!(SessionExport::decode("not an export", None).is_err());
+        assert!(SessionExport::decode("{}", Some(SessionExportFormat::Json)).is_err());
+        assert!(SessionExport::decode(CSV_HEADER, Some(SessionExportFormat::Csv)).is_err());
+        assert!(SessionExport::decode("# termcom-session-export v99\n", None).is_err());
+
+        let encoded = create_export().encode(SessionExportFormat::Log).unwrap();
+        let corrupted = encoded.replace("hex=41540d0a", "hex=zz");
+        assert!(SessionExport::decode(&corrupted, None).is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(SessionExportFormat::from_path(Path::new("a.JSON")), Some(SessionExportFormat::Json));
+        assert_eq!(SessionExportFormat::from_path(Path::new("a.csv")), Some(SessionExportFormat::Csv));
+        assert_eq!(SessionExportFormat::from_path(Path::new("a.log")), Some(SessionExportFormat::Log));
+        assert_eq!(SessionExportFormat::from_path(Path::new("a")), None);
+    }
+}
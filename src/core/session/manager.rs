@@ -1,14 +1,15 @@
 use crate::core::{
-    communication::CommunicationEngine,
+    communication::{CommunicationEngine, ControlLines, Message, ModemStatus},
     session::{
+        boot::BootLog,
         session::{Session, SessionConfig, SessionType},
-        state::{SessionState, SessionStatus},
+        state::{SessionActivity, SessionState, SessionStatus},
     },
 };
-use crate::domain::error::{TermComError, TermComResult};
+use crate::domain::{config::SerialSettings, error::{TermComError, TermComResult}};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 
 /// Session manager for handling multiple sessions
@@ -24,7 +25,7 @@ pub struct SessionManager {
 }
 
 /// Session summary information
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SessionSummary {
     pub session_id: String,
     pub name: String,
@@ -85,10 +86,45 @@ impl SessionFilter {
         self.tags.push(tag.to_string());
         self
     }
+    
+    /// Check whether a session summary matches this filter
+    ///
+    /// Tags are not part of the summary and are therefore ignored.
+    pub fn matches(&self, summary: &SessionSummary) -> bool {
+        // Check session type
+        if let Some(ref session_type) = self.session_type {
+            if summary.session_type != *session_type {
+                return false;
+            }
+        }
+        
+        // Check status
+        if let Some(ref status) = self.status {
+            if std::mem::discriminant(&summary.status) != std::mem::discriminant(status) {
+                return false;
+            }
+        }
+        
+        // Check device name
+        if let Some(ref device_name) = self.device_name {
+            if summary.device_name != *device_name {
+                return false;
+            }
+        }
+        
+        // Check name pattern
+        if let Some(ref pattern) = self.name_pattern {
+            if !summary.name.contains(pattern) {
+                return false;
+            }
+        }
+        
+        true
+    }
 }
 
 /// Global session statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct GlobalStatistics {
     pub total_sessions: usize,
     pub active_sessions: usize,
@@ -96,6 +132,9 @@ pub struct GlobalStatistics {
     pub total_bytes_sent: u64,
     pub total_bytes_received: u64,
     pub total_errors: u64,
+    /// Messages discarded because a queue was full
+    #[serde(default)]
+    pub total_dropped: u64,
 }
 
 impl SessionManager {
@@ -204,6 +243,20 @@ impl SessionManager {
         sessions.contains_key(session_id)
     }
     
+    /// Resolve a session ID from either an ID or a session name
+    pub async fn resolve_session_id(&self, id_or_name: &str) -> Option<String> {
+        let sessions = self.sessions.read().await;
+        
+        if sessions.contains_key(id_or_name) {
+            return Some(id_or_name.to_string());
+        }
+        
+        sessions
+            .iter()
+            .find(|(_, session)| session.get_config().name == id_or_name)
+            .map(|(session_id, _)| session_id.clone())
+    }
+    
     /// Send data to a session
     pub async fn send_data(&self, session_id: &str, data: Vec<u8>) -> TermComResult<()> {
         let sessions = self.sessions.read().await;
@@ -217,6 +270,97 @@ impl SessionManager {
         }
     }
     
+    /// Send data to one peer of a session
+    pub async fn send_to_peer(&self, session_id: &str, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            session.send_to_peer(peer, data).await
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// List the peers connected to a session
+    pub async fn list_peers(&self, session_id: &str) -> TermComResult<Vec<String>> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            Ok(session.list_peers().await)
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// Drive the DTR and RTS lines of a session's port
+    pub async fn set_control_lines(&self, session_id: &str, lines: ControlLines) -> TermComResult<()> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            session.set_control_lines(lines).await
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// Read the modem status lines of a session's port
+    pub async fn modem_status(&self, session_id: &str) -> TermComResult<ModemStatus> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            session.modem_status().await
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// Send a break condition on a session's line
+    pub async fn send_break(&self, session_id: &str, duration: std::time::Duration) -> TermComResult<()> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            session.send_break(duration).await
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// Change the serial line settings of a running session
+    pub async fn set_serial_settings(&self, session_id: &str, settings: SerialSettings) -> TermComResult<SerialSettings> {
+        let mut sessions = self.sessions.write().await;
+        
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.set_serial_settings(settings).await
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
+    /// Run a reset sequence on a session and capture its boot log
+    pub async fn run_reset_sequence(&self, session_id: &str, sequence: &str) -> TermComResult<BootLog> {
+        // The capture can take seconds; don't hold the session map meanwhile
+        let runner = self.sessions.read().await.get(session_id).map(Session::reset_runner);
+        
+        match runner {
+            Some(runner) => runner.run(sequence).await,
+            None => Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            }),
+        }
+    }
+    
     /// Send command to a session
     pub async fn send_command(&self, session_id: &str, command: &str) -> TermComResult<()> {
         let sessions = self.sessions.read().await;
@@ -241,6 +385,47 @@ impl SessionManager {
         }
     }
     
+    /// Get the configuration of a session
+    pub async fn get_session_config(&self, session_id: &str) -> Option<SessionConfig> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(|session| session.get_config().clone())
+    }
+
+    /// Get session message history
+    pub async fn get_message_history(&self, session_id: &str) -> Option<Vec<Message>> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            Some(session.get_message_history().await)
+        } else {
+            None
+        }
+    }
+    
+    /// Get session activity history
+    pub async fn get_activity_history(&self, session_id: &str) -> Option<Vec<SessionActivity>> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            Some(session.get_activity_history().await)
+        } else {
+            None
+        }
+    }
+    
+    /// Subscribe to live messages of a session
+    pub async fn subscribe(&self, session_id: &str) -> TermComResult<broadcast::Receiver<Message>> {
+        let sessions = self.sessions.read().await;
+        
+        if let Some(session) = sessions.get(session_id) {
+            Ok(session.subscribe())
+        } else {
+            Err(TermComError::Session {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+    
     /// List all sessions
     pub async fn list_sessions(&self) -> Vec<SessionSummary> {
         let sessions = self.sessions.read().await;
@@ -261,7 +446,7 @@ impl SessionManager {
         
         summaries
             .into_iter()
-            .filter(|summary| self.matches_filter(summary, filter))
+            .filter(|summary| filter.matches(summary))
             .collect()
     }
     
@@ -353,9 +538,10 @@ impl SessionManager {
     /// Get session statistics
     pub async fn get_statistics(&self) -> SessionManagerStats {
         let sessions = self.sessions.read().await;
-        let mut stats = SessionManagerStats::default();
-        
-        stats.total_sessions = sessions.len();
+        let mut stats = SessionManagerStats {
+            total_sessions: sessions.len(),
+            ..Default::default()
+        };
         
         for session in sessions.values() {
             let state = session.get_state().await;
@@ -373,6 +559,7 @@ impl SessionManager {
             stats.total_messages_sent += state.statistics.messages_sent;
             stats.total_messages_received += state.statistics.messages_received;
             stats.total_errors += state.statistics.error_count;
+            stats.total_messages_dropped += state.statistics.messages_dropped;
         }
         
         stats
@@ -381,9 +568,10 @@ impl SessionManager {
     /// Get global statistics across all sessions
     pub async fn get_global_statistics(&self) -> GlobalStatistics {
         let sessions = self.sessions.read().await;
-        let mut stats = GlobalStatistics::default();
-        
-        stats.total_sessions = sessions.len();
+        let mut stats = GlobalStatistics {
+            total_sessions: sessions.len(),
+            ..Default::default()
+        };
         
         for session in sessions.values() {
             let state = session.get_state().await;
@@ -396,6 +584,7 @@ impl SessionManager {
             stats.total_bytes_sent += state.statistics.bytes_sent;
             stats.total_bytes_received += state.statistics.bytes_received;
             stats.total_errors += state.statistics.error_count;
+            stats.total_dropped += state.statistics.messages_dropped;
         }
         
         stats
@@ -535,41 +724,6 @@ impl SessionManager {
             bytes_received: state.statistics.bytes_received,
         })
     }
-    
-    fn matches_filter(&self, summary: &SessionSummary, filter: &SessionFilter) -> bool {
-        // Check session type
-        if let Some(ref session_type) = filter.session_type {
-            if summary.session_type != *session_type {
-                return false;
-            }
-        }
-        
-        // Check status
-        if let Some(ref status) = filter.status {
-            if std::mem::discriminant(&summary.status) != std::mem::discriminant(status) {
-                return false;
-            }
-        }
-        
-        // Check device name
-        if let Some(ref device_name) = filter.device_name {
-            if summary.device_name != *device_name {
-                return false;
-            }
-        }
-        
-        // Check name pattern
-        if let Some(ref pattern) = filter.name_pattern {
-            if !summary.name.contains(pattern) {
-                return false;
-            }
-        }
-        
-        // For tags, we would need to access the session state, which is more complex
-        // For now, we'll skip tag filtering in summaries
-        
-        true
-    }
 }
 
 /// Session manager statistics
@@ -586,6 +740,7 @@ pub struct SessionManagerStats {
     pub total_messages_sent: u64,
     pub total_messages_received: u64,
     pub total_errors: u64,
+    pub total_messages_dropped: u64,
 }
 
 
@@ -597,17 +752,19 @@ mod tests {
     
     fn create_test_device_config(name: &str) -> DeviceConfig {
         DeviceConfig {
-            name: name.to_string(),
             description: format!("Test device {}", name),
-            connection: ConnectionConfig::Serial {
-                port: "/dev/null".to_string(),
-                baud_rate: 9600,
-                data_bits: 8,
-                stop_bits: 1,
-                parity: ParityConfig::None,
-                flow_control: FlowControlConfig::None,
-            },
-            commands: Vec::new(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Serial {
+                    port: "/dev/null".to_string(),
+                    baud_rate: 9600,
+                    data_bits: 8,
+                    stop_bits: 1,
+                    parity: ParityConfig::None,
+                    flow_control: FlowControlConfig::None,
+                    rs485: None,
+                },
+            )
         }
     }
     
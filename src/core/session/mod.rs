@@ -1,8 +1,13 @@
 // Session module - Session management
+pub mod boot;
+pub mod export;
 pub mod manager;
+#[allow(clippy::module_inception)]
 pub mod session;
 pub mod state;
 
+pub use boot::BootLog;
+pub use export::{SessionExport, SessionExportFormat};
 pub use manager::{SessionManager, SessionFilter, SessionSummary};
 pub use session::{Session, SessionConfig, SessionType};
 pub use state::{SessionState, SessionStatus};
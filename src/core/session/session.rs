@@ -1,14 +1,28 @@
 use crate::core::{
-    communication::{CommunicationEngine, Message, MessagePattern},
-    session::state::{SessionState, SessionActivity, SessionStatus, ActivityType},
+    communication::{
+        message::{MessageType, DISCONNECTED_EVENT},
+        CommunicationEngine, ControlLines, Message, MessagePattern, ModemStatus, TransportType,
+    },
+    session::{
+        boot::BootLog,
+        state::{SessionState, SessionActivity, SessionStatus, ActivityType},
+    },
 };
-use crate::domain::{config::DeviceConfig, error::{TermComError, TermComResult}};
+use crate::domain::{config::{DeviceConfig, SerialSettings}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{DropCounter, QueueReceiver};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
+/// Number of messages buffered for each live subscriber
+const MESSAGE_BROADCAST_CAPACITY: usize = 1024;
+
+/// Longest wait between reconnection attempts
+pub const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
 /// Session type enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SessionType {
@@ -33,9 +47,10 @@ pub struct SessionConfig {
     pub device_config: DeviceConfig,
     /// Auto-reconnect on disconnect
     pub auto_reconnect: bool,
-    /// Maximum reconnection attempts
+    /// Maximum reconnection attempts (0 = keep trying)
     pub max_reconnect_attempts: u32,
-    /// Reconnection delay in milliseconds
+    /// Delay before the first reconnection attempt in milliseconds,
+    /// doubled for each further attempt
     pub reconnect_delay_ms: u64,
     /// Session timeout in milliseconds (0 = no timeout)
     pub timeout_ms: u64,
@@ -67,10 +82,36 @@ pub struct Session {
     activity_sender: mpsc::UnboundedSender<SessionActivity>,
     /// Running flag
     running: Arc<RwLock<bool>>,
+    /// Task feeding transport messages into the history
+    message_task: Option<tokio::task::JoinHandle<()>>,
+    /// Live message feed for monitors
+    message_broadcast: broadcast::Sender<Message>,
+    /// Messages the engine discarded before this session received them
+    dropped: DropCounter,
     /// Background task handles
     _background_tasks: Vec<tokio::task::JoinHandle<()>>,
 }
 
+/// Runs a reset sequence for a session without borrowing it
+///
+/// Holds clones of the session's shared parts, taken by `Session::reset_runner`.
+pub struct ResetRunner {
+    session_name: String,
+    device_config: DeviceConfig,
+    comm_engine: Arc<CommunicationEngine>,
+    transport_session_id: Option<String>,
+    running: Arc<RwLock<bool>>,
+    activity_sender: mpsc::UnboundedSender<SessionActivity>,
+    message_broadcast: broadcast::Sender<Message>,
+}
+
+/// Restores a session's transport connection after the device goes away
+struct Reconnector {
+    comm_engine: Arc<CommunicationEngine>,
+    transport_session_id: String,
+    config: SessionConfig,
+}
+
 impl Session {
     /// Create a new session
     pub async fn new(
@@ -78,10 +119,7 @@ impl Session {
         comm_engine: Arc<CommunicationEngine>,
     ) -> TermComResult<Self> {
         let session_id = uuid::Uuid::new_v4().to_string();
-        let transport_type = match &config.device_config.connection {
-            crate::domain::config::ConnectionConfig::Serial { .. } => "serial",
-            crate::domain::config::ConnectionConfig::Tcp { .. } => "tcp",
-        };
+        let transport_type = TransportType::for_connection(&config.device_config.connection);
         
         let mut state = SessionState::new(
             session_id.clone(),
@@ -103,6 +141,7 @@ impl Session {
         let activity_history = Arc::new(RwLock::new(VecDeque::new()));
         let (activity_sender, activity_receiver) = mpsc::unbounded_channel();
         let running = Arc::new(RwLock::new(false));
+        let (message_broadcast, _) = broadcast::channel(MESSAGE_BROADCAST_CAPACITY);
         
         // Start activity processor
         let activity_processor = Self::start_activity_processor(
@@ -122,6 +161,9 @@ impl Session {
             activity_history,
             activity_sender,
             running,
+            message_task: None,
+            message_broadcast,
+            dropped: DropCounter::default(),
             _background_tasks: vec![activity_processor],
         })
     }
@@ -151,6 +193,21 @@ impl Session {
         // Create transport session
         match self.comm_engine.create_session(&self.config.device_config).await {
             Ok(transport_session_id) => {
+                let receiver = self.comm_engine.subscribe(&transport_session_id).await;
+                self.dropped = receiver.drop_counter();
+                let reconnector = self.config.auto_reconnect.then(|| Reconnector {
+                    comm_engine: Arc::clone(&self.comm_engine),
+                    transport_session_id: transport_session_id.clone(),
+                    config: self.config.clone(),
+                });
+                self.message_task = Some(Self::start_message_processor(
+                    receiver,
+                    Arc::clone(&self.message_history),
+                    self.activity_sender.clone(),
+                    self.message_broadcast.clone(),
+                    self.config.max_history_size,
+                    reconnector,
+                ));
                 self.transport_session_id = Some(transport_session_id);
                 
                 // Update state to active
@@ -198,6 +255,11 @@ impl Session {
             state.update_status(SessionStatus::Closing);
         }
         
+        // Stop processing first so a pending reconnect cannot reopen the transport
+        if let Some(message_task) = self.message_task.take() {
+            message_task.abort();
+        }
+        
         // Close transport session if exists
         if let Some(ref transport_session_id) = self.transport_session_id {
             if let Err(e) = self.comm_engine.close_session(transport_session_id).await {
@@ -248,6 +310,160 @@ impl Session {
         }
     }
     
+    /// Send data to one peer of a session that serves several peers
+    pub async fn send_to_peer(&self, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        self.ensure_running().await?;
+        
+        if let Some(ref transport_session_id) = self.transport_session_id {
+            self.comm_engine.send_to_peer(transport_session_id, peer, data.clone()).await?;
+            
+            // Record activity
+            self.record_activity(
+                SessionActivity::data_sent(
+                    format!("Sent {} bytes to {}", data.len(), peer),
+                    data.len(),
+                )
+            ).await;
+            
+            debug!("Session '{}' sent {} bytes to {}", self.config.name, data.len(), peer);
+            Ok(())
+        } else {
+            Err(TermComError::Session {
+                message: "No active transport session".to_string(),
+            })
+        }
+    }
+    
+    /// List the peers connected to the session
+    pub async fn list_peers(&self) -> Vec<String> {
+        match self.transport_session_id {
+            Some(ref transport_session_id) => self.comm_engine.list_peers(transport_session_id).await,
+            None => Vec::new(),
+        }
+    }
+    
+    /// Drive the DTR and RTS lines of the device's port
+    pub async fn set_control_lines(&self, lines: ControlLines) -> TermComResult<()> {
+        self.ensure_running().await?;
+        
+        if let Some(ref transport_session_id) = self.transport_session_id {
+            self.comm_engine.set_control_lines(transport_session_id, lines).await?;
+            
+            self.record_activity(SessionActivity::signal_changed(format!("Set {}", lines))).await;
+            
+            debug!("Session '{}' set {}", self.config.name, lines);
+            Ok(())
+        } else {
+            Err(TermComError::Session {
+                message: "No active transport session".to_string(),
+            })
+        }
+    }
+    
+    /// Read the CTS, DSR, RI and CD lines of the device's port
+    pub async fn modem_status(&self) -> TermComResult<ModemStatus> {
+        self.ensure_running().await?;
+        
+        match self.transport_session_id {
+            Some(ref transport_session_id) => self.comm_engine.modem_status(transport_session_id).await,
+            None => Err(TermComError::Session {
+                message: "No active transport session".to_string(),
+            }),
+        }
+    }
+    
+    /// Hold the device's line in the break condition for `duration`
+    pub async fn send_break(&self, duration: std::time::Duration) -> TermComResult<()> {
+        self.ensure_running().await?;
+        
+        if let Some(ref transport_session_id) = self.transport_session_id {
+            self.comm_engine.send_break(transport_session_id, duration).await?;
+            
+            self.record_activity(
+                SessionActivity::signal_changed(format!("Sent break for {} ms", duration.as_millis()))
+                    .with_duration(duration)
+            ).await;
+            
+            debug!("Session '{}' sent break for {:?}", self.config.name, duration);
+            Ok(())
+        } else {
+            Err(TermComError::Session {
+                message: "No active transport session".to_string(),
+            })
+        }
+    }
+    
+    /// Change the line settings of the device's serial port while it is open
+    ///
+    /// The session keeps its identity and history; the device configuration
+    /// is updated so later reconnects use the new settings. Returns the
+    /// resulting settings of the port.
+    pub async fn set_serial_settings(&mut self, settings: SerialSettings) -> TermComResult<SerialSettings> {
+        self.ensure_running().await?;
+        
+        let mut connection = self.config.device_config.connection.clone();
+        settings.apply(&mut connection)?;
+        if settings.is_empty() {
+            return Ok(SerialSettings::from_connection(&connection).unwrap_or_default());
+        }
+        
+        let transport_session_id = self.transport_session_id.as_ref().ok_or_else(|| TermComError::Session {
+            message: "No active transport session".to_string(),
+        })?;
+        self.comm_engine.reconfigure(transport_session_id, &connection).await?;
+        self.config.device_config.connection = connection;
+        
+        let current = SerialSettings::from_connection(&self.config.device_config.connection).unwrap_or_default();
+        {
+            let mut state = self.state.write().await;
+            let params = [
+                ("baud_rate", current.baud_rate.map(|value| value.to_string())),
+                ("data_bits", current.data_bits.map(|value| value.to_string())),
+                ("stop_bits", current.stop_bits.map(|value| value.to_string())),
+                ("parity", current.parity.map(|value| value.to_string())),
+                ("flow_control", current.flow_control.map(|value| value.to_string())),
+            ];
+            for (key, value) in params {
+                if let Some(value) = value {
+                    state.set_connection_param(key.to_string(), value);
+                }
+            }
+        }
+        
+        self.record_activity(SessionActivity::custom(
+            "serial_settings".to_string(),
+            format!("Changed serial settings to {}", settings),
+        )).await;
+        
+        info!("Session '{}' changed serial settings to {}", self.config.name, settings);
+        Ok(current)
+    }
+    
+    /// Run a named reset sequence and capture the device's output
+    ///
+    /// The sequence is looked up in the device profile, then among the
+    /// built-in ones. Output is captured from the first step until the
+    /// sequence's `until` text appears or its timeout passes.
+    pub async fn run_reset_sequence(&self, name: &str) -> TermComResult<BootLog> {
+        self.reset_runner().run(name).await
+    }
+    
+    /// Take what a reset sequence needs, so it can run without borrowing the session
+    ///
+    /// Used by the session manager to run a sequence without holding its
+    /// session map for the whole capture.
+    pub fn reset_runner(&self) -> ResetRunner {
+        ResetRunner {
+            session_name: self.config.name.clone(),
+            device_config: self.config.device_config.clone(),
+            comm_engine: Arc::clone(&self.comm_engine),
+            transport_session_id: self.transport_session_id.clone(),
+            running: Arc::clone(&self.running),
+            activity_sender: self.activity_sender.clone(),
+            message_broadcast: self.message_broadcast.clone(),
+        }
+    }
+    
     /// Send a command to the device
     pub async fn send_command(&self, command: &str) -> TermComResult<()> {
         self.ensure_running().await?;
@@ -272,7 +488,15 @@ impl Session {
     
     /// Get session state
     pub async fn get_state(&self) -> SessionState {
-        self.state.read().await.clone()
+        let mut state = self.state.read().await.clone();
+        
+        let transport_dropped = match &self.transport_session_id {
+            Some(id) => self.comm_engine.get_session_info(id).await.map_or(0, |info| info.dropped_messages),
+            None => 0,
+        };
+        state.statistics.messages_dropped = self.dropped.get() + transport_dropped;
+        
+        state
     }
     
     /// Get session configuration
@@ -296,6 +520,14 @@ impl Session {
             .collect()
     }
     
+    /// Subscribe to messages as they are sent and received
+    ///
+    /// Slow subscribers skip messages rather than blocking the session;
+    /// the receiver reports how many were skipped.
+    pub fn subscribe(&self) -> broadcast::Receiver<Message> {
+        self.message_broadcast.subscribe()
+    }
+    
     /// Get activity history
     pub async fn get_activity_history(&self) -> Vec<SessionActivity> {
         let history = self.activity_history.read().await;
@@ -360,11 +592,66 @@ impl Session {
     }
     
     async fn record_activity(&self, activity: SessionActivity) {
-        if let Err(_) = self.activity_sender.send(activity) {
+        if self.activity_sender.send(activity).is_err() {
             error!("Failed to record activity for session '{}'", self.config.name);
         }
     }
     
+    fn start_message_processor(
+        mut receiver: QueueReceiver<Message>,
+        message_history: Arc<RwLock<VecDeque<Message>>>,
+        activity_sender: mpsc::UnboundedSender<SessionActivity>,
+        message_broadcast: broadcast::Sender<Message>,
+        max_history_size: usize,
+        reconnector: Option<Reconnector>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let disconnected = message.event_name() == Some(DISCONNECTED_EVENT);
+                
+                // No live subscribers is not an error
+                let _ = message_broadcast.send(message.clone());
+                
+                // Received data updates statistics through its activity;
+                // sent data is already recorded by send_data/send_command
+                if matches!(message.message_type, MessageType::Received) {
+                    let size = message.data.len();
+                    let _ = activity_sender.send(SessionActivity::data_received(
+                        format!("Received {} bytes", size),
+                        size,
+                    ));
+                } else if matches!(message.message_type, MessageType::Error) {
+                    let error = String::from_utf8_lossy(&message.data).into_owned();
+                    let _ = activity_sender.send(SessionActivity::error(error));
+                } else if let Some(event) = message.event_name() {
+                    let description = String::from_utf8_lossy(&message.data).into_owned();
+                    let activity = if event == DISCONNECTED_EVENT {
+                        SessionActivity::connection_lost(description)
+                    } else {
+                        SessionActivity::custom(event.to_string(), description)
+                    };
+                    let _ = activity_sender.send(activity);
+                }
+                
+                {
+                    let mut history = message_history.write().await;
+                    history.push_back(message);
+                    
+                    // Trim history if needed
+                    while history.len() > max_history_size {
+                        history.pop_front();
+                    }
+                }
+                
+                if disconnected {
+                    if let Some(ref reconnector) = reconnector {
+                        reconnector.run(&activity_sender).await;
+                    }
+                }
+            }
+        })
+    }
+    
     fn start_activity_processor(
         state: Arc<RwLock<SessionState>>,
         activity_history: Arc<RwLock<VecDeque<SessionActivity>>>,
@@ -400,23 +687,194 @@ impl Session {
     }
 }
 
+impl ResetRunner {
+    /// Run the named reset sequence and capture the device's output
+    ///
+    /// See `Session::run_reset_sequence`.
+    pub async fn run(self, name: &str) -> TermComResult<BootLog> {
+        self.ensure_running().await?;
+        let sequence = self.device_config.reset_sequence(name)?;
+        
+        // Subscribe first so that nothing sent during the reset is missed
+        let mut receiver = self.message_broadcast.subscribe();
+        let started_at = SystemTime::now();
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(sequence.timeout_ms);
+        
+        for step in &sequence.steps {
+            let lines = ControlLines { dtr: step.dtr, rts: step.rts };
+            if !lines.is_empty() {
+                self.set_control_lines(lines).await?;
+            }
+            if step.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(step.delay_ms)).await;
+            }
+        }
+        
+        let until = sequence.until.as_deref().map(str::as_bytes).filter(|until| !until.is_empty());
+        let mut output = Vec::new();
+        let mut matched = false;
+        
+        while !matched {
+            let message = match tokio::time::timeout_at(deadline.into(), receiver.recv()).await {
+                Ok(Ok(message)) => message,
+                Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                    warn!("Boot log for session '{}' skipped {} messages", self.session_name, skipped);
+                    continue;
+                }
+                Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break,
+            };
+            if !matches!(message.message_type, MessageType::Received) {
+                continue;
+            }
+            
+            // Only the new data and the tail before it can complete a match
+            let search_from = until.map_or(0, |until| output.len().saturating_sub(until.len() - 1));
+            output.extend_from_slice(&message.data);
+            matched = until.is_some_and(|until| output[search_from..].windows(until.len()).any(|window| window == until));
+        }
+        
+        let log = BootLog {
+            device_name: self.device_config.name.clone(),
+            sequence: sequence.name,
+            started_at,
+            duration: started.elapsed(),
+            until: sequence.until,
+            matched,
+            output,
+        };
+        
+        let result = match log.until {
+            Some(ref until) if log.matched => format!(", found '{}'", until),
+            Some(ref until) => format!(", did not find '{}'", until),
+            None => String::new(),
+        };
+        let _ = self.activity_sender.send(
+            SessionActivity::custom(
+                "reset_sequence".to_string(),
+                format!("Ran '{}': captured {} bytes{}", log.sequence, log.output.len(), result),
+            )
+            .with_duration(log.duration)
+        );
+        
+        info!("Session '{}' ran reset sequence '{}'", self.session_name, log.sequence);
+        Ok(log)
+    }
+    
+    async fn ensure_running(&self) -> TermComResult<()> {
+        if !*self.running.read().await {
+            return Err(TermComError::Session {
+                message: "Session is not running".to_string(),
+            });
+        }
+        Ok(())
+    }
+    
+    async fn set_control_lines(&self, lines: ControlLines) -> TermComResult<()> {
+        self.ensure_running().await?;
+        
+        let transport_session_id = self.transport_session_id.as_ref().ok_or_else(|| TermComError::Session {
+            message: "No active transport session".to_string(),
+        })?;
+        self.comm_engine.set_control_lines(transport_session_id, lines).await?;
+        let _ = self.activity_sender.send(SessionActivity::signal_changed(format!("Set {}", lines)));
+        
+        debug!("Session '{}' set {}", self.session_name, lines);
+        Ok(())
+    }
+}
+
+impl Reconnector {
+    /// Try to reconnect with exponential backoff until it works or the attempts run out
+    ///
+    /// Every attempt is recorded as an activity. A successful one records a
+    /// `Connected` activity, which makes the session active again.
+    async fn run(&self, activity_sender: &mpsc::UnboundedSender<SessionActivity>) {
+        let max_attempts = self.config.max_reconnect_attempts;
+        let of_max = if max_attempts > 0 { format!(" of {}", max_attempts) } else { String::new() };
+        
+        for attempt in 1.. {
+            tokio::time::sleep(self.config.reconnect_delay(attempt)).await;
+            
+            match self.comm_engine.reconnect(&self.transport_session_id).await {
+                Ok(()) => {
+                    let _ = activity_sender.send(SessionActivity::reconnect_attempt(
+                        format!("Reconnect attempt {}{} succeeded", attempt, of_max),
+                    ));
+                    let _ = activity_sender.send(SessionActivity::new(
+                        ActivityType::Connected,
+                        format!("Transport session re-established after {} attempt(s)", attempt),
+                    ));
+                    info!("Session '{}' reconnected on attempt {}", self.config.name, attempt);
+                    return;
+                }
+                Err(e) => {
+                    let _ = activity_sender.send(SessionActivity::reconnect_attempt(
+                        format!("Reconnect attempt {}{} failed: {}", attempt, of_max, e),
+                    ));
+                    debug!("Session '{}' reconnect attempt {} failed: {}", self.config.name, attempt, e);
+                    
+                    if max_attempts > 0 && attempt >= max_attempts {
+                        let _ = activity_sender.send(SessionActivity::error(
+                            format!("Gave up reconnecting after {} attempts", attempt),
+                        ));
+                        warn!("Session '{}' gave up reconnecting after {} attempts", self.config.name, attempt);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SessionConfig {
+    /// Delay before reconnection attempt `attempt`, counting from 1
+    ///
+    /// Starts at `reconnect_delay_ms` and doubles with every attempt, up to
+    /// [`MAX_RECONNECT_DELAY`].
+    pub fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+        Duration::from_millis(self.reconnect_delay_ms.saturating_mul(factor)).min(MAX_RECONNECT_DELAY)
+    }
+    
+    /// Create a session configuration from a device profile
+    ///
+    /// Reconnect behavior, tags and properties are taken from the profile;
+    /// everything else uses the defaults.
+    pub fn from_device(name: String, session_type: SessionType, device_config: DeviceConfig) -> Self {
+        Self {
+            name,
+            session_type,
+            auto_reconnect: device_config.auto_reconnect,
+            max_reconnect_attempts: device_config.max_reconnect_attempts,
+            reconnect_delay_ms: device_config.reconnect_delay_ms,
+            tags: device_config.tags.clone(),
+            properties: device_config.properties.clone(),
+            device_config,
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             name: "default_session".to_string(),
             session_type: SessionType::Interactive,
             device_config: DeviceConfig {
-                name: "default_device".to_string(),
                 description: "Default device".to_string(),
-                connection: crate::domain::config::ConnectionConfig::Serial {
-                    port: "/dev/ttyUSB0".to_string(),
-                    baud_rate: 9600,
-                    data_bits: 8,
-                    stop_bits: 1,
-                    parity: crate::domain::config::ParityConfig::None,
-                    flow_control: crate::domain::config::FlowControlConfig::None,
-                },
-                commands: Vec::new(),
+                ..DeviceConfig::new(
+                    "default_device",
+                    crate::domain::config::ConnectionConfig::Serial {
+                        port: "/dev/ttyUSB0".to_string(),
+                        baud_rate: 9600,
+                        data_bits: 8,
+                        stop_bits: 1,
+                        parity: crate::domain::config::ParityConfig::None,
+                        flow_control: crate::domain::config::FlowControlConfig::None,
+                        rs485: None,
+                    },
+                )
             },
             auto_reconnect: false,
             max_reconnect_attempts: 3,
@@ -451,17 +909,19 @@ mod tests {
             name: "test_session".to_string(),
             session_type: SessionType::Testing,
             device_config: DeviceConfig {
-                name: "test_device".to_string(),
                 description: "Test device".to_string(),
-                connection: ConnectionConfig::Serial {
-                    port: "/dev/null".to_string(),
-                    baud_rate: 9600,
-                    data_bits: 8,
-                    stop_bits: 1,
-                    parity: ParityConfig::None,
-                    flow_control: FlowControlConfig::None,
-                },
-                commands: Vec::new(),
+                ..DeviceConfig::new(
+                    "test_device",
+                    ConnectionConfig::Serial {
+                        port: "/dev/null".to_string(),
+                        baud_rate: 9600,
+                        data_bits: 8,
+                        stop_bits: 1,
+                        parity: ParityConfig::None,
+                        flow_control: FlowControlConfig::None,
+                        rs485: None,
+                    },
+                )
             },
             auto_reconnect: false,
             max_reconnect_attempts: 1,
@@ -497,6 +957,27 @@ mod tests {
         assert!(matches!(state.status, SessionStatus::Initializing));
     }
     
+    #[test]
+    fn test_config_from_device() {
+        let mut device_config = create_test_config().device_config;
+        device_config.auto_reconnect = true;
+        device_config.max_reconnect_attempts = 0;
+        device_config.reconnect_delay_ms = 250;
+        device_config.tags.push("lab".to_string());
+        device_config.properties.insert("firmware".to_string(), "1.2.0".to_string());
+        
+        let config = SessionConfig::from_device("board".to_string(), SessionType::Testing, device_config);
+        
+        assert_eq!(config.name, "board");
+        assert!(matches!(config.session_type, SessionType::Testing));
+        assert!(config.auto_reconnect);
+        assert_eq!(config.max_reconnect_attempts, 0);
+        assert_eq!(config.reconnect_delay_ms, 250);
+        assert_eq!(config.tags, vec!["lab".to_string()]);
+        assert_eq!(config.properties.get("firmware"), Some(&"1.2.0".to_string()));
+        assert_eq!(config.device_config.name, "test_device");
+    }
+    
     #[tokio::test]
     async fn test_session_lifecycle() {
         let mut session = create_test_session().await;
@@ -552,6 +1033,94 @@ mod tests {
         assert!(activities.is_empty());
     }
     
+    #[tokio::test]
+    async fn test_tcp_server_peer_events_become_activities() {
+        use crate::core::session::state::ActivityType;
+        
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut config = create_test_config();
+        config.device_config.connection = ConnectionConfig::TcpServer {
+            bind: "127.0.0.1".to_string(),
+            port,
+            max_clients: None,
+        };
+        
+        let comm_engine = Arc::new(CommunicationEngine::new(1000, 10));
+        comm_engine.start().await.unwrap();
+        let mut session = Session::new(config, Arc::clone(&comm_engine)).await.unwrap();
+        session.start().await.unwrap();
+        
+        let client = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let peer = client.local_addr().unwrap().to_string();
+        drop(client);
+        
+        let mut names = Vec::new();
+        for _ in 0..40 {
+            names = session.get_activity_history().await
+                .into_iter()
+                .filter_map(|activity| match activity.activity_type {
+                    ActivityType::Custom(name) => Some((name, activity.description)),
+                    _ => None,
+                })
+                .collect();
+            if names.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(25)).await;
+        }
+        
+        assert_eq!(names, vec![
+            ("peer_connected".to_string(), format!("Client {} connected", peer)),
+            ("peer_disconnected".to_string(), format!("Client {} disconnected", peer)),
+        ]);
+        
+        session.stop().await.unwrap();
+        comm_engine.stop().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_process_exit_disconnects_session() {
+        use crate::core::session::state::ActivityType;
+        
+        let mut config = create_test_config();
+        config.device_config.connection = ConnectionConfig::Process {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "read line; echo \"got $line\"; exit 4".to_string()],
+            env: std::collections::HashMap::new(),
+            cwd: None,
+        };
+        
+        let comm_engine = Arc::new(CommunicationEngine::new(1000, 10));
+        comm_engine.start().await.unwrap();
+        let mut session = Session::new(config, Arc::clone(&comm_engine)).await.unwrap();
+        session.start().await.unwrap();
+        session.send_data(b"ping\n".to_vec()).await.unwrap();
+        
+        let mut lost = None;
+        for _ in 0..40 {
+            lost = session.get_activity_history().await
+                .into_iter()
+                .find(|activity| matches!(activity.activity_type, ActivityType::ConnectionLost));
+            if lost.is_some() {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(25)).await;
+        }
+        
+        assert_eq!(lost.unwrap().description, "Process exited with code 4");
+        assert_eq!(session.get_state().await.status, SessionStatus::Disconnected);
+        
+        let received: Vec<u8> = session.get_message_history().await
+            .into_iter()
+            .filter(|message| matches!(message.message_type, MessageType::Received))
+            .flat_map(|message| message.data)
+            .collect();
+        assert_eq!(received, b"got ping\n");
+        
+        comm_engine.stop().await.unwrap();
+    }
+    
     #[tokio::test]
     async fn test_configuration_update() {
         let mut session = create_test_session().await;
@@ -585,4 +1154,14 @@ mod tests {
         assert!(!config.auto_reconnect);
         assert_eq!(config.max_history_size, 1000);
     }
+    
+    #[test]
+    fn test_reconnect_backoff() {
+        let config = SessionConfig { reconnect_delay_ms: 500, ..SessionConfig::default() };
+        assert_eq!(config.reconnect_delay(1), Duration::from_millis(500));
+        assert_eq!(config.reconnect_delay(2), Duration::from_secs(1));
+        assert_eq!(config.reconnect_delay(4), Duration::from_secs(4));
+        assert_eq!(config.reconnect_delay(7), MAX_RECONNECT_DELAY);
+        assert_eq!(config.reconnect_delay(u32::MAX), MAX_RECONNECT_DELAY);
+    }
 }
\ No newline at end of file
@@ -51,6 +51,9 @@ pub struct SessionStatistics {
     pub messages_received: u64,
     /// Number of errors
     pub error_count: u64,
+    /// Messages discarded because a queue was full
+    #[serde(default)]
+    pub messages_dropped: u64,
     /// Average response time in milliseconds
     pub avg_response_time_ms: f64,
     /// Last response time in milliseconds
@@ -110,6 +113,10 @@ pub enum ActivityType {
     Closed,
     /// Error occurred
     Error,
+    /// Serial control lines were changed or a break was sent
+    SignalChanged,
+    /// An attempt was made to restore a lost connection
+    ReconnectAttempt,
     /// Custom activity
     Custom(String),
 }
@@ -163,6 +170,12 @@ impl SessionState {
             ActivityType::Error => {
                 self.statistics.error_count += 1;
             }
+            ActivityType::ConnectionLost if self.status == SessionStatus::Active => {
+                self.status = SessionStatus::Disconnected;
+            }
+            ActivityType::Connected if self.status == SessionStatus::Disconnected => {
+                self.status = SessionStatus::Active;
+            }
             ActivityType::ResponseReceived => {
                 self.statistics.messages_received += 1;
                 if let Some(duration) = activity.duration {
@@ -255,6 +268,7 @@ impl Default for SessionStatistics {
             messages_sent: 0,
             messages_received: 0,
             error_count: 0,
+            messages_dropped: 0,
             avg_response_time_ms: 0.0,
             last_response_time_ms: None,
             uptime: Duration::new(0, 0),
@@ -311,6 +325,21 @@ impl SessionActivity {
         Self::new(ActivityType::Error, format!("Error: {}", error_message))
     }
     
+    /// Create a signal changed activity
+    pub fn signal_changed(description: String) -> Self {
+        Self::new(ActivityType::SignalChanged, description)
+    }
+    
+    /// Create a connection lost activity
+    pub fn connection_lost(description: String) -> Self {
+        Self::new(ActivityType::ConnectionLost, description)
+    }
+    
+    /// Create a reconnect attempt activity
+    pub fn reconnect_attempt(description: String) -> Self {
+        Self::new(ActivityType::ReconnectAttempt, description)
+    }
+    
     /// Create a custom activity
     pub fn custom(activity_name: String, description: String) -> Self {
         Self::new(ActivityType::Custom(activity_name), description)
@@ -342,6 +371,8 @@ impl std::fmt::Display for ActivityType {
             ActivityType::ConnectionLost => write!(f, "Connection Lost"),
             ActivityType::Closed => write!(f, "Closed"),
             ActivityType::Error => write!(f, "Error"),
+            ActivityType::SignalChanged => write!(f, "Signal Changed"),
+            ActivityType::ReconnectAttempt => write!(f, "Reconnect Attempt"),
             ActivityType::Custom(name) => write!(f, "{}", name),
         }
     }
@@ -363,8 +394,8 @@ mod tests {
         assert_eq!(state.device_name, "test_device");
         assert_eq!(state.metadata.transport_type, "serial");
         assert!(matches!(state.status, SessionStatus::Initializing));
-        assert!(state.is_active() == false);
-        assert!(state.is_closed() == false);
+        assert!(!state.is_active());
+        assert!(!state.is_closed());
     }
     
     #[test]
@@ -416,6 +447,41 @@ mod tests {
         assert_eq!(state.statistics.error_count, 1);
     }
     
+    #[test]
+    fn test_connection_lost_disconnects_active_session() {
+        let mut state = SessionState::new(
+            "test_session".to_string(),
+            "test_device".to_string(),
+            "process".to_string(),
+        );
+        
+        state.update_status(SessionStatus::Active);
+        state.record_activity(SessionActivity::connection_lost("Process exited with code 1".to_string()));
+        assert_eq!(state.status, SessionStatus::Disconnected);
+        
+        state.update_status(SessionStatus::Closed);
+        state.record_activity(SessionActivity::connection_lost("Late report".to_string()));
+        assert!(state.is_closed());
+    }
+    
+    #[test]
+    fn test_reconnect_reactivates_disconnected_session() {
+        let mut state = SessionState::new(
+            "test_session".to_string(),
+            "test_device".to_string(),
+            "serial".to_string(),
+        );
+        
+        state.update_status(SessionStatus::Active);
+        state.record_activity(SessionActivity::connection_lost("Lost serial port /dev/ttyUSB0".to_string()));
+        state.record_activity(SessionActivity::reconnect_attempt("Reconnect attempt 1 of 3 failed".to_string()));
+        assert_eq!(state.status, SessionStatus::Disconnected);
+        
+        state.record_activity(SessionActivity::new(ActivityType::Connected, "Reconnected".to_string()));
+        assert!(state.is_active());
+        assert_eq!(ActivityType::ReconnectAttempt.to_string(), "Reconnect Attempt");
+    }
+    
     #[test]
     fn test_response_time_tracking() {
         let mut state = SessionState::new(
@@ -0,0 +1,144 @@
+use crate::domain::error::{TermComError, TermComResult};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Format a timestamp as RFC 3339 UTC with millisecond precision
+pub fn format_millis(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{}.{:03}Z", format_seconds(since_epoch.as_secs()), since_epoch.subsec_millis())
+}
+
+/// Format a timestamp as RFC 3339 UTC with nanosecond precision
+///
+/// The output parses back to the exact same `SystemTime` with [`parse`].
+pub fn format_nanos(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{}.{:09}Z", format_seconds(since_epoch.as_secs()), since_epoch.subsec_nanos())
+}
+
+/// Parse an RFC 3339 UTC timestamp such as `2024-02-29T23:59:59.250Z`
+///
+/// Fractional seconds are optional and may have up to nine digits.
+pub fn parse(value: &str) -> TermComResult<SystemTime> {
+    let invalid = || TermComError::InvalidData(format!("Invalid timestamp '{}'", value));
+    let (date, time) = value
+        .trim()
+        .strip_suffix('Z')
+        .and_then(|rest| rest.split_once('T'))
+        .ok_or_else(invalid)?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = next_number(&mut date_parts).ok_or_else(invalid)?;
+    let month: u32 = next_number(&mut date_parts).ok_or_else(invalid)?;
+    let day: u32 = next_number(&mut date_parts).ok_or_else(invalid)?;
+
+    let (clock, fraction) = match time.split_once('.') {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (time, None),
+    };
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: u64 = next_number(&mut clock_parts).ok_or_else(invalid)?;
+    let minute: u64 = next_number(&mut clock_parts).ok_or_else(invalid)?;
+    let second: u64 = next_number(&mut clock_parts).ok_or_else(invalid)?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return Err(invalid());
+    }
+
+    let nanos = match fraction {
+        Some(digits) if !digits.is_empty() && digits.len() <= 9 && digits.bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{:0<9}", digits).parse::<u32>().map_err(|_| invalid())?
+        }
+        Some(_) => return Err(invalid()),
+        None => 0,
+    };
+
+    let days = u64::try_from(days_from_civil(year, month, day)).map_err(|_| invalid())?;
+    let seconds = days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+
+    Ok(UNIX_EPOCH + Duration::new(seconds, nanos))
+}
+
+fn next_number<'a, T: std::str::FromStr>(parts: &mut impl Iterator<Item = &'a str>) -> Option<T> {
+    parts.next().and_then(|part| part.parse().ok())
+}
+
+fn format_seconds(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / SECONDS_PER_DAY) as i64);
+    let seconds_of_day = secs % SECONDS_PER_DAY;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Convert days since the Unix epoch into a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Convert a (year, month, day) date into days since the Unix epoch
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_millis(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+
+        let time = UNIX_EPOCH + Duration::new(1_709_251_199, 250_000_001);
+        assert_eq!(format_millis(time), "2024-02-29T23:59:59.250Z");
+        assert_eq!(format_nanos(time), "2024-02-29T23:59:59.250000001Z");
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let time = UNIX_EPOCH + Duration::new(1_709_251_199, 250_000_001);
+        assert_eq!(parse(&format_nanos(time)).unwrap(), time);
+        assert_eq!(
+            parse("2024-02-29T23:59:59.25Z").unwrap(),
+            UNIX_EPOCH + Duration::from_millis(1_709_251_199_250)
+        );
+        assert_eq!(parse("1970-01-02T00:00:00Z").unwrap(), UNIX_EPOCH + Duration::from_secs(86_400));
+
+        let now = SystemTime::now();
+        assert_eq!(parse(&format_nanos(now)).unwrap(), now);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("2024-02-29 23:59:59Z").is_err());
+        assert!(parse("2024-13-01T00:00:00Z").is_err());
+        assert!(parse("2024-01-01T00:00:00.1234567890Z").is_err());
+        assert!(parse("1969-12-31T23:59:59Z").is_err());
+    }
+}
@@ -0,0 +1,515 @@
+use crate::core::communication::{ControlLines, Message, ModemStatus};
+use crate::core::session::{
+    manager::GlobalStatistics, state::SessionActivity, BootLog, SessionConfig, SessionState, SessionSummary,
+};
+use crate::daemon::protocol::{DaemonRequest, DaemonResponse};
+use crate::domain::{config::{QueueConfig, SerialSettings}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, QueueReceiver};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tracing::warn;
+
+/// Client for the daemon control socket
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    socket_path: PathBuf,
+    timeout: Duration,
+}
+
+impl DaemonClient {
+    /// Create a client for the given socket path
+    pub fn new(socket_path: impl Into<PathBuf>) -> Self {
+        Self {
+            socket_path: socket_path.into(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Set the request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get the control socket path
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Connect to a running daemon, returning `None` if none is reachable
+    pub async fn connect(socket_path: impl Into<PathBuf>) -> Option<Self> {
+        let client = Self::new(socket_path);
+        if client.is_running().await {
+            Some(client)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether a daemon answers on the socket
+    pub async fn is_running(&self) -> bool {
+        self.socket_path.exists() && self.ping().await.is_ok()
+    }
+
+    /// Send a request and wait for the response
+    ///
+    /// Error responses from the daemon are returned as `Err`.
+    pub async fn request(&self, request: &DaemonRequest) -> TermComResult<DaemonResponse> {
+        self.request_with_timeout(request, self.timeout).await
+    }
+
+    /// Send a request and wait up to `timeout` for the response
+    ///
+    /// For requests that take a known time on the daemon side, such as a
+    /// reset sequence or a break.
+    pub async fn request_with_timeout(&self, request: &DaemonRequest, timeout: Duration) -> TermComResult<DaemonResponse> {
+        let exchange = async {
+            let stream = UnixStream::connect(&self.socket_path).await.map_err(|e| {
+                TermComError::Communication {
+                    message: format!("Failed to connect to daemon at {}: {}", self.socket_path.display(), e),
+                }
+            })?;
+            let (reader, mut writer) = stream.into_split();
+
+            writer.write_all(request.to_line()?.as_bytes()).await?;
+            writer.flush().await?;
+
+            let mut line = String::new();
+            let read = BufReader::new(reader).read_line(&mut line).await?;
+            if read == 0 {
+                return Err(TermComError::Protocol("Daemon closed the connection".to_string()));
+            }
+
+            DaemonResponse::from_line(&line)
+        };
+
+        match tokio::time::timeout(timeout, exchange).await {
+            Ok(Ok(DaemonResponse::Error { message })) => Err(TermComError::Session { message }),
+            Ok(result) => result,
+            Err(_) => Err(TermComError::Timeout),
+        }
+    }
+
+    /// Ping the daemon, returning its process ID
+    pub async fn ping(&self) -> TermComResult<u32> {
+        match self.request(&DaemonRequest::Ping).await? {
+            DaemonResponse::Pong { pid, .. } => Ok(pid),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Create a session, optionally starting it
+    pub async fn create_session(&self, config: SessionConfig, start: bool) -> TermComResult<String> {
+        match self.request(&DaemonRequest::CreateSession { config: Box::new(config), start }).await? {
+            DaemonResponse::SessionCreated { session_id } => Ok(session_id),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Start a session
+    pub async fn start_session(&self, session: &str) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::StartSession { session: session.to_string() }).await
+    }
+
+    /// Stop a session
+    pub async fn stop_session(&self, session: &str) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::StopSession { session: session.to_string() }).await
+    }
+
+    /// Remove a session
+    pub async fn remove_session(&self, session: &str) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::RemoveSession { session: session.to_string() }).await
+    }
+
+    /// Send data through a session
+    pub async fn send_data(&self, session: &str, data: Vec<u8>) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::SendData { session: session.to_string(), data }).await
+    }
+
+    /// Send raw data to one peer of a listening session
+    pub async fn send_to_peer(&self, session: &str, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::SendToPeer {
+            session: session.to_string(),
+            peer: peer.to_string(),
+            data,
+        })
+        .await
+    }
+
+    /// List the peers connected to a listening session
+    pub async fn list_peers(&self, session: &str) -> TermComResult<Vec<String>> {
+        match self.request(&DaemonRequest::ListPeers { session: session.to_string() }).await? {
+            DaemonResponse::Peers { peers } => Ok(peers),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Drive the DTR and RTS lines of a serial session
+    pub async fn set_control_lines(&self, session: &str, lines: ControlLines) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::SetControlLines { session: session.to_string(), lines }).await
+    }
+
+    /// Read the modem status lines of a serial session
+    pub async fn modem_status(&self, session: &str) -> TermComResult<ModemStatus> {
+        match self.request(&DaemonRequest::ModemStatus { session: session.to_string() }).await? {
+            DaemonResponse::ModemStatus { status } => Ok(status),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Send a break condition on a serial session
+    pub async fn send_break(&self, session: &str, duration: Duration) -> TermComResult<()> {
+        let request = DaemonRequest::SendBreak {
+            session: session.to_string(),
+            duration_ms: duration.as_millis() as u64,
+        };
+        match self.request_with_timeout(&request, self.timeout + duration).await? {
+            DaemonResponse::Ok => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Change the line settings of a serial session
+    pub async fn set_serial_settings(&self, session: &str, settings: SerialSettings) -> TermComResult<SerialSettings> {
+        let request = DaemonRequest::SetSerialSettings { session: session.to_string(), settings };
+        match self.request(&request).await? {
+            DaemonResponse::SerialSettings { settings } => Ok(settings),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Run a reset sequence on a session and capture its boot log
+    ///
+    /// The sequence is looked up in the session's device configuration first,
+    /// so the response is awaited for as long as the sequence can take.
+    pub async fn run_reset_sequence(&self, session: &str, sequence: &str) -> TermComResult<BootLog> {
+        let duration = self.session_config(session).await?.device_config.reset_sequence(sequence)?.duration();
+        let request = DaemonRequest::RunResetSequence {
+            session: session.to_string(),
+            sequence: sequence.to_string(),
+        };
+        match self.request_with_timeout(&request, self.timeout + duration).await? {
+            DaemonResponse::BootLog { log } => Ok(*log),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// List all sessions
+    pub async fn list_sessions(&self) -> TermComResult<Vec<SessionSummary>> {
+        match self.request(&DaemonRequest::ListSessions).await? {
+            DaemonResponse::Sessions { sessions } => Ok(sessions),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Get session state
+    pub async fn session_state(&self, session: &str) -> TermComResult<SessionState> {
+        match self.request(&DaemonRequest::SessionState { session: session.to_string() }).await? {
+            DaemonResponse::State { state } => Ok(*state),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Get the configuration of a session
+    pub async fn session_config(&self, session: &str) -> TermComResult<SessionConfig> {
+        match self.request(&DaemonRequest::SessionConfig { session: session.to_string() }).await? {
+            DaemonResponse::Config { config } => Ok(*config),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Get session message history
+    pub async fn message_history(&self, session: &str) -> TermComResult<Vec<Message>> {
+        match self.request(&DaemonRequest::MessageHistory { session: session.to_string() }).await? {
+            DaemonResponse::Messages { messages } => Ok(messages),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Get session activity history
+    pub async fn activity_history(&self, session: &str) -> TermComResult<Vec<SessionActivity>> {
+        match self.request(&DaemonRequest::ActivityHistory { session: session.to_string() }).await? {
+            DaemonResponse::Activities { activities } => Ok(activities),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Get global statistics
+    pub async fn statistics(&self) -> TermComResult<GlobalStatistics> {
+        match self.request(&DaemonRequest::Statistics).await? {
+            DaemonResponse::Statistics { statistics } => Ok(statistics),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Subscribe to live messages of a session
+    ///
+    /// Messages are delivered until the session is removed, the daemon
+    /// exits or the returned receiver is dropped. They are buffered according
+    /// to `queue`, and messages the daemon skipped count as dropped.
+    pub async fn subscribe(&self, session: &str, queue: QueueConfig) -> TermComResult<QueueReceiver<Message>> {
+        let request = DaemonRequest::Subscribe { session: session.to_string() };
+        let handshake = async {
+            let stream = UnixStream::connect(&self.socket_path).await.map_err(|e| {
+                TermComError::Communication {
+                    message: format!("Failed to connect to daemon at {}: {}", self.socket_path.display(), e),
+                }
+            })?;
+            let (reader, mut writer) = stream.into_split();
+
+            writer.write_all(request.to_line()?.as_bytes()).await?;
+            writer.flush().await?;
+
+            let mut lines = BufReader::new(reader).lines();
+            match lines.next_line().await? {
+                Some(line) => Ok((DaemonResponse::from_line(&line)?, lines, writer)),
+                None => Err(TermComError::Protocol("Daemon closed the connection".to_string())),
+            }
+        };
+
+        let (response, mut lines, writer) = match tokio::time::timeout(self.timeout, handshake).await {
+            Ok(result) => result?,
+            Err(_) => return Err(TermComError::Timeout),
+        };
+
+        match response {
+            DaemonResponse::Subscribed { .. } => {}
+            DaemonResponse::Error { message } => return Err(TermComError::Session { message }),
+            other => return Err(unexpected(other)),
+        }
+
+        let (sender, receiver) = queue::bounded(queue);
+        tokio::spawn(async move {
+            // Keep the write half open; closing it ends the subscription
+            let _writer = writer;
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                match DaemonResponse::from_line(&line) {
+                    Ok(DaemonResponse::Message { message }) => {
+                        if sender.send(*message).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(DaemonResponse::Lagged { skipped }) => {
+                        warn!("Subscription fell behind, skipped {} messages", skipped);
+                        sender.add_dropped(skipped);
+                    }
+                    Ok(other) => warn!("Unexpected daemon response on subscription: {:?}", other),
+                    Err(e) => warn!("{}", e),
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+
+    /// Ask the daemon to shut down
+    pub async fn shutdown(&self) -> TermComResult<()> {
+        self.expect_ok(DaemonRequest::Shutdown).await
+    }
+
+    async fn expect_ok(&self, request: DaemonRequest) -> TermComResult<()> {
+        match self.request(&request).await? {
+            DaemonResponse::Ok => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+}
+
+fn unexpected(response: DaemonResponse) -> TermComError {
+    TermComError::Protocol(format!("Unexpected daemon response: {:?}", response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::server::DaemonServer;
+    use crate::domain::config::{ConnectionConfig, DeviceConfig, QueueConfig};
+
+    fn create_tcp_session_config(name: &str, port: u16) -> SessionConfig {
+        SessionConfig {
+            name: name.to_string(),
+            device_config: DeviceConfig {
+                description: "Daemon test device".to_string(),
+                ..DeviceConfig::new(
+                    format!("{}-device", name),
+                    ConnectionConfig::Tcp {
+                        host: "127.0.0.1".to_string(),
+                        port,
+                        timeout_ms: 1000,
+                        keep_alive: false,
+                    },
+                )
+            },
+            ..SessionConfig::default()
+        }
+    }
+
+    async fn next_message(receiver: &mut QueueReceiver<Message>) -> Message {
+        tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .expect("message should arrive")
+            .expect("subscription should be open")
+    }
+
+    #[tokio::test]
+    async fn test_client_without_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = DaemonClient::new(dir.path().join("missing.sock"));
+
+        assert!(!client.is_running().await);
+        assert!(client.ping().await.is_err());
+        assert!(DaemonClient::connect(dir.path().join("missing.sock")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_daemon_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("run").join("termcom.sock");
+
+        let server = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.unwrap();
+        let server_task = tokio::spawn(server.run());
+
+        let client = DaemonClient::connect(&socket_path).await.expect("daemon should be reachable");
+        assert_eq!(client.ping().await.unwrap(), std::process::id());
+
+        // Sessions created through one request are visible to the next
+        let session_id = client
+            .create_session(create_tcp_session_config("persistent", 9), false)
+            .await
+            .unwrap();
+        let sessions = client.list_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, session_id);
+
+        // Sessions can be addressed by name as well as ID
+        let state = client.session_state("persistent").await.unwrap();
+        assert_eq!(state.session_id, session_id);
+        assert!(client.activity_history(&session_id).await.is_ok());
+
+        // Errors are reported back to the client
+        assert!(client.send_data("persistent", b"data".to_vec()).await.is_err());
+        assert!(client.session_state("missing").await.is_err());
+
+        client.remove_session("persistent").await.unwrap();
+        assert!(client.list_sessions().await.unwrap().is_empty());
+
+        client.shutdown().await.unwrap();
+        server_task.await.unwrap().unwrap();
+        assert!(!socket_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_reset_sequence_outlasts_request_timeout() {
+        use crate::domain::config::ResetSequence;
+        use tokio::net::TcpListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("run").join("termcom.sock");
+        let server = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.unwrap();
+        let server_task = tokio::spawn(server.run());
+        let client = DaemonClient::new(&socket_path).with_timeout(Duration::from_millis(200));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = create_tcp_session_config("slow-boot", listener.local_addr().unwrap().port());
+        config.device_config.reset_sequences.push(ResetSequence {
+            name: "slow".to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+            until: None,
+            timeout_ms: 400,
+        });
+        client.create_session(config, true).await.unwrap();
+        let (_peer, _) = listener.accept().await.unwrap();
+
+        // The capture runs twice as long as the request timeout
+        let log = client.run_reset_sequence("slow-boot", "slow").await.unwrap();
+        assert_eq!(log.sequence, "slow");
+        assert!(client.run_reset_sequence("slow-boot", "missing").await.is_err());
+
+        client.shutdown().await.unwrap();
+        server_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_messages() {
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("run").join("termcom.sock");
+        let server = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.unwrap();
+        let server_task = tokio::spawn(server.run());
+        let client = DaemonClient::new(&socket_path);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        client
+            .create_session(create_tcp_session_config("monitored", port), true)
+            .await
+            .unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        assert!(client.subscribe("missing", QueueConfig::default()).await.is_err());
+        let mut messages = client.subscribe("monitored", QueueConfig::default()).await.unwrap();
+
+        client.send_data("monitored", b"PING".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 4];
+        peer.read_exact(&mut buffer).await.unwrap();
+        peer.write_all(b"PONG").await.unwrap();
+
+        let sent = next_message(&mut messages).await;
+        assert!(matches!(sent.message_type, crate::core::communication::message::MessageType::Sent));
+        assert_eq!(sent.data, b"PING");
+
+        let mut received = Vec::new();
+        while received.len() < 4 {
+            received.extend(next_message(&mut messages).await.data);
+        }
+        assert_eq!(received, b"PONG");
+
+        client.shutdown().await.unwrap();
+        server_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_rejects_running_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("run").join("termcom.sock");
+
+        let server = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.unwrap();
+        let shutdown = server.shutdown_handle();
+        let server_task = tokio::spawn(server.run());
+
+        assert!(DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.is_err());
+
+        shutdown.send(()).await.unwrap();
+        server_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_restricts_socket_access() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_dir = dir.path().join("run").join("termcom");
+        let socket_path = socket_dir.join("termcom.sock");
+
+        let server = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.unwrap();
+        let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&socket_dir), 0o700);
+        assert_eq!(mode(&socket_path), 0o600);
+
+        let shutdown = server.shutdown_handle();
+        let server_task = tokio::spawn(server.run());
+        shutdown.send(()).await.unwrap();
+        server_task.await.unwrap().unwrap();
+
+        // A directory others can look into is not used
+        std::fs::set_permissions(&socket_dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let error = DaemonServer::bind(&socket_path, 100, 5, QueueConfig::default()).await.err().unwrap();
+        assert!(error.to_string().contains("accessible to other users"));
+    }
+}
@@ -0,0 +1,29 @@
+// Daemon module - Long-running session host with a Unix socket control channel
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+pub use client::DaemonClient;
+pub use protocol::{DaemonRequest, DaemonResponse};
+pub use server::DaemonServer;
+
+use std::path::PathBuf;
+
+/// Environment variable overriding the daemon socket path
+pub const SOCKET_ENV_VAR: &str = "TERMCOM_SOCKET";
+
+/// Get the default daemon socket path
+///
+/// Uses `$TERMCOM_SOCKET` if set, then the user runtime directory, and
+/// falls back to the configuration directory.
+pub fn default_socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_ENV_VAR) {
+        return PathBuf::from(path);
+    }
+
+    dirs::runtime_dir()
+        .or_else(dirs::config_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("termcom")
+        .join("termcom.sock")
+}
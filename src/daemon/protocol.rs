@@ -0,0 +1,227 @@
+use crate::core::communication::{ControlLines, Message, ModemStatus};
+use crate::core::session::{
+    manager::GlobalStatistics, state::SessionActivity, BootLog, SessionConfig, SessionState, SessionSummary,
+};
+use crate::domain::{config::SerialSettings, error::{TermComError, TermComResult}};
+use serde::{Deserialize, Serialize};
+
+/// Request sent from a CLI client to the daemon
+///
+/// Requests are encoded as a single line of JSON. Session references accept
+/// either a session ID or a session name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    /// Check that the daemon is alive
+    Ping,
+    /// Create a session, optionally starting it right away
+    CreateSession { config: Box<SessionConfig>, start: bool },
+    /// Start an existing session
+    StartSession { session: String },
+    /// Stop a running session
+    StopSession { session: String },
+    /// Remove a session
+    RemoveSession { session: String },
+    /// Send raw data through a session
+    SendData { session: String, data: Vec<u8> },
+    /// Send raw data to one peer of a listening session
+    SendToPeer { session: String, peer: String, data: Vec<u8> },
+    /// List the peers connected to a listening session
+    ListPeers { session: String },
+    /// Drive the DTR and RTS lines of a serial session
+    SetControlLines { session: String, lines: ControlLines },
+    /// Read the modem status lines of a serial session
+    ModemStatus { session: String },
+    /// Send a break condition on a serial session
+    SendBreak { session: String, duration_ms: u64 },
+    /// Change the line settings of a serial session
+    SetSerialSettings { session: String, settings: SerialSettings },
+    /// Run a reset sequence on a session and capture its boot log
+    RunResetSequence { session: String, sequence: String },
+    /// List all sessions
+    ListSessions,
+    /// Get the state of a session
+    SessionState { session: String },
+    /// Get the configuration of a session
+    SessionConfig { session: String },
+    /// Get the message history of a session
+    MessageHistory { session: String },
+    /// Get the activity history of a session
+    ActivityHistory { session: String },
+    /// Get statistics across all sessions
+    Statistics,
+    /// Stream messages of a session as they happen
+    ///
+    /// The daemon answers with `Subscribed` followed by one `Message` line
+    /// per message until the client disconnects or the session is removed.
+    Subscribe { session: String },
+    /// Stop all sessions and exit the daemon
+    Shutdown,
+}
+
+/// Response returned by the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum DaemonResponse {
+    /// Reply to a ping
+    Pong {
+        pid: u32,
+        version: String,
+        session_count: usize,
+    },
+    /// Session was created
+    SessionCreated { session_id: String },
+    /// Request completed without a payload
+    Ok,
+    /// Session list
+    Sessions { sessions: Vec<SessionSummary> },
+    /// Peers connected to a listening session
+    Peers { peers: Vec<String> },
+    /// Modem status lines of a serial session
+    ModemStatus { status: ModemStatus },
+    /// Line settings of a serial session
+    SerialSettings { settings: SerialSettings },
+    /// Output captured after a reset sequence
+    BootLog { log: Box<BootLog> },
+    /// Session state
+    State { state: Box<SessionState> },
+    /// Session configuration
+    Config { config: Box<SessionConfig> },
+    /// Session message history
+    Messages { messages: Vec<Message> },
+    /// Session activity history
+    Activities { activities: Vec<SessionActivity> },
+    /// Global statistics
+    Statistics { statistics: GlobalStatistics },
+    /// Subscription accepted
+    Subscribed { session_id: String },
+    /// Live message of a subscribed session
+    Message { message: Box<Message> },
+    /// Messages skipped because the subscriber fell behind
+    Lagged { skipped: u64 },
+    /// Request failed
+    Error { message: String },
+}
+
+impl DaemonRequest {
+    /// Encode the request as a newline-terminated JSON line
+    pub fn to_line(&self) -> TermComResult<String> {
+        encode_line(self)
+    }
+
+    /// Decode a request from a JSON line
+    pub fn from_line(line: &str) -> TermComResult<Self> {
+        decode_line(line)
+    }
+}
+
+impl DaemonResponse {
+    /// Encode the response as a newline-terminated JSON line
+    pub fn to_line(&self) -> TermComResult<String> {
+        encode_line(self)
+    }
+
+    /// Decode a response from a JSON line
+    pub fn from_line(line: &str) -> TermComResult<Self> {
+        decode_line(line)
+    }
+
+    /// Build an error response from a TermCom error
+    pub fn error(error: &TermComError) -> Self {
+        Self::Error {
+            message: error.to_string(),
+        }
+    }
+}
+
+fn encode_line<T: Serialize>(value: &T) -> TermComResult<String> {
+    let mut line = serde_json::to_string(value)
+        .map_err(|e| TermComError::Protocol(format!("Failed to encode daemon message: {}", e)))?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn decode_line<T: for<'de> Deserialize<'de>>(line: &str) -> TermComResult<T> {
+    serde_json::from_str(line.trim_end())
+        .map_err(|e| TermComError::Protocol(format!("Invalid daemon message: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = DaemonRequest::SendData {
+            session: "device-1".to_string(),
+            data: b"AT\r\n".to_vec(),
+        };
+
+        let line = request.to_line().unwrap();
+        assert!(line.ends_with('\n'));
+        assert!(line.contains("\"request\":\"send_data\""));
+
+        match DaemonRequest::from_line(&line).unwrap() {
+            DaemonRequest::SendData { session, data } => {
+                assert_eq!(session, "device-1");
+                assert_eq!(data, b"AT\r\n".to_vec());
+            }
+            other => panic!("Unexpected request: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_control_lines_round_trip() {
+        let request = DaemonRequest::SetControlLines {
+            session: "esp32".to_string(),
+            lines: ControlLines { dtr: Some(false), rts: None },
+        };
+
+        let line = request.to_line().unwrap();
+        assert!(line.contains("\"request\":\"set_control_lines\""));
+
+        match DaemonRequest::from_line(&line).unwrap() {
+            DaemonRequest::SetControlLines { session, lines } => {
+                assert_eq!(session, "esp32");
+                assert_eq!(lines, ControlLines::dtr(false));
+            }
+            other => panic!("Unexpected request: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_create_session_round_trip() {
+        let request = DaemonRequest::CreateSession {
+            config: Box::default(),
+            start: true,
+        };
+
+        let line = request.to_line().unwrap();
+        match DaemonRequest::from_line(&line).unwrap() {
+            DaemonRequest::CreateSession { config, start } => {
+                assert_eq!(config.name, "default_session");
+                assert!(start);
+            }
+            other => panic!("Unexpected request: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_response_round_trip() {
+        let response = DaemonResponse::Error {
+            message: "Session 'x' not found".to_string(),
+        };
+
+        let line = response.to_line().unwrap();
+        match DaemonResponse::from_line(&line).unwrap() {
+            DaemonResponse::Error { message } => assert_eq!(message, "Session 'x' not found"),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        assert!(DaemonRequest::from_line("not json").is_err());
+        assert!(DaemonRequest::from_line("{\"request\":\"unknown\"}").is_err());
+    }
+}
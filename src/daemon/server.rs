@@ -0,0 +1,401 @@
+use crate::core::communication::CommunicationEngine;
+use crate::core::session::SessionManager;
+use crate::daemon::protocol::{DaemonRequest, DaemonResponse};
+use crate::domain::{config::QueueConfig, error::{TermComError, TermComResult}};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info, warn};
+
+/// Daemon server owning the session manager
+///
+/// Listens on a Unix socket and serves [`DaemonRequest`]s until a
+/// shutdown request is received or the shutdown handle is triggered.
+pub struct DaemonServer {
+    listener: UnixListener,
+    socket_path: PathBuf,
+    comm_engine: Arc<CommunicationEngine>,
+    session_manager: Arc<SessionManager>,
+    shutdown_sender: mpsc::Sender<()>,
+    shutdown_receiver: mpsc::Receiver<()>,
+}
+
+impl DaemonServer {
+    /// Bind the daemon control socket
+    ///
+    /// A stale socket file left behind by a crashed daemon is removed; a
+    /// socket with a live daemon behind it is reported as an error.
+    ///
+    /// Anyone who can connect can run commands as the daemon user, so the
+    /// socket is only accessible to its owner and its directory must be
+    /// private to the owner as well.
+    pub async fn bind(
+        socket_path: &Path,
+        max_history_size: usize,
+        max_sessions: usize,
+        queue: QueueConfig,
+    ) -> TermComResult<Self> {
+        if let Some(parent) = socket_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            prepare_socket_dir(parent)?;
+        }
+
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).await.is_ok() {
+                return Err(TermComError::Session {
+                    message: format!("Daemon is already running on {}", socket_path.display()),
+                });
+            }
+
+            warn!("Removing stale daemon socket {}", socket_path.display());
+            std::fs::remove_file(socket_path)?;
+        }
+
+        let listener = UnixListener::bind(socket_path).map_err(|e| TermComError::Communication {
+            message: format!("Failed to bind daemon socket {}: {}", socket_path.display(), e),
+        })?;
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+
+        let comm_engine = Arc::new(
+            CommunicationEngine::builder()
+                .max_history_size(max_history_size)
+                .max_sessions_per_transport(max_sessions)
+                .queue(queue)
+                .build(),
+        );
+        let session_manager = Arc::new(SessionManager::new(Arc::clone(&comm_engine), max_sessions));
+        let (shutdown_sender, shutdown_receiver) = mpsc::channel(1);
+
+        info!("Daemon listening on {}", socket_path.display());
+
+        Ok(Self {
+            listener,
+            socket_path: socket_path.to_path_buf(),
+            comm_engine,
+            session_manager,
+            shutdown_sender,
+            shutdown_receiver,
+        })
+    }
+
+    /// Get the control socket path
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Get the session manager owned by the daemon
+    pub fn session_manager(&self) -> Arc<SessionManager> {
+        Arc::clone(&self.session_manager)
+    }
+
+    /// Get a handle that stops the daemon when sent to
+    pub fn shutdown_handle(&self) -> mpsc::Sender<()> {
+        self.shutdown_sender.clone()
+    }
+
+    /// Serve requests until shutdown
+    pub async fn run(mut self) -> TermComResult<()> {
+        self.comm_engine.start().await?;
+
+        loop {
+            tokio::select! {
+                accept_result = self.listener.accept() => {
+                    match accept_result {
+                        Ok((stream, _)) => {
+                            let session_manager = Arc::clone(&self.session_manager);
+                            let shutdown_sender = self.shutdown_sender.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = Self::handle_connection(stream, session_manager, shutdown_sender).await {
+                                    debug!("Daemon client connection ended with error: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            error!("Failed to accept daemon connection: {}", e);
+                        }
+                    }
+                }
+
+                _ = self.shutdown_receiver.recv() => {
+                    info!("Daemon shutting down");
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = self.session_manager.remove_all_sessions().await {
+            warn!("Failed to stop sessions during shutdown: {}", e);
+        }
+        self.comm_engine.stop().await?;
+
+        if let Err(e) = std::fs::remove_file(&self.socket_path) {
+            warn!("Failed to remove daemon socket {}: {}", self.socket_path.display(), e);
+        }
+
+        Ok(())
+    }
+
+    async fn handle_connection(
+        stream: UnixStream,
+        session_manager: Arc<SessionManager>,
+        shutdown_sender: mpsc::Sender<()>,
+    ) -> TermComResult<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (response, shutdown) = match DaemonRequest::from_line(&line) {
+                Ok(DaemonRequest::Subscribe { session }) => {
+                    // The connection is dedicated to the stream from here on
+                    return Self::stream_messages(&session_manager, &session, lines, writer).await;
+                }
+                Ok(DaemonRequest::Shutdown) => (DaemonResponse::Ok, true),
+                Ok(request) => (handle_request(&session_manager, request).await, false),
+                Err(e) => (DaemonResponse::error(&e), false),
+            };
+
+            writer.write_all(response.to_line()?.as_bytes()).await?;
+            writer.flush().await?;
+
+            if shutdown {
+                let _ = shutdown_sender.send(()).await;
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn stream_messages(
+        session_manager: &SessionManager,
+        session: &str,
+        mut lines: Lines<BufReader<OwnedReadHalf>>,
+        mut writer: OwnedWriteHalf,
+    ) -> TermComResult<()> {
+        let subscription = match resolve(session_manager, session).await {
+            Ok(session_id) => session_manager
+                .subscribe(&session_id)
+                .await
+                .map(|receiver| (session_id, receiver)),
+            Err(e) => Err(e),
+        };
+
+        let (session_id, mut receiver) = match subscription {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                writer.write_all(DaemonResponse::error(&e).to_line()?.as_bytes()).await?;
+                return Ok(());
+            }
+        };
+
+        writer
+            .write_all(DaemonResponse::Subscribed { session_id }.to_line()?.as_bytes())
+            .await?;
+        writer.flush().await?;
+
+        loop {
+            let response = tokio::select! {
+                result = receiver.recv() => match result {
+                    Ok(message) => DaemonResponse::Message { message: Box::new(message) },
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => DaemonResponse::Lagged { skipped },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+
+                // Anything other than more input means the client went away
+                line = lines.next_line() => match line {
+                    Ok(Some(_)) => continue,
+                    _ => break,
+                },
+            };
+
+            writer.write_all(response.to_line()?.as_bytes()).await?;
+            writer.flush().await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Create the socket directory accessible only to the current user
+///
+/// An existing directory is refused if another user owns it or if other
+/// users have any access to it.
+pub fn prepare_socket_dir(dir: &Path) -> TermComResult<()> {
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+    let metadata = std::fs::metadata(dir)?;
+    let uid = nix::unistd::geteuid().as_raw();
+    if metadata.uid() != uid {
+        return Err(TermComError::Session {
+            message: format!(
+                "Daemon socket directory {} is owned by user {}, not {}",
+                dir.display(),
+                metadata.uid(),
+                uid
+            ),
+        });
+    }
+
+    let mode = metadata.mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(TermComError::Session {
+            message: format!(
+                "Daemon socket directory {} is accessible to other users (mode {:o}); restrict it with `chmod 700`",
+                dir.display(),
+                mode
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Execute a single request against the session manager
+pub async fn handle_request(session_manager: &SessionManager, request: DaemonRequest) -> DaemonResponse {
+    match dispatch(session_manager, request).await {
+        Ok(response) => response,
+        Err(e) => DaemonResponse::error(&e),
+    }
+}
+
+async fn dispatch(session_manager: &SessionManager, request: DaemonRequest) -> TermComResult<DaemonResponse> {
+    match request {
+        DaemonRequest::Ping => Ok(DaemonResponse::Pong {
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            session_count: session_manager.get_session_count().await,
+        }),
+        DaemonRequest::CreateSession { config, start } => {
+            let session_id = session_manager.create_session(*config).await?;
+            if start {
+                if let Err(e) = session_manager.start_session(&session_id).await {
+                    // Do not keep a session around that the caller never got an ID for
+                    let _ = session_manager.remove_session(&session_id).await;
+                    return Err(e);
+                }
+            }
+            Ok(DaemonResponse::SessionCreated { session_id })
+        }
+        DaemonRequest::StartSession { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.start_session(&session_id).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::StopSession { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.stop_session(&session_id).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::RemoveSession { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.remove_session(&session_id).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::SendData { session, data } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.send_data(&session_id, data).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::SendToPeer { session, peer, data } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.send_to_peer(&session_id, &peer, data).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::ListPeers { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            Ok(DaemonResponse::Peers {
+                peers: session_manager.list_peers(&session_id).await?,
+            })
+        }
+        DaemonRequest::SetControlLines { session, lines } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.set_control_lines(&session_id, lines).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::ModemStatus { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            Ok(DaemonResponse::ModemStatus {
+                status: session_manager.modem_status(&session_id).await?,
+            })
+        }
+        DaemonRequest::SendBreak { session, duration_ms } => {
+            let session_id = resolve(session_manager, &session).await?;
+            session_manager.send_break(&session_id, Duration::from_millis(duration_ms)).await?;
+            Ok(DaemonResponse::Ok)
+        }
+        DaemonRequest::SetSerialSettings { session, settings } => {
+            let session_id = resolve(session_manager, &session).await?;
+            Ok(DaemonResponse::SerialSettings {
+                settings: session_manager.set_serial_settings(&session_id, settings).await?,
+            })
+        }
+        DaemonRequest::RunResetSequence { session, sequence } => {
+            let session_id = resolve(session_manager, &session).await?;
+            let log = session_manager.run_reset_sequence(&session_id, &sequence).await?;
+            Ok(DaemonResponse::BootLog { log: Box::new(log) })
+        }
+        DaemonRequest::ListSessions => Ok(DaemonResponse::Sessions {
+            sessions: session_manager.list_sessions().await,
+        }),
+        DaemonRequest::SessionState { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            let state = session_manager
+                .get_session_state(&session_id)
+                .await
+                .ok_or_else(|| not_found(&session))?;
+            Ok(DaemonResponse::State { state: Box::new(state) })
+        }
+        DaemonRequest::SessionConfig { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            let config = session_manager
+                .get_session_config(&session_id)
+                .await
+                .ok_or_else(|| not_found(&session))?;
+            Ok(DaemonResponse::Config { config: Box::new(config) })
+        }
+        DaemonRequest::MessageHistory { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            let messages = session_manager
+                .get_message_history(&session_id)
+                .await
+                .ok_or_else(|| not_found(&session))?;
+            Ok(DaemonResponse::Messages { messages })
+        }
+        DaemonRequest::ActivityHistory { session } => {
+            let session_id = resolve(session_manager, &session).await?;
+            let activities = session_manager
+                .get_activity_history(&session_id)
+                .await
+                .ok_or_else(|| not_found(&session))?;
+            Ok(DaemonResponse::Activities { activities })
+        }
+        DaemonRequest::Statistics => Ok(DaemonResponse::Statistics {
+            statistics: session_manager.get_global_statistics().await,
+        }),
+        DaemonRequest::Subscribe { .. } => Err(TermComError::Protocol(
+            "Subscriptions require a dedicated connection".to_string(),
+        )),
+        DaemonRequest::Shutdown => Ok(DaemonResponse::Ok),
+    }
+}
+
+async fn resolve(session_manager: &SessionManager, session: &str) -> TermComResult<String> {
+    session_manager
+        .resolve_session_id(session)
+        .await
+        .ok_or_else(|| not_found(session))
+}
+
+fn not_found(session: &str) -> TermComError {
+    TermComError::Session {
+        message: format!("Session '{}' not found", session),
+    }
+}
@@ -1,7 +1,9 @@
+use crate::domain::error::{TermComError, TermComResult};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// TermCom configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TermComConfig {
     /// Global configuration
     pub global: GlobalConfig,
@@ -27,6 +29,43 @@ pub struct GlobalConfig {
     /// History limit
     #[serde(default = "default_history_limit")]
     pub history_limit: usize,
+    /// Messages each queue between a device and its consumers can hold
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// What happens to messages when a consumer falls behind and a queue is full
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+}
+
+/// What a full message queue does with new messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for room, which stops reading from the device
+    #[default]
+    Block,
+    /// Discard the oldest queued message to make room
+    DropOldest,
+    /// Discard the new message
+    DropNewest,
+}
+
+/// Size and overflow behavior of a message queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// Messages the queue can hold
+    pub capacity: usize,
+    /// What happens when the queue is full
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_queue_capacity(),
+            overflow: OverflowPolicy::default(),
+        }
+    }
 }
 
 /// Device configuration
@@ -39,9 +78,101 @@ pub struct DeviceConfig {
     pub description: String,
     /// Connection type
     pub connection: ConnectionConfig,
+    /// How received data is split into messages and sent data is framed
+    #[serde(default, skip_serializing_if = "FramingConfig::is_raw")]
+    pub framing: FramingConfig,
     /// Custom commands
     #[serde(default)]
     pub commands: Vec<CustomCommand>,
+    /// Named DTR/RTS sequences that reset the board
+    #[serde(default)]
+    pub reset_sequences: Vec<ResetSequence>,
+    /// Reconnect sessions for this device when the connection drops
+    #[serde(default)]
+    pub auto_reconnect: bool,
+    /// Reconnection attempts before giving up (0 = keep trying)
+    #[serde(default = "default_max_reconnect_attempts")]
+    pub max_reconnect_attempts: u32,
+    /// Delay before the first reconnection attempt in milliseconds,
+    /// doubled for each further attempt
+    #[serde(default = "default_reconnect_delay")]
+    pub reconnect_delay_ms: u64,
+    /// Tags applied to sessions for this device
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Properties applied to sessions for this device
+    #[serde(default)]
+    pub properties: std::collections::HashMap<String, String>,
+}
+
+/// How a device's byte stream is split into frames
+///
+/// Received data is collected until a whole frame has arrived, and each
+/// frame becomes one message. Sent data is wrapped into a frame the same way.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FramingConfig {
+    /// Whatever one read returns becomes a message
+    #[default]
+    Raw,
+    /// Text lines; the line ending is removed from received lines
+    Line {
+        #[serde(default)]
+        ending: LineEnding,
+    },
+    /// Frames ending in a custom byte sequence
+    Delimiter {
+        delimiter: Vec<u8>,
+    },
+    /// Frames of a fixed number of bytes
+    FixedLength {
+        length: usize,
+    },
+    /// Frames whose payload length is stored in a header field
+    ///
+    /// The `offset` bytes before the length field stay part of the message;
+    /// the length field itself is removed and counts the bytes after it.
+    LengthPrefixed {
+        #[serde(default)]
+        offset: usize,
+        /// Size of the length field in bytes: 1, 2, 4 or 8
+        width: u8,
+        #[serde(default)]
+        endianness: Endianness,
+    },
+    /// SLIP as in RFC 1055
+    Slip,
+    /// Consistent Overhead Byte Stuffing, each frame ending in a zero byte
+    Cobs,
+    /// Frames between STX and ETX; those bytes and the escape byte are
+    /// preceded by the escape byte when they occur in the data
+    StxEtx {
+        #[serde(default = "default_stx")]
+        stx: u8,
+        #[serde(default = "default_etx")]
+        etx: u8,
+        #[serde(default = "default_escape")]
+        escape: u8,
+    },
+}
+
+/// Line ending used by line framing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Cr,
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// Byte order of a length field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
 }
 
 /// Connection configuration
@@ -60,6 +191,9 @@ pub enum ConnectionConfig {
         parity: ParityConfig,
         #[serde(default = "default_flow_control")]
         flow_control: FlowControlConfig,
+        /// RS-485 half-duplex operation; full duplex if unset
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rs485: Option<Rs485Config>,
     },
     #[serde(rename = "tcp")]
     Tcp {
@@ -70,10 +204,88 @@ pub enum ConnectionConfig {
         #[serde(default)]
         keep_alive: bool,
     },
+    #[serde(rename = "tcp_server")]
+    TcpServer {
+        /// Local address to listen on
+        #[serde(default = "default_tcp_server_bind")]
+        bind: String,
+        port: u16,
+        /// Maximum number of simultaneous clients (unlimited if unset)
+        #[serde(default)]
+        max_clients: Option<usize>,
+    },
+    #[serde(rename = "udp")]
+    Udp {
+        /// Local address to bind, e.g. `0.0.0.0:5000`
+        #[serde(default = "default_udp_bind")]
+        bind: String,
+        /// Default destination for sent datagrams
+        #[serde(default)]
+        remote: Option<String>,
+        /// Allow sending to broadcast addresses
+        #[serde(default)]
+        broadcast: bool,
+        /// Multicast group to join
+        #[serde(default)]
+        multicast: Option<String>,
+    },
+    #[serde(rename = "pty")]
+    Pty {
+        /// Existing pseudo-terminal to attach to; a new one is created if unset
+        #[serde(default)]
+        path: Option<String>,
+        /// Symlink to point at a newly created pseudo-terminal, like socat's `link=`
+        #[serde(default)]
+        link: Option<String>,
+    },
+    #[serde(rename = "process")]
+    Process {
+        /// Program to run
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Extra environment variables for the program
+        #[serde(default)]
+        env: std::collections::HashMap<String, String>,
+        /// Working directory (the current directory if unset)
+        #[serde(default)]
+        cwd: Option<String>,
+    },
+    #[serde(rename = "unix")]
+    Unix {
+        /// Socket path to connect to, or to create when listening
+        path: String,
+        #[serde(default)]
+        mode: UnixSocketMode,
+    },
+    /// Connection handled by a transport registered by a library user
+    #[serde(rename = "custom")]
+    Custom {
+        /// Name the transport was registered under
+        transport: String,
+        /// Transport specific settings, read with [`ConnectionConfig::custom_options`]
+        #[serde(default)]
+        options: toml::Table,
+    },
+}
+
+/// How a Unix domain socket device is reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnixSocketMode {
+    /// Connect to a listening stream socket
+    #[default]
+    Stream,
+    /// Create a stream socket and accept one client at a time
+    StreamListen,
+    /// Send datagrams to a bound datagram socket
+    Datagram,
+    /// Bind a datagram socket and reply to the last sender
+    DatagramListen,
 }
 
 /// Parity configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ParityConfig {
     None,
@@ -82,7 +294,7 @@ pub enum ParityConfig {
 }
 
 /// Flow control configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FlowControlConfig {
     None,
@@ -90,6 +302,82 @@ pub enum FlowControlConfig {
     Software,
 }
 
+/// RS-485 half-duplex settings of a serial port
+///
+/// The transceiver's driver enable is wired to RTS, which is asserted
+/// around every write and released afterwards so the bus can answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rs485Config {
+    /// Who switches the transmitter on and off
+    #[serde(default)]
+    pub mode: Rs485Mode,
+    /// RTS level while transmitting; the opposite level enables the receiver
+    #[serde(default = "default_rts_on_send")]
+    pub rts_on_send: bool,
+    /// Delay between enabling the transmitter and sending, in milliseconds
+    #[serde(default)]
+    pub delay_before_send_ms: u32,
+    /// Delay between the last bit and releasing the bus, in milliseconds
+    #[serde(default)]
+    pub delay_after_send_ms: u32,
+    /// Drop our own transmissions when the transceiver echoes them back
+    #[serde(default = "default_suppress_echo")]
+    pub suppress_echo: bool,
+}
+
+/// How RS-485 direction control is done
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rs485Mode {
+    /// Use the kernel driver if it supports RS-485, else toggle RTS in software
+    #[default]
+    Auto,
+    /// Let the kernel driver switch the transmitter (Linux `TIOCSRS485`)
+    Kernel,
+    /// Toggle RTS around each write
+    Software,
+}
+
+/// USB identity of a serial adapter, used instead of a volatile port path
+///
+/// Written as `usb:VID:PID` or `usb:VID:PID:SERIAL` with hexadecimal IDs,
+/// for example `usb:0403:6001:A50285BI`, wherever a serial port path is
+/// accepted. The port is looked up when the session starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsbMatch {
+    /// USB vendor ID
+    pub vid: u16,
+    /// USB product ID
+    pub pid: u16,
+    /// Adapter serial number; any adapter with the IDs matches if unset
+    pub serial_number: Option<String>,
+}
+
+/// Prefix that marks a serial port given as a [`UsbMatch`]
+pub const USB_PORT_PREFIX: &str = "usb:";
+
+/// Serial line settings to change on an open port
+///
+/// Settings left out keep their current value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerialSettings {
+    /// Baud rate
+    #[serde(default)]
+    pub baud_rate: Option<u32>,
+    /// Data bits (5-8)
+    #[serde(default)]
+    pub data_bits: Option<u8>,
+    /// Stop bits (1-2)
+    #[serde(default)]
+    pub stop_bits: Option<u8>,
+    /// Parity
+    #[serde(default)]
+    pub parity: Option<ParityConfig>,
+    /// Flow control
+    #[serde(default)]
+    pub flow_control: Option<FlowControlConfig>,
+}
+
 /// Custom command definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomCommand {
@@ -108,6 +396,48 @@ pub struct CustomCommand {
     pub timeout_ms: u64,
 }
 
+/// Timed DTR/RTS sequence that resets a board, such as entering a bootloader
+///
+/// After the steps have run, the board's output is captured as a boot log
+/// until `until` shows up in it or `timeout_ms` has passed since the reset
+/// started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResetSequence {
+    /// Sequence name, such as `esp32-bootloader`
+    pub name: String,
+    /// Sequence description
+    #[serde(default)]
+    pub description: String,
+    /// Line changes, applied in order
+    pub steps: Vec<ResetStep>,
+    /// Text that ends the capture once it appears in the output
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Capture timeout in milliseconds
+    #[serde(default = "default_capture_timeout")]
+    pub timeout_ms: u64,
+}
+
+/// One step of a reset sequence
+///
+/// Lines left out keep their level; the new levels are held for `delay_ms`
+/// before the next step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResetStep {
+    /// Data Terminal Ready level
+    #[serde(default)]
+    pub dtr: Option<bool>,
+    /// Request To Send level
+    #[serde(default)]
+    pub rts: Option<bool>,
+    /// Time to hold the levels, in milliseconds
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+/// Names of the reset sequences available for every device
+pub const BUILTIN_RESET_SEQUENCES: &[&str] = &["esp32-bootloader", "esp32-reset", "arduino-reset"];
+
 // Default value functions
 fn default_log_level() -> String {
     "info".to_string()
@@ -129,6 +459,18 @@ fn default_history_limit() -> usize {
     1000
 }
 
+fn default_queue_capacity() -> usize {
+    1024
+}
+
+fn default_max_reconnect_attempts() -> u32 {
+    3
+}
+
+fn default_reconnect_delay() -> u64 {
+    1000
+}
+
 fn default_data_bits() -> u8 {
     8
 }
@@ -145,23 +487,868 @@ fn default_flow_control() -> FlowControlConfig {
     FlowControlConfig::None
 }
 
+fn default_rts_on_send() -> bool {
+    true
+}
+
+fn default_suppress_echo() -> bool {
+    true
+}
+
+fn default_stx() -> u8 {
+    0x02
+}
+
+fn default_etx() -> u8 {
+    0x03
+}
+
+/// DLE, the usual escape byte for STX/ETX framing
+fn default_escape() -> u8 {
+    0x10
+}
+
 fn default_tcp_timeout() -> u64 {
     3000
 }
 
+fn default_tcp_server_bind() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_udp_bind() -> String {
+    "0.0.0.0:0".to_string()
+}
+
+fn default_capture_timeout() -> u64 {
+    3000
+}
+
 fn default_command_timeout() -> u64 {
     1000
 }
 
-impl Default for TermComConfig {
+/// Log levels accepted in `global.log_level`
+const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+impl TermComConfig {
+    /// Find a device profile by name
+    pub fn find_device(&self, name: &str) -> Option<&DeviceConfig> {
+        self.devices.iter().find(|device| device.name == name)
+    }
+
+    /// Add a device profile
+    ///
+    /// A profile with the same name is an error unless `replace` is set, in
+    /// which case it is replaced in place.
+    pub fn add_device(&mut self, device: DeviceConfig, replace: bool) -> TermComResult<()> {
+        match self.devices.iter_mut().find(|existing| existing.name == device.name) {
+            Some(existing) if replace => *existing = device,
+            Some(_) => {
+                return Err(config_error(format!("Device '{}' already exists", device.name)));
+            }
+            None => self.devices.push(device),
+        }
+        Ok(())
+    }
+
+    /// Remove a device profile by name
+    pub fn remove_device(&mut self, name: &str) -> TermComResult<DeviceConfig> {
+        let index = self
+            .devices
+            .iter()
+            .position(|device| device.name == name)
+            .ok_or_else(|| config_error(format!("Device '{}' not found", name)))?;
+        Ok(self.devices.remove(index))
+    }
+
+    /// Set a value by dotted key
+    ///
+    /// Keys are `global.<key>` (the `global.` prefix is optional) or
+    /// `devices.<name>.<field>`.
+    pub fn set_value(&mut self, key: &str, value: &str) -> TermComResult<()> {
+        if let Some(device_key) = key.strip_prefix("devices.") {
+            let (name, field) = device_key
+                .split_once('.')
+                .ok_or_else(|| config_error(format!("Expected devices.<name>.<field>, got '{}'", key)))?;
+            let device = self
+                .devices
+                .iter_mut()
+                .find(|device| device.name == name)
+                .ok_or_else(|| config_error(format!("Device '{}' not found", name)))?;
+            return device.set_value(field, value);
+        }
+
+        self.global.set_value(key.strip_prefix("global.").unwrap_or(key), value)
+    }
+}
+
+impl GlobalConfig {
+    /// Queue settings for the message pipeline
+    pub fn queue(&self) -> QueueConfig {
+        QueueConfig {
+            capacity: self.queue_capacity,
+            overflow: self.overflow_policy,
+        }
+    }
+
+    /// Set a global setting by key
+    pub fn set_value(&mut self, key: &str, value: &str) -> TermComResult<()> {
+        match key {
+            "log_level" => {
+                let level = value.to_lowercase();
+                if !LOG_LEVELS.contains(&level.as_str()) {
+                    return Err(config_error(format!(
+                        "Invalid log level '{}'; expected one of {}",
+                        value,
+                        LOG_LEVELS.join(", ")
+                    )));
+                }
+                self.log_level = level;
+            }
+            "max_sessions" => self.max_sessions = parse_positive(key, value)?,
+            "timeout_ms" => self.timeout_ms = parse_value(key, value)?,
+            "auto_save" => self.auto_save = parse_value(key, value)?,
+            "history_limit" => self.history_limit = parse_positive(key, value)?,
+            "queue_capacity" => self.queue_capacity = parse_positive(key, value)?,
+            "overflow_policy" => self.overflow_policy = value.parse()?,
+            _ => return Err(config_error(format!("Unknown global setting '{}'", key))),
+        }
+        Ok(())
+    }
+}
+
+impl DeviceConfig {
+    /// Create a device profile with no description, commands, tags or
+    /// properties and raw framing
+    pub fn new(name: impl Into<String>, connection: ConnectionConfig) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            connection,
+            framing: FramingConfig::Raw,
+            commands: Vec::new(),
+            reset_sequences: Vec::new(),
+            auto_reconnect: false,
+            max_reconnect_attempts: default_max_reconnect_attempts(),
+            reconnect_delay_ms: default_reconnect_delay(),
+            tags: Vec::new(),
+            properties: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Set a device field by name
+    ///
+    /// `tags` takes a comma separated list and `properties.<key>` sets one
+    /// property, removing it when the value is empty. Connection fields
+    /// depend on the connection type.
+    pub fn set_value(&mut self, field: &str, value: &str) -> TermComResult<()> {
+        if let Some(property) = field.strip_prefix("properties.") {
+            if value.is_empty() {
+                self.properties.remove(property);
+            } else {
+                self.properties.insert(property.to_string(), value.to_string());
+            }
+            return Ok(());
+        }
+
+        match field {
+            "description" => self.description = value.to_string(),
+            "auto_reconnect" => self.auto_reconnect = parse_value(field, value)?,
+            "max_reconnect_attempts" => self.max_reconnect_attempts = parse_value(field, value)?,
+            "reconnect_delay_ms" => self.reconnect_delay_ms = parse_positive(field, value)?,
+            "framing" => self.framing = value.parse()?,
+            "tags" => {
+                self.tags = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            _ => self.connection.set_value(field, value)?,
+        }
+        Ok(())
+    }
+}
+
+impl ConnectionConfig {
+    /// Set a connection parameter by name
+    pub fn set_value(&mut self, field: &str, value: &str) -> TermComResult<()> {
+        match self {
+            ConnectionConfig::Serial {
+                port,
+                baud_rate,
+                data_bits,
+                stop_bits,
+                parity,
+                flow_control,
+                rs485,
+            } => match field {
+                "port" => {
+                    UsbMatch::from_port(value)?;
+                    *port = value.to_string();
+                }
+                "baud_rate" => *baud_rate = parse_positive(field, value)?,
+                "data_bits" => *data_bits = parse_in_range(field, value, 5..=8)?,
+                "stop_bits" => *stop_bits = parse_in_range(field, value, 1..=2)?,
+                "parity" => *parity = value.parse()?,
+                "flow_control" => *flow_control = value.parse()?,
+                "rs485" => {
+                    *rs485 = match value.to_lowercase().as_str() {
+                        "off" | "none" => None,
+                        mode => Some(Rs485Config { mode: mode.parse()?, ..rs485.unwrap_or_default() }),
+                    }
+                }
+                _ => return Err(config_error(format!("Unknown serial device field '{}'", field))),
+            },
+            ConnectionConfig::Tcp {
+                host,
+                port,
+                timeout_ms,
+                keep_alive,
+            } => match field {
+                "host" => *host = value.to_string(),
+                "port" => *port = parse_positive(field, value)?,
+                "timeout_ms" => *timeout_ms = parse_value(field, value)?,
+                "keep_alive" => *keep_alive = parse_value(field, value)?,
+                _ => return Err(config_error(format!("Unknown TCP device field '{}'", field))),
+            },
+            ConnectionConfig::TcpServer {
+                bind,
+                port,
+                max_clients,
+            } => match field {
+                "bind" => *bind = value.to_string(),
+                "port" => *port = parse_value(field, value)?,
+                "max_clients" if value.is_empty() => *max_clients = None,
+                "max_clients" => *max_clients = Some(parse_positive(field, value)?),
+                _ => return Err(config_error(format!("Unknown TCP server device field '{}'", field))),
+            },
+            ConnectionConfig::Udp {
+                bind,
+                remote,
+                broadcast,
+                multicast,
+            } => match field {
+                "bind" => *bind = value.to_string(),
+                "remote" => *remote = optional_value(value),
+                "broadcast" => *broadcast = parse_value(field, value)?,
+                "multicast" => *multicast = optional_value(value),
+                _ => return Err(config_error(format!("Unknown UDP device field '{}'", field))),
+            },
+            ConnectionConfig::Pty { path, link } => match field {
+                "path" => *path = optional_value(value),
+                "link" => *link = optional_value(value),
+                _ => return Err(config_error(format!("Unknown PTY device field '{}'", field))),
+            },
+            ConnectionConfig::Process { command, args, env, cwd } => match field {
+                "command" => *command = value.to_string(),
+                "args" => *args = value.split_whitespace().map(str::to_string).collect(),
+                "cwd" => *cwd = optional_value(value),
+                _ => match field.strip_prefix("env.") {
+                    Some(name) if value.is_empty() => {
+                        env.remove(name);
+                    }
+                    Some(name) => {
+                        env.insert(name.to_string(), value.to_string());
+                    }
+                    None => return Err(config_error(format!("Unknown process device field '{}'", field))),
+                },
+            },
+            ConnectionConfig::Unix { path, mode } => match field {
+                "path" => *path = value.to_string(),
+                "mode" => *mode = value.parse()?,
+                _ => return Err(config_error(format!("Unknown Unix socket device field '{}'", field))),
+            },
+            ConnectionConfig::Custom { transport, options } => match field {
+                "transport" => *transport = value.to_string(),
+                _ => match field.strip_prefix("options.") {
+                    Some(name) if value.is_empty() => {
+                        options.remove(name);
+                    }
+                    Some(name) => {
+                        options.insert(name.to_string(), option_value(value));
+                    }
+                    None => return Err(config_error(format!("Unknown custom device field '{}'", field))),
+                },
+            },
+        }
+        Ok(())
+    }
+
+    /// Read the options of a custom connection into the transport's settings type
+    pub fn custom_options<T: serde::de::DeserializeOwned>(&self) -> TermComResult<T> {
+        match self {
+            ConnectionConfig::Custom { transport, options } => toml::Value::Table(options.clone())
+                .try_into()
+                .map_err(|e| config_error(format!("Invalid options for transport '{}': {}", transport, e))),
+            _ => Err(config_error("Connection is not handled by a custom transport".to_string())),
+        }
+    }
+}
+
+impl CustomCommand {
+    /// Create a command with the default timeout and no response pattern
+    pub fn new(name: String, template: String) -> Self {
+        Self {
+            name,
+            description: String::new(),
+            template,
+            response_pattern: None,
+            timeout_ms: default_command_timeout(),
+        }
+    }
+}
+
+impl ResetSequence {
+    /// Get a built-in sequence by name
+    ///
+    /// The ESP32 sequences assume the usual auto-reset circuit (DTR drives
+    /// GPIO0 and RTS drives EN, both inverted); `arduino-reset` pulses the
+    /// DTR line that resets boards with an auto-reset capacitor.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let step = |dtr: Option<bool>, rts: Option<bool>, delay_ms: u64| ResetStep { dtr, rts, delay_ms };
+
+        let (description, steps, until, timeout_ms) = match name {
+            "esp32-bootloader" => (
+                "Reset an ESP32 into its serial bootloader",
+                vec![
+                    step(Some(false), Some(true), 100),
+                    step(Some(true), Some(false), 50),
+                    step(Some(false), None, 0),
+                ],
+                Some("waiting for download"),
+                3000,
+            ),
+            "esp32-reset" => (
+                "Reset an ESP32 and let it boot its firmware",
+                vec![step(Some(false), Some(true), 100), step(None, Some(false), 0)],
+                None,
+                3000,
+            ),
+            "arduino-reset" => (
+                "Reset an Arduino through its auto-reset circuit",
+                vec![step(Some(false), Some(false), 250), step(Some(true), Some(true), 50)],
+                None,
+                2000,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            steps,
+            until: until.map(str::to_string),
+            timeout_ms,
+        })
+    }
+
+    /// Longest time the sequence can take: every step delay plus the capture timeout
+    pub fn duration(&self) -> std::time::Duration {
+        let delays: u64 = self.steps.iter().map(|step| step.delay_ms).sum();
+        std::time::Duration::from_millis(delays.saturating_add(self.timeout_ms))
+    }
+}
+
+impl DeviceConfig {
+    /// Find a reset sequence by name
+    ///
+    /// Sequences defined for the device take precedence over built-in ones.
+    pub fn reset_sequence(&self, name: &str) -> TermComResult<ResetSequence> {
+        if let Some(sequence) = self.reset_sequences.iter().find(|sequence| sequence.name == name) {
+            return Ok(sequence.clone());
+        }
+
+        ResetSequence::builtin(name).ok_or_else(|| {
+            let mut available: Vec<_> = self.reset_sequences.iter().map(|sequence| sequence.name.as_str()).collect();
+            for builtin in BUILTIN_RESET_SEQUENCES {
+                if !available.contains(builtin) {
+                    available.push(builtin);
+                }
+            }
+            config_error(format!(
+                "Unknown reset sequence '{}'; available: {}",
+                name,
+                available.join(", ")
+            ))
+        })
+    }
+}
+
+impl UsbMatch {
+    /// Parse a serial port given as a USB match
+    ///
+    /// Returns `None` for ordinary port paths.
+    pub fn from_port(port: &str) -> TermComResult<Option<Self>> {
+        if port.starts_with(USB_PORT_PREFIX) {
+            port.parse().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Whether an adapter with these IDs and serial number matches
+    pub fn matches(&self, vid: u16, pid: u16, serial_number: Option<&str>) -> bool {
+        self.vid == vid
+            && self.pid == pid
+            && self.serial_number.as_deref().is_none_or(|wanted| serial_number == Some(wanted))
+    }
+}
+
+impl FromStr for UsbMatch {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        let invalid = || config_error(format!("Invalid USB match '{}'; expected usb:VID:PID[:SERIAL]", value));
+        let mut parts = value.strip_prefix(USB_PORT_PREFIX).ok_or_else(invalid)?.splitn(3, ':');
+        let mut id = || {
+            parts
+                .next()
+                .filter(|id| id.len() == 4)
+                .and_then(|id| u16::from_str_radix(id, 16).ok())
+                .ok_or_else(invalid)
+        };
+        let vid = id()?;
+        let pid = id()?;
+        let serial_number = match parts.next() {
+            Some("") => return Err(invalid()),
+            serial_number => serial_number.map(str::to_string),
+        };
+
+        Ok(Self { vid, pid, serial_number })
+    }
+}
+
+impl std::fmt::Display for UsbMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{:04x}:{:04x}", USB_PORT_PREFIX, self.vid, self.pid)?;
+        if let Some(ref serial_number) = self.serial_number {
+            write!(f, ":{}", serial_number)?;
+        }
+        Ok(())
+    }
+}
+
+impl SerialSettings {
+    /// Change only the baud rate
+    pub fn baud_rate(baud_rate: u32) -> Self {
+        Self { baud_rate: Some(baud_rate), ..Self::default() }
+    }
+
+    /// All settings of a serial connection
+    pub fn from_connection(connection: &ConnectionConfig) -> Option<Self> {
+        match connection {
+            ConnectionConfig::Serial { baud_rate, data_bits, stop_bits, parity, flow_control, .. } => Some(Self {
+                baud_rate: Some(*baud_rate),
+                data_bits: Some(*data_bits),
+                stop_bits: Some(*stop_bits),
+                parity: Some(*parity),
+                flow_control: Some(*flow_control),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether no setting is changed
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the settings to a serial connection
+    ///
+    /// Nothing is changed unless every setting is valid.
+    pub fn apply(&self, connection: &mut ConnectionConfig) -> TermComResult<()> {
+        let ConnectionConfig::Serial { baud_rate, data_bits, stop_bits, parity, flow_control, .. } = connection else {
+            return Err(config_error("Serial settings only apply to serial connections".to_string()));
+        };
+
+        if self.baud_rate == Some(0) {
+            return Err(config_error("Invalid value '0' for baud_rate".to_string()));
+        }
+        if let Some(bits) = self.data_bits.filter(|bits| !(5..=8).contains(bits)) {
+            return Err(config_error(format!("Invalid value '{}' for data_bits; expected 5 to 8", bits)));
+        }
+        if let Some(bits) = self.stop_bits.filter(|bits| !(1..=2).contains(bits)) {
+            return Err(config_error(format!("Invalid value '{}' for stop_bits; expected 1 to 2", bits)));
+        }
+
+        *baud_rate = self.baud_rate.unwrap_or(*baud_rate);
+        *data_bits = self.data_bits.unwrap_or(*data_bits);
+        *stop_bits = self.stop_bits.unwrap_or(*stop_bits);
+        *parity = self.parity.unwrap_or(*parity);
+        *flow_control = self.flow_control.unwrap_or(*flow_control);
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SerialSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(baud_rate) = self.baud_rate {
+            parts.push(format!("{} baud", baud_rate));
+        }
+        if let Some(data_bits) = self.data_bits {
+            parts.push(format!("{} data bits", data_bits));
+        }
+        match self.parity {
+            Some(ParityConfig::None) => parts.push("no parity".to_string()),
+            Some(parity) => parts.push(format!("{} parity", parity)),
+            None => {}
+        }
+        match self.stop_bits {
+            Some(1) => parts.push("1 stop bit".to_string()),
+            Some(stop_bits) => parts.push(format!("{} stop bits", stop_bits)),
+            None => {}
+        }
+        match self.flow_control {
+            Some(FlowControlConfig::None) => parts.push("no flow control".to_string()),
+            Some(flow_control) => parts.push(format!("{} flow control", flow_control)),
+            None => {}
+        }
+
+        if parts.is_empty() {
+            write!(f, "no settings")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+impl std::fmt::Display for ParityConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParityConfig::None => write!(f, "none"),
+            ParityConfig::Odd => write!(f, "odd"),
+            ParityConfig::Even => write!(f, "even"),
+        }
+    }
+}
+
+impl std::fmt::Display for FlowControlConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlowControlConfig::None => write!(f, "none"),
+            FlowControlConfig::Hardware => write!(f, "hardware"),
+            FlowControlConfig::Software => write!(f, "software"),
+        }
+    }
+}
+
+impl FromStr for ParityConfig {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "odd" => Ok(Self::Odd),
+            "even" => Ok(Self::Even),
+            _ => Err(config_error(format!("Invalid parity '{}'; expected none, odd or even", value))),
+        }
+    }
+}
+
+impl FromStr for FlowControlConfig {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "hardware" => Ok(Self::Hardware),
+            "software" => Ok(Self::Software),
+            _ => Err(config_error(format!(
+                "Invalid flow control '{}'; expected none, hardware or software",
+                value
+            ))),
+        }
+    }
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "block" => Ok(Self::Block),
+            "drop_oldest" => Ok(Self::DropOldest),
+            "drop_newest" => Ok(Self::DropNewest),
+            _ => Err(config_error(format!(
+                "Invalid overflow policy '{}'; expected block, drop_oldest or drop_newest",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverflowPolicy::Block => write!(f, "block"),
+            OverflowPolicy::DropOldest => write!(f, "drop_oldest"),
+            OverflowPolicy::DropNewest => write!(f, "drop_newest"),
+        }
+    }
+}
+
+impl FramingConfig {
+    pub fn is_raw(&self) -> bool {
+        *self == FramingConfig::Raw
+    }
+
+    /// Check that the settings describe frames that can be found
+    pub fn validate(&self) -> TermComResult<()> {
+        match self {
+            FramingConfig::Delimiter { delimiter } if delimiter.is_empty() => {
+                Err(config_error("Framing delimiter must not be empty".to_string()))
+            }
+            FramingConfig::FixedLength { length: 0 } => {
+                Err(config_error("Frame length must be greater than zero".to_string()))
+            }
+            FramingConfig::LengthPrefixed { width, .. } if ![1, 2, 4, 8].contains(width) => Err(config_error(format!(
+                "Invalid length field width {}; expected 1, 2, 4 or 8",
+                width
+            ))),
+            FramingConfig::StxEtx { stx, etx, escape } if stx == etx || stx == escape || etx == escape => {
+                Err(config_error("STX, ETX and escape bytes must differ".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses the compact form used on the command line
+///
+/// `raw`, `line[:cr|lf|crlf]`, `delimiter:<HEX>`, `fixed:<LENGTH>`,
+/// `length:<WIDTH>[:be|le][:<OFFSET>]`, `slip`, `cobs` or `stx-etx`.
+impl FromStr for FramingConfig {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        let invalid = || {
+            config_error(format!(
+                "Invalid framing '{}'; expected raw, line[:cr|lf|crlf], delimiter:<hex>, fixed:<length>, \
+                 length:<width>[:be|le][:<offset>], slip, cobs or stx-etx",
+                value
+            ))
+        };
+        let lowercase = value.to_lowercase().replace('-', "_");
+        let mut parts = lowercase.split(':');
+        let mode = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+
+        let config = match (mode, args.as_slice()) {
+            ("raw", []) => FramingConfig::Raw,
+            ("line", []) => FramingConfig::Line { ending: LineEnding::default() },
+            ("line", [ending]) => FramingConfig::Line {
+                ending: match *ending {
+                    "cr" => LineEnding::Cr,
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::Crlf,
+                    _ => return Err(invalid()),
+                },
+            },
+            ("delimiter", [hex]) => FramingConfig::Delimiter {
+                delimiter: parse_hex(hex).ok_or_else(invalid)?,
+            },
+            ("fixed", [length]) => FramingConfig::FixedLength {
+                length: length.parse().map_err(|_| invalid())?,
+            },
+            ("length", [width, rest @ ..]) if rest.len() <= 2 => {
+                let mut endianness = Endianness::default();
+                let mut offset = 0;
+                for arg in rest {
+                    match *arg {
+                        "be" => endianness = Endianness::Big,
+                        "le" => endianness = Endianness::Little,
+                        _ => offset = arg.parse().map_err(|_| invalid())?,
+                    }
+                }
+                FramingConfig::LengthPrefixed {
+                    offset,
+                    width: width.parse().map_err(|_| invalid())?,
+                    endianness,
+                }
+            }
+            ("slip", []) => FramingConfig::Slip,
+            ("cobs", []) => FramingConfig::Cobs,
+            ("stx_etx", []) => FramingConfig::StxEtx {
+                stx: default_stx(),
+                etx: default_etx(),
+                escape: default_escape(),
+            },
+            _ => return Err(invalid()),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl std::fmt::Display for FramingConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramingConfig::Raw => write!(f, "raw"),
+            FramingConfig::Line { ending } => write!(f, "line:{}", ending),
+            FramingConfig::Delimiter { delimiter } => {
+                write!(f, "delimiter:")?;
+                delimiter.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+            FramingConfig::FixedLength { length } => write!(f, "fixed:{}", length),
+            FramingConfig::LengthPrefixed { offset, width, endianness } => {
+                let order = match endianness {
+                    Endianness::Big => "be",
+                    Endianness::Little => "le",
+                };
+                write!(f, "length:{}:{}:{}", width, order, offset)
+            }
+            FramingConfig::Slip => write!(f, "slip"),
+            FramingConfig::Cobs => write!(f, "cobs"),
+            FramingConfig::StxEtx { .. } => write!(f, "stx-etx"),
+        }
+    }
+}
+
+impl LineEnding {
+    pub fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Cr => b"\r",
+            LineEnding::Lf => b"\n",
+            LineEnding::Crlf => b"\r\n",
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::Cr => write!(f, "cr"),
+            LineEnding::Lf => write!(f, "lf"),
+            LineEnding::Crlf => write!(f, "crlf"),
+        }
+    }
+}
+
+/// Parse bytes written as hexadecimal digits, such as `0d0a`
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    if value.is_empty() || !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl FromStr for UnixSocketMode {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "stream" => Ok(Self::Stream),
+            "stream_listen" => Ok(Self::StreamListen),
+            "datagram" => Ok(Self::Datagram),
+            "datagram_listen" => Ok(Self::DatagramListen),
+            _ => Err(config_error(format!(
+                "Invalid Unix socket mode '{}'; expected stream, stream_listen, datagram or datagram_listen",
+                value
+            ))),
+        }
+    }
+}
+
+impl Default for Rs485Config {
     fn default() -> Self {
         Self {
-            global: GlobalConfig::default(),
-            devices: Vec::new(),
+            mode: Rs485Mode::default(),
+            rts_on_send: default_rts_on_send(),
+            delay_before_send_ms: 0,
+            delay_after_send_ms: 0,
+            suppress_echo: default_suppress_echo(),
         }
     }
 }
 
+impl FromStr for Rs485Mode {
+    type Err = TermComError;
+
+    fn from_str(value: &str) -> TermComResult<Self> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "kernel" => Ok(Self::Kernel),
+            "software" => Ok(Self::Software),
+            _ => Err(config_error(format!(
+                "Invalid RS-485 mode '{}'; expected off, auto, kernel or software",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Rs485Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rs485Mode::Auto => write!(f, "auto"),
+            Rs485Mode::Kernel => write!(f, "kernel"),
+            Rs485Mode::Software => write!(f, "software"),
+        }
+    }
+}
+
+impl std::fmt::Display for UnixSocketMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnixSocketMode::Stream => write!(f, "stream"),
+            UnixSocketMode::StreamListen => write!(f, "stream_listen"),
+            UnixSocketMode::Datagram => write!(f, "datagram"),
+            UnixSocketMode::DatagramListen => write!(f, "datagram_listen"),
+        }
+    }
+}
+
+fn config_error(message: String) -> TermComError {
+    TermComError::Config { message }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> TermComResult<T> {
+    value
+        .parse()
+        .map_err(|_| config_error(format!("Invalid value '{}' for {}", value, key)))
+}
+
+/// Parse a custom transport option as a TOML value, falling back to a string
+fn option_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+/// Treat an empty value as unsetting an optional field
+fn optional_value(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn parse_positive<T: FromStr + Default + PartialOrd>(key: &str, value: &str) -> TermComResult<T> {
+    let parsed: T = parse_value(key, value)?;
+    if parsed <= T::default() {
+        return Err(config_error(format!("{} must be greater than zero", key)));
+    }
+    Ok(parsed)
+}
+
+fn parse_in_range(key: &str, value: &str, range: std::ops::RangeInclusive<u8>) -> TermComResult<u8> {
+    let parsed = parse_value(key, value)?;
+    if !range.contains(&parsed) {
+        return Err(config_error(format!(
+            "{} must be between {} and {}",
+            key,
+            range.start(),
+            range.end()
+        )));
+    }
+    Ok(parsed)
+}
+
 impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
@@ -170,6 +1357,8 @@ impl Default for GlobalConfig {
             timeout_ms: default_timeout(),
             auto_save: default_auto_save(),
             history_limit: default_history_limit(),
+            queue_capacity: default_queue_capacity(),
+            overflow_policy: OverflowPolicy::default(),
         }
     }
 }
@@ -217,6 +1406,7 @@ impl From<SerialConfig> for ConnectionConfig {
             stop_bits: config.stop_bits,
             parity: config.parity,
             flow_control: config.flow_control,
+            rs485: None,
         }
     }
 }
@@ -252,15 +1442,14 @@ mod tests {
             stop_bits: 1,
             parity: ParityConfig::None,
             flow_control: FlowControlConfig::None,
+            rs485: None,
         };
         
         let config = TermComConfig {
             global: GlobalConfig::default(),
             devices: vec![DeviceConfig {
-                name: "test_device".to_string(),
                 description: "Test device".to_string(),
-                connection: serial_config,
-                commands: Vec::new(),
+                ..DeviceConfig::new("test_device", serial_config)
             }],
         };
         
@@ -268,6 +1457,124 @@ mod tests {
         let _deserialized: TermComConfig = toml::from_str(&toml_str).unwrap();
     }
 
+    #[test]
+    fn test_device_profile_fields() {
+        let toml_str = r#"
+            [global]
+
+            [[devices]]
+            name = "board"
+
+            [devices.connection]
+            type = "tcp"
+            host = "127.0.0.1"
+            port = 23
+        "#;
+        let config: TermComConfig = toml::from_str(toml_str).unwrap();
+        let device = config.find_device("board").unwrap();
+        assert!(!device.auto_reconnect);
+        assert_eq!(device.max_reconnect_attempts, 3);
+        assert_eq!(device.reconnect_delay_ms, 1000);
+        assert!(device.tags.is_empty());
+        assert!(config.find_device("missing").is_none());
+
+        let mut device = device.clone();
+        device.auto_reconnect = true;
+        device.tags.push("lab".to_string());
+        device.properties.insert("firmware".to_string(), "1.2.0".to_string());
+        let config = TermComConfig {
+            global: GlobalConfig::default(),
+            devices: vec![device],
+        };
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let deserialized: TermComConfig = toml::from_str(&toml_str).unwrap();
+        let device = deserialized.find_device("board").unwrap();
+        assert!(device.auto_reconnect);
+        assert_eq!(device.tags, vec!["lab".to_string()]);
+        assert_eq!(device.properties.get("firmware"), Some(&"1.2.0".to_string()));
+    }
+
+    fn create_serial_device(name: &str) -> DeviceConfig {
+        DeviceConfig::new(
+            name,
+            ConnectionConfig::Serial {
+                port: "/dev/ttyUSB0".to_string(),
+                baud_rate: 9600,
+                data_bits: 8,
+                stop_bits: 1,
+                parity: ParityConfig::None,
+                flow_control: FlowControlConfig::None,
+                rs485: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_add_and_remove_devices() {
+        let mut config = TermComConfig::default();
+        config.add_device(create_serial_device("a"), false).unwrap();
+        config.add_device(create_serial_device("b"), false).unwrap();
+        assert!(config.add_device(create_serial_device("a"), false).is_err());
+
+        let mut replacement = create_serial_device("a");
+        replacement.description = "Replaced".to_string();
+        config.add_device(replacement, true).unwrap();
+        assert_eq!(config.devices.len(), 2);
+        assert_eq!(config.devices[0].description, "Replaced");
+
+        assert_eq!(config.remove_device("a").unwrap().name, "a");
+        assert!(config.remove_device("a").is_err());
+        assert_eq!(config.devices.len(), 1);
+        assert_eq!(config.devices[0].name, "b");
+    }
+
+    #[test]
+    fn test_set_values() {
+        let mut config = TermComConfig::default();
+        config.add_device(create_serial_device("board"), false).unwrap();
+
+        config.set_value("log_level", "DEBUG").unwrap();
+        config.set_value("global.history_limit", "5000").unwrap();
+        assert_eq!(config.global.log_level, "debug");
+        assert_eq!(config.global.history_limit, 5000);
+        assert!(config.set_value("log_level", "loud").is_err());
+        assert!(config.set_value("max_sessions", "0").is_err());
+        assert!(config.set_value("unknown", "1").is_err());
+
+        config.set_value("overflow_policy", "drop-oldest").unwrap();
+        config.set_value("queue_capacity", "256").unwrap();
+        assert_eq!(config.global.queue(), QueueConfig { capacity: 256, overflow: OverflowPolicy::DropOldest });
+        assert!(config.set_value("overflow_policy", "grow").is_err());
+        assert!(config.set_value("queue_capacity", "0").is_err());
+
+        config.set_value("devices.board.baud_rate", "115200").unwrap();
+        config.set_value("devices.board.parity", "even").unwrap();
+        config.set_value("devices.board.tags", "lab, uno").unwrap();
+        config.set_value("devices.board.properties.firmware", "1.2.0").unwrap();
+        config.set_value("devices.board.auto_reconnect", "true").unwrap();
+        config.set_value("devices.board.max_reconnect_attempts", "0").unwrap();
+        config.set_value("devices.board.reconnect_delay_ms", "250").unwrap();
+        assert!(config.set_value("devices.board.reconnect_delay_ms", "0").is_err());
+        assert!(config.set_value("devices.board.data_bits", "9").is_err());
+        assert!(config.set_value("devices.board.host", "localhost").is_err());
+        assert!(config.set_value("devices.missing.port", "x").is_err());
+
+        let device = config.find_device("board").unwrap();
+        assert!(matches!(
+            device.connection,
+            ConnectionConfig::Serial { baud_rate: 115200, parity: ParityConfig::Even, .. }
+        ));
+        assert_eq!(device.tags, vec!["lab".to_string(), "uno".to_string()]);
+        assert_eq!(device.properties.get("firmware"), Some(&"1.2.0".to_string()));
+        assert!(device.auto_reconnect);
+        assert_eq!(device.max_reconnect_attempts, 0);
+        assert_eq!(device.reconnect_delay_ms, 250);
+
+        config.set_value("devices.board.properties.firmware", "").unwrap();
+        assert!(config.find_device("board").unwrap().properties.is_empty());
+    }
+
     #[test]
     fn test_tcp_config() {
         let tcp_config = ConnectionConfig::Tcp {
@@ -280,14 +1587,394 @@ mod tests {
         let config = TermComConfig {
             global: GlobalConfig::default(),
             devices: vec![DeviceConfig {
-                name: "tcp_device".to_string(),
                 description: "TCP device".to_string(),
-                connection: tcp_config,
-                commands: Vec::new(),
+                ..DeviceConfig::new("tcp_device", tcp_config)
             }],
         };
         
         let toml_str = toml::to_string(&config).unwrap();
         let _deserialized: TermComConfig = toml::from_str(&toml_str).unwrap();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tcp_server_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "collector"
+
+[connection]
+type = "tcp_server"
+port = 7000
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert!(matches!(
+            connection,
+            ConnectionConfig::TcpServer { ref bind, port: 7000, max_clients: None } if bind == "0.0.0.0"
+        ));
+
+        connection.set_value("max_clients", "4").unwrap();
+        assert!(matches!(connection, ConnectionConfig::TcpServer { max_clients: Some(4), .. }));
+        connection.set_value("max_clients", "").unwrap();
+        assert!(matches!(connection, ConnectionConfig::TcpServer { max_clients: None, .. }));
+        assert!(connection.set_value("max_clients", "0").is_err());
+    }
+
+    #[test]
+    fn test_udp_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "beacon"
+
+[connection]
+type = "udp"
+bind = "0.0.0.0:5000"
+multicast = "239.1.2.3"
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Udp { ref bind, remote: None, broadcast: false, multicast: Some(ref group) }
+                if bind == "0.0.0.0:5000" && group == "239.1.2.3"
+        ));
+
+        connection.set_value("remote", "192.168.1.255:5000").unwrap();
+        connection.set_value("broadcast", "true").unwrap();
+        connection.set_value("multicast", "").unwrap();
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Udp { remote: Some(_), broadcast: true, multicast: None, .. }
+        ));
+        assert!(connection.set_value("baud_rate", "9600").is_err());
+    }
+
+    #[test]
+    fn test_pty_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "simulator"
+
+[connection]
+type = "pty"
+link = "/tmp/ttyV0"
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Pty { path: None, link: Some(ref link) } if link == "/tmp/ttyV0"
+        ));
+
+        connection.set_value("path", "/dev/pts/7").unwrap();
+        connection.set_value("link", "").unwrap();
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Pty { path: Some(ref path), link: None } if path == "/dev/pts/7"
+        ));
+        assert!(connection.set_value("port", "1").is_err());
+    }
+
+    #[test]
+    fn test_process_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "firmware-sim"
+
+[connection]
+type = "process"
+command = "qemu-system-arm"
+args = ["-M", "lm3s6965evb", "-nographic"]
+env = { QEMU_AUDIO_DRV = "none" }
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Process { ref command, ref args, ref env, cwd: None }
+                if command == "qemu-system-arm" && args.len() == 3 && env["QEMU_AUDIO_DRV"] == "none"
+        ));
+
+        connection.set_value("args", "-M virt  -nographic").unwrap();
+        connection.set_value("cwd", "/opt/sim").unwrap();
+        connection.set_value("env.QEMU_AUDIO_DRV", "").unwrap();
+        connection.set_value("env.RUST_LOG", "debug").unwrap();
+        match &connection {
+            ConnectionConfig::Process { args, env, cwd, .. } => {
+                assert_eq!(args, &["-M", "virt", "-nographic"]);
+                assert_eq!(cwd.as_deref(), Some("/opt/sim"));
+                assert_eq!(env.len(), 1);
+                assert_eq!(env["RUST_LOG"], "debug");
+            }
+            other => panic!("Unexpected connection: {:?}", other),
+        }
+        assert!(connection.set_value("port", "1").is_err());
+    }
+
+    #[test]
+    fn test_unix_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "qemu-console"
+
+[connection]
+type = "unix"
+path = "/tmp/qemu-serial.sock"
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Unix { ref path, mode: UnixSocketMode::Stream } if path == "/tmp/qemu-serial.sock"
+        ));
+
+        connection.set_value("mode", "datagram-listen").unwrap();
+        assert!(matches!(connection, ConnectionConfig::Unix { mode: UnixSocketMode::DatagramListen, .. }));
+        assert_eq!(UnixSocketMode::DatagramListen.to_string(), "datagram_listen");
+        assert!(connection.set_value("mode", "seqpacket").is_err());
+        assert!(connection.set_value("port", "1").is_err());
+    }
+
+    #[test]
+    fn test_framing_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "gps"
+
+[connection]
+type = "serial"
+port = "/dev/ttyUSB0"
+baud_rate = 9600
+
+[framing]
+mode = "delimiter"
+delimiter = [0x0d, 0x0a]
+"#).unwrap();
+        assert_eq!(device.framing, FramingConfig::Delimiter { delimiter: b"\r\n".to_vec() });
+
+        let mut device: DeviceConfig = toml::from_str(r#"
+name = "bus"
+
+[connection]
+type = "tcp"
+host = "10.0.0.9"
+port = 4000
+
+[framing]
+mode = "stx_etx"
+"#).unwrap();
+        assert_eq!(device.framing, FramingConfig::StxEtx { stx: 0x02, etx: 0x03, escape: 0x10 });
+
+        // The compact form reads back what it writes
+        for spec in ["raw", "line:crlf", "delimiter:0d0a", "fixed:16", "length:4:le:2", "slip", "cobs", "stx-etx"] {
+            let framing: FramingConfig = spec.parse().unwrap();
+            assert_eq!(framing.to_string(), spec);
+        }
+        assert_eq!("line".parse::<FramingConfig>().unwrap(), FramingConfig::Line { ending: LineEnding::Lf });
+        assert_eq!(
+            "length:2".parse::<FramingConfig>().unwrap(),
+            FramingConfig::LengthPrefixed { offset: 0, width: 2, endianness: Endianness::Big }
+        );
+        for invalid in ["line:lfcr", "delimiter:", "delimiter:0d0", "fixed:0", "length:3", "length:2:be:1:0", "hdlc"] {
+            assert!(invalid.parse::<FramingConfig>().is_err(), "{}", invalid);
+        }
+
+        device.set_value("framing", "cobs").unwrap();
+        assert_eq!(device.framing, FramingConfig::Cobs);
+        assert!(toml::to_string(&device).unwrap().contains("mode = \"cobs\""));
+
+        // Raw devices leave the table out
+        device.set_value("framing", "raw").unwrap();
+        assert!(!toml::to_string(&device).unwrap().contains("framing"));
+    }
+
+    #[test]
+    fn test_rs485_config() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "modbus-meter"
+
+[connection]
+type = "serial"
+port = "/dev/ttyUSB0"
+baud_rate = 19200
+data_bits = 8
+stop_bits = 1
+parity = "even"
+flow_control = "none"
+
+[connection.rs485]
+mode = "software"
+delay_after_send_ms = 1
+"#).unwrap();
+
+        let mut connection = device.connection;
+        let ConnectionConfig::Serial { rs485: Some(rs485), .. } = connection else {
+            panic!("Expected RS-485 settings: {:?}", connection);
+        };
+        assert_eq!(rs485.mode, Rs485Mode::Software);
+        assert_eq!((rs485.delay_before_send_ms, rs485.delay_after_send_ms), (0, 1));
+        assert!(rs485.rts_on_send && rs485.suppress_echo);
+
+        // Changing the mode keeps the tuning
+        connection.set_value("rs485", "kernel").unwrap();
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Serial { rs485: Some(Rs485Config { mode: Rs485Mode::Kernel, delay_after_send_ms: 1, .. }), .. }
+        ));
+        connection.set_value("rs485", "off").unwrap();
+        assert!(matches!(connection, ConnectionConfig::Serial { rs485: None, .. }));
+        assert!(connection.set_value("rs485", "full-duplex").is_err());
+
+        // Plain serial devices leave the table out
+        assert!(!toml::to_string(&connection).unwrap().contains("rs485"));
+    }
+
+    #[test]
+    fn test_usb_match() {
+        let usb: UsbMatch = "usb:0403:6001:A50285BI".parse().unwrap();
+        assert_eq!(usb, UsbMatch { vid: 0x0403, pid: 0x6001, serial_number: Some("A50285BI".to_string()) });
+        assert_eq!(usb.to_string(), "usb:0403:6001:A50285BI");
+        assert!(usb.matches(0x0403, 0x6001, Some("A50285BI")));
+        assert!(!usb.matches(0x0403, 0x6001, Some("A50285BJ")));
+        assert!(!usb.matches(0x0403, 0x6001, None));
+
+        let any = UsbMatch::from_port("usb:10C4:EA60").unwrap().unwrap();
+        assert_eq!(any.to_string(), "usb:10c4:ea60");
+        assert!(any.matches(0x10c4, 0xea60, None));
+        assert!(!any.matches(0x10c4, 0xea61, None));
+
+        assert!(UsbMatch::from_port("/dev/ttyUSB0").unwrap().is_none());
+        for invalid in ["usb:", "usb:0403", "usb:403:6001", "usb:0403:zzzz", "usb:0403:6001:"] {
+            assert!(UsbMatch::from_port(invalid).is_err(), "{}", invalid);
+        }
+
+        let mut connection = ConnectionConfig::Serial {
+            port: "/dev/ttyUSB0".to_string(),
+            baud_rate: 115200,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        };
+        connection.set_value("port", "usb:0403:6001").unwrap();
+        assert!(connection.set_value("port", "usb:ftdi").is_err());
+        assert!(matches!(connection, ConnectionConfig::Serial { ref port, .. } if port == "usb:0403:6001"));
+    }
+
+    #[test]
+    fn test_serial_settings() {
+        let mut connection = ConnectionConfig::Serial {
+            port: "/dev/ttyUSB0".to_string(),
+            baud_rate: 115200,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        };
+        assert_eq!(
+            SerialSettings::from_connection(&connection).unwrap().to_string(),
+            "115200 baud, 8 data bits, no parity, 1 stop bit, no flow control"
+        );
+
+        let settings = SerialSettings { data_bits: Some(7), parity: Some(ParityConfig::Even), ..SerialSettings::baud_rate(921600) };
+        assert_eq!(settings.to_string(), "921600 baud, 7 data bits, even parity");
+        settings.apply(&mut connection).unwrap();
+        assert!(matches!(
+            connection,
+            ConnectionConfig::Serial { baud_rate: 921600, data_bits: 7, stop_bits: 1, parity: ParityConfig::Even, .. }
+        ));
+
+        // Invalid settings leave the connection untouched
+        let invalid = SerialSettings { stop_bits: Some(3), ..SerialSettings::baud_rate(9600) };
+        assert!(invalid.apply(&mut connection).is_err());
+        assert!(matches!(connection, ConnectionConfig::Serial { baud_rate: 921600, .. }));
+
+        assert!(SerialSettings::default().is_empty());
+        assert_eq!(SerialSettings::default().to_string(), "no settings");
+        let mut tcp = ConnectionConfig::Tcp { host: "localhost".to_string(), port: 23, timeout_ms: 1000, keep_alive: false };
+        assert!(SerialSettings::baud_rate(9600).apply(&mut tcp).is_err());
+    }
+
+    #[test]
+    fn test_reset_sequences() {
+        let device: DeviceConfig = toml::from_str(r#"
+name = "devkit"
+
+[connection]
+type = "serial"
+port = "/dev/ttyUSB0"
+baud_rate = 115200
+data_bits = 8
+stop_bits = 1
+parity = "none"
+flow_control = "none"
+
+[[reset_sequences]]
+name = "esp32-bootloader"
+until = "boot:0x3"
+steps = [
+    { dtr = false, rts = true, delay_ms = 200 },
+    { dtr = true, rts = false, delay_ms = 100 },
+    { dtr = false },
+]
+"#).unwrap();
+
+        // Device sequences shadow the built-in ones
+        let sequence = device.reset_sequence("esp32-bootloader").unwrap();
+        assert_eq!(sequence.until.as_deref(), Some("boot:0x3"));
+        assert_eq!(sequence.timeout_ms, 3000);
+        assert_eq!(sequence.steps[0], ResetStep { dtr: Some(false), rts: Some(true), delay_ms: 200 });
+        assert_eq!(sequence.steps[2], ResetStep { dtr: Some(false), rts: None, delay_ms: 0 });
+        assert_eq!(sequence.duration(), std::time::Duration::from_millis(3300));
+
+        let sequence = device.reset_sequence("arduino-reset").unwrap();
+        assert_eq!(sequence.steps.len(), 2);
+        assert!(sequence.until.is_none());
+
+        for name in BUILTIN_RESET_SEQUENCES {
+            assert_eq!(ResetSequence::builtin(name).unwrap().name, *name);
+        }
+        let error = device.reset_sequence("stm32-boot0").unwrap_err().to_string();
+        assert!(error.ends_with("available: esp32-bootloader, esp32-reset, arduino-reset"), "{}", error);
+    }
+
+    #[test]
+    fn test_custom_config() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct CanOptions {
+            interface: String,
+            bitrate: u32,
+            #[serde(default)]
+            fd: bool,
+        }
+
+        let device: DeviceConfig = toml::from_str(r#"
+name = "vehicle-bus"
+
+[connection]
+type = "custom"
+transport = "socketcan"
+
+[connection.options]
+interface = "can0"
+bitrate = 500000
+"#).unwrap();
+
+        let mut connection = device.connection;
+        assert_eq!(
+            connection.custom_options::<CanOptions>().unwrap(),
+            CanOptions { interface: "can0".to_string(), bitrate: 500000, fd: false }
+        );
+
+        connection.set_value("options.bitrate", "250000").unwrap();
+        connection.set_value("options.fd", "true").unwrap();
+        connection.set_value("options.interface", "vcan0").unwrap();
+        assert_eq!(
+            connection.custom_options::<CanOptions>().unwrap(),
+            CanOptions { interface: "vcan0".to_string(), bitrate: 250000, fd: true }
+        );
+
+        connection.set_value("options.bitrate", "").unwrap();
+        assert!(connection.custom_options::<CanOptions>().is_err());
+        assert!(connection.set_value("port", "1").is_err());
+
+        let tcp = ConnectionConfig::Tcp { host: "h".to_string(), port: 1, timeout_ms: 1, keep_alive: false };
+        assert!(tcp.custom_options::<CanOptions>().is_err());
+    }
+}
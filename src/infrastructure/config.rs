@@ -1,4 +1,4 @@
-use crate::domain::{config::TermComConfig, error::{TermComError, TermComResult}};
+use crate::domain::{config::{DeviceConfig, TermComConfig}, error::{TermComError, TermComResult}};
 use std::path::{Path, PathBuf};
 use std::fs;
 
@@ -114,6 +114,17 @@ impl ConfigManager {
         })
     }
 
+    /// Load a standalone device profile from a TOML file
+    pub fn load_device_from_path(&self, path: &Path) -> TermComResult<DeviceConfig> {
+        let content = fs::read_to_string(path).map_err(|e| TermComError::Config {
+            message: format!("Failed to read device file {}: {}", path.display(), e),
+        })?;
+        
+        toml::from_str(&content).map_err(|e| TermComError::Config {
+            message: format!("Failed to parse device file {}: {}", path.display(), e),
+        })
+    }
+
     /// Save configuration to specific path
     pub fn save_config_to_path(&self, path: &Path, config: &TermComConfig) -> TermComResult<()> {
         let content = toml::to_string_pretty(config).map_err(|e| TermComError::Config {
@@ -125,6 +136,49 @@ impl ConfigManager {
         })
     }
 
+    /// Apply an edit to the configuration file at a specific path
+    ///
+    /// A missing file starts from the default configuration. Everything the
+    /// edit does not touch is written back unchanged, and nothing is written
+    /// if the edit fails.
+    pub fn update_config_at_path<T>(
+        &self,
+        path: &Path,
+        edit: impl FnOnce(&mut TermComConfig) -> TermComResult<T>,
+    ) -> TermComResult<T> {
+        let mut config = if path.exists() {
+            self.load_config_from_path(path)?
+        } else {
+            TermComConfig::default()
+        };
+        
+        let result = edit(&mut config)?;
+        
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| TermComError::Config {
+                message: format!("Failed to create config directory {}: {}", parent.display(), e),
+            })?;
+        }
+        self.save_config_to_path(path, &config)?;
+        
+        Ok(result)
+    }
+
+    /// Get the project config file that edits should go to
+    ///
+    /// This is the nearest existing project config, or a new one in the
+    /// current directory.
+    pub fn project_config_target(&self) -> TermComResult<PathBuf> {
+        if let Some(path) = &self.project_config_path {
+            return Ok(path.clone());
+        }
+        
+        let current_dir = std::env::current_dir().map_err(|e| TermComError::Config {
+            message: format!("Failed to get current directory: {}", e),
+        })?;
+        Ok(current_dir.join(".termcom").join("config.toml"))
+    }
+
     /// Create default project configuration
     pub fn init_project_config(&self, path: &Path) -> TermComResult<()> {
         let config_dir = path.join(".termcom");
@@ -144,16 +198,7 @@ impl ConfigManager {
             global: crate::domain::config::GlobalConfig::default(),
             devices: vec![
                 crate::domain::config::DeviceConfig {
-                    name: "example_serial".to_string(),
                     description: "Example serial device".to_string(),
-                    connection: crate::domain::config::ConnectionConfig::Serial {
-                        port: "/dev/ttyUSB0".to_string(),
-                        baud_rate: 9600,
-                        data_bits: 8,
-                        stop_bits: 1,
-                        parity: crate::domain::config::ParityConfig::None,
-                        flow_control: crate::domain::config::FlowControlConfig::None,
-                    },
                     commands: vec![
                         crate::domain::config::CustomCommand {
                             name: "status".to_string(),
@@ -163,17 +208,30 @@ impl ConfigManager {
                             timeout_ms: 1000,
                         },
                     ],
+                    ..crate::domain::config::DeviceConfig::new(
+                        "example_serial",
+                        crate::domain::config::ConnectionConfig::Serial {
+                            port: "/dev/ttyUSB0".to_string(),
+                            baud_rate: 9600,
+                            data_bits: 8,
+                            stop_bits: 1,
+                            parity: crate::domain::config::ParityConfig::None,
+                            flow_control: crate::domain::config::FlowControlConfig::None,
+                            rs485: None,
+                        },
+                    )
                 },
                 crate::domain::config::DeviceConfig {
-                    name: "example_tcp".to_string(),
                     description: "Example TCP device".to_string(),
-                    connection: crate::domain::config::ConnectionConfig::Tcp {
-                        host: "192.168.1.100".to_string(),
-                        port: 8080,
-                        timeout_ms: 3000,
-                        keep_alive: true,
-                    },
-                    commands: Vec::new(),
+                    ..crate::domain::config::DeviceConfig::new(
+                        "example_tcp",
+                        crate::domain::config::ConnectionConfig::Tcp {
+                            host: "192.168.1.100".to_string(),
+                            port: 8080,
+                            timeout_ms: 3000,
+                            keep_alive: true,
+                        },
+                    )
                 },
             ],
         };
@@ -220,6 +278,34 @@ mod tests {
         assert!(config.devices.is_empty());
     }
 
+    #[test]
+    fn test_load_device_from_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let device_file = temp_dir.path().join("board.toml");
+        fs::write(&device_file, r#"
+name = "board"
+auto_reconnect = true
+tags = ["lab"]
+
+[connection]
+type = "serial"
+port = "/dev/ttyUSB1"
+baud_rate = 115200
+
+[properties]
+firmware = "1.2.0"
+"#).unwrap();
+        
+        let manager = ConfigManager::new().unwrap();
+        let device = manager.load_device_from_path(&device_file).unwrap();
+        
+        assert_eq!(device.name, "board");
+        assert!(device.auto_reconnect);
+        assert_eq!(device.tags, vec!["lab".to_string()]);
+        assert_eq!(device.properties.get("firmware"), Some(&"1.2.0".to_string()));
+        assert!(manager.load_device_from_path(&temp_dir.path().join("missing.toml")).is_err());
+    }
+
     #[test]
     fn test_init_project_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -234,4 +320,35 @@ mod tests {
         let config: TermComConfig = toml::from_str(&content).unwrap();
         assert_eq!(config.devices.len(), 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_update_config_at_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ConfigManager::new().unwrap();
+        manager.init_project_config(temp_dir.path()).unwrap();
+        let config_file = temp_dir.path().join(".termcom").join("config.toml");
+        
+        let removed = manager
+            .update_config_at_path(&config_file, |config| config.remove_device("example_tcp"))
+            .unwrap();
+        assert_eq!(removed.name, "example_tcp");
+        
+        // A failed edit leaves the file untouched
+        assert!(manager
+            .update_config_at_path(&config_file, |config| config.remove_device("missing"))
+            .is_err());
+        
+        let config = manager.load_config_from_path(&config_file).unwrap();
+        assert_eq!(config.devices.len(), 1);
+        assert_eq!(config.devices[0].name, "example_serial");
+        
+        // A missing file starts from the defaults
+        let new_file = temp_dir.path().join("nested").join("config.toml");
+        manager
+            .update_config_at_path(&new_file, |config| config.set_value("max_sessions", "3"))
+            .unwrap();
+        let config = manager.load_config_from_path(&new_file).unwrap();
+        assert_eq!(config.global.max_sessions, 3);
+        assert!(config.devices.is_empty());
+    }
+}
@@ -1,5 +1,12 @@
 // Infrastructure module - External dependencies and adapters
 pub mod serial;
 pub mod tcp;
+pub mod udp;
+pub mod process;
+#[cfg(unix)]
+pub mod pty;
+#[cfg(unix)]
+pub mod unix;
 pub mod logging;
+pub mod queue;
 pub mod config;
\ No newline at end of file
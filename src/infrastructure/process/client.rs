@@ -0,0 +1,303 @@
+use crate::domain::{config::{ConnectionConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver, QueueSender};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{ChildStdin, Command};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{debug, info, warn};
+
+/// How long to wait for buffered output after the process exits
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Output stream of a child process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl std::fmt::Display for OutputStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputStream::Stdout => write!(f, "stdout"),
+            OutputStream::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+/// What happened in a child process
+#[derive(Debug, Clone)]
+pub enum ProcessEvent {
+    /// The process wrote to one of its output streams
+    Output { stream: OutputStream, data: Vec<u8> },
+    /// The process exited on its own
+    Exited(ExitStatus),
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessMessage {
+    pub timestamp: std::time::SystemTime,
+    pub event: ProcessEvent,
+}
+
+/// Child process driven through its standard streams
+///
+/// Everything the process writes to stdout or stderr is reported as output
+/// and sent data is written to its stdin. The process is killed when the
+/// client is closed or dropped.
+pub struct ProcessClient {
+    command: String,
+    pid: Option<u32>,
+    stdin: Mutex<Option<ChildStdin>>,
+    kill_sender: Option<oneshot::Sender<()>>,
+    event_receiver: Option<QueueReceiver<ProcessMessage>>,
+    /// Events discarded because they were not received in time
+    dropped: DropCounter,
+    _wait_handle: tokio::task::JoinHandle<()>,
+}
+
+impl ProcessClient {
+    pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+
+    /// Start the process with its events buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, output is not read
+    /// until there is room again, so the process stalls once its pipes fill.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (command, args, env, cwd) = match config {
+            ConnectionConfig::Process { command, args, env, cwd } => (command, args, env, cwd),
+            _ => return Err(TermComError::Communication {
+                message: "Invalid connection type for process client".to_string(),
+            }),
+        };
+
+        let mut builder = Command::new(command);
+        builder
+            .args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = cwd {
+            builder.current_dir(cwd);
+        }
+
+        let mut child = builder.spawn().map_err(|e| TermComError::Communication {
+            message: format!("Failed to start '{}': {}", command, e),
+        })?;
+        let pid = child.id();
+
+        info!("Started '{}' (pid {})", command, pid.map_or("unknown".to_string(), |pid| pid.to_string()));
+
+        let stdin = child.stdin.take();
+        let (event_sender, event_receiver) = queue::bounded(queue);
+        let readers = [
+            child.stdout.take().map(|stdout| Self::spawn_reader(stdout, OutputStream::Stdout, event_sender.clone())),
+            child.stderr.take().map(|stderr| Self::spawn_reader(stderr, OutputStream::Stderr, event_sender.clone())),
+        ];
+
+        let (kill_sender, kill_receiver) = oneshot::channel();
+        let wait_command = command.clone();
+        let wait_handle = tokio::spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => status,
+                _ = kill_receiver => {
+                    if let Err(e) = child.kill().await {
+                        warn!("Failed to kill '{}': {}", wait_command, e);
+                    }
+                    return;
+                }
+            };
+
+            // Report output written just before exiting ahead of the exit itself
+            for reader in readers.into_iter().flatten() {
+                let _ = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, reader).await;
+            }
+
+            match status {
+                Ok(status) => {
+                    info!("'{}' exited: {}", wait_command, status);
+                    let _ = event_sender.send(ProcessMessage {
+                        timestamp: std::time::SystemTime::now(),
+                        event: ProcessEvent::Exited(status),
+                    }).await;
+                }
+                Err(e) => warn!("Failed to wait for '{}': {}", wait_command, e),
+            }
+        });
+
+        Ok(Self {
+            command: command.clone(),
+            pid,
+            stdin: Mutex::new(stdin),
+            kill_sender: Some(kill_sender),
+            dropped: event_receiver.drop_counter(),
+            event_receiver: Some(event_receiver),
+            _wait_handle: wait_handle,
+        })
+    }
+
+    fn spawn_reader(
+        mut reader: impl AsyncRead + Unpin + Send + 'static,
+        stream: OutputStream,
+        sender: QueueSender<ProcessMessage>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 4096];
+
+            loop {
+                match reader.read(&mut buffer).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        debug!("Read {} bytes from {}", n, stream);
+                        let message = ProcessMessage {
+                            timestamp: std::time::SystemTime::now(),
+                            event: ProcessEvent::Output { stream, data: buffer[..n].to_vec() },
+                        };
+                        if sender.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to read from {}: {}", stream, e);
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Write data to the process's stdin
+    pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
+        let mut stdin = self.stdin.lock().await;
+        let writer = stdin.as_mut().ok_or_else(|| TermComError::Communication {
+            message: format!("stdin of '{}' is closed", self.command),
+        })?;
+
+        let result = match writer.write_all(&data).await {
+            Ok(()) => writer.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            // A broken pipe means the process is gone; later sends fail fast
+            *stdin = None;
+            return Err(TermComError::Communication {
+                message: format!("Failed to write to '{}': {}", self.command, e),
+            });
+        }
+
+        debug!("Wrote {} bytes to stdin of '{}'", data.len(), self.command);
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> Option<ProcessMessage> {
+        match self.event_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+
+    /// Take the event receiver so events can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_event_receiver(&mut self) -> Option<QueueReceiver<ProcessMessage>> {
+        self.event_receiver.take()
+    }
+
+    /// Events discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    pub fn get_pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    pub fn get_command(&self) -> &str {
+        &self.command
+    }
+
+    /// Kill the process if it is still running
+    pub async fn close(mut self) -> TermComResult<()> {
+        self.stdin.lock().await.take();
+        if let Some(kill_sender) = self.kill_sender.take() {
+            let _ = kill_sender.send(());
+        }
+        let _ = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut self._wait_handle).await;
+
+        info!("Process '{}' closed", self.command);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_config(script: &str) -> ConnectionConfig {
+        ConnectionConfig::Process {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            env: HashMap::from([("GREETING".to_string(), "hello".to_string())]),
+            cwd: None,
+        }
+    }
+
+    async fn next_event(client: &mut ProcessClient) -> ProcessEvent {
+        tokio::time::timeout(Duration::from_secs(2), client.receive()).await.unwrap().unwrap().event
+    }
+
+    #[tokio::test]
+    async fn test_process_echoes_stdin() {
+        let mut client = ProcessClient::new(&create_test_config("cat")).await.unwrap();
+        assert!(client.get_pid().is_some());
+
+        client.send(b"ping\n".to_vec()).await.unwrap();
+        match next_event(&mut client).await {
+            ProcessEvent::Output { stream, data } => {
+                assert_eq!(stream, OutputStream::Stdout);
+                assert_eq!(data, b"ping\n");
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_process_reports_output_then_exit_code() {
+        let script = "echo \"$GREETING\"; echo oops >&2; exit 3";
+        let mut client = ProcessClient::new(&create_test_config(script)).await.unwrap();
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let status = loop {
+            match next_event(&mut client).await {
+                ProcessEvent::Output { stream: OutputStream::Stdout, data } => stdout.extend(data),
+                ProcessEvent::Output { stream: OutputStream::Stderr, data } => stderr.extend(data),
+                ProcessEvent::Exited(status) => break status,
+            }
+        };
+
+        assert_eq!(stdout, b"hello\n");
+        assert_eq!(stderr, b"oops\n");
+        assert_eq!(status.code(), Some(3));
+        assert!(client.send(b"too late\n".to_vec()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_program_fails_to_start() {
+        let config = ConnectionConfig::Process {
+            command: "termcom-no-such-program".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            cwd: None,
+        };
+        assert!(ProcessClient::new(&config).await.is_err());
+    }
+}
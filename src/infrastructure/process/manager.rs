@@ -0,0 +1,288 @@
+use crate::domain::{config::{DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::process::client::{ProcessClient, ProcessEvent, ProcessMessage};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use std::sync::Arc;
+use tracing::{error, info};
+
+pub type SessionId = String;
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub device_name: String,
+    pub status: SessionStatus,
+    pub created_at: std::time::SystemTime,
+    pub last_activity: std::time::SystemTime,
+    pub command: String,
+    pub pid: Option<u32>,
+    /// Events discarded because they were not received in time
+    pub dropped_messages: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionStatus {
+    Connected,
+    Disconnected,
+    Error(String),
+}
+
+pub struct SessionHandle {
+    client: ProcessClient,
+    info: SessionInfo,
+    _forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.client.dropped_messages(), ..self.info.clone() }
+    }
+}
+
+type SessionMap = Arc<RwLock<HashMap<SessionId, SessionHandle>>>;
+
+pub struct ProcessManager {
+    sessions: SessionMap,
+    max_sessions: usize,
+    queue: QueueConfig,
+    message_receiver: Mutex<mpsc::Receiver<(SessionId, ProcessMessage)>>,
+    message_sender: mpsc::Sender<(SessionId, ProcessMessage)>,
+}
+
+impl ProcessManager {
+    pub fn new(max_sessions: usize) -> Self {
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+
+    /// Create a manager whose sessions buffer process events according to `queue`
+    ///
+    /// Events of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(queue.capacity.max(1));
+
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            max_sessions,
+            queue,
+            message_receiver: Mutex::new(message_receiver),
+            message_sender,
+        }
+    }
+
+    pub async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<SessionId> {
+        let sessions = self.sessions.read().await;
+
+        if sessions.len() >= self.max_sessions {
+            return Err(TermComError::Communication {
+                message: format!("Maximum number of sessions ({}) reached", self.max_sessions),
+            });
+        }
+
+        // Check if device is already connected
+        if sessions.values().any(|handle| handle.info.device_name == device_config.name) {
+            return Err(TermComError::Communication {
+                message: format!("Device '{}' is already connected", device_config.name),
+            });
+        }
+
+        drop(sessions);
+
+        let mut client = ProcessClient::with_queue(&device_config.connection, self.queue).await?;
+        let session_id = format!("process_{}", uuid::Uuid::new_v4().simple());
+
+        let session_info = SessionInfo {
+            id: session_id.clone(),
+            device_name: device_config.name.clone(),
+            status: SessionStatus::Connected,
+            created_at: std::time::SystemTime::now(),
+            last_activity: std::time::SystemTime::now(),
+            command: client.get_command().to_string(),
+            pid: client.get_pid(),
+            dropped_messages: 0,
+        };
+
+        // Hold the lock until the session is inserted so an immediate exit
+        // cannot be reported before the forwarder can find the session
+        let mut sessions = self.sessions.write().await;
+        let forward_handle = Self::spawn_message_forwarder(
+            session_id.clone(),
+            client.take_event_receiver(),
+            self.message_sender.clone(),
+            Arc::clone(&self.sessions),
+        );
+
+        sessions.insert(session_id.clone(), SessionHandle {
+            client,
+            info: session_info,
+            _forward_handle: forward_handle,
+        });
+
+        info!("Created process session '{}' for device '{}'", session_id, device_config.name);
+
+        Ok(session_id)
+    }
+
+    pub async fn close_session(&self, session_id: &SessionId) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle._forward_handle.abort();
+            session_handle.client.close().await?;
+            info!("Closed process session '{}'", session_id);
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_data(&self, session_id: &SessionId, data: Vec<u8>) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.get_mut(session_id) {
+            session_handle.client.send(data).await?;
+            session_handle.info.last_activity = std::time::SystemTime::now();
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_command(&self, session_id: &SessionId, command: &str) -> TermComResult<()> {
+        let data = command.as_bytes().to_vec();
+        self.send_data(session_id, data).await
+    }
+
+    pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(SessionHandle::info)
+    }
+
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.values().map(SessionHandle::info).collect()
+    }
+
+    /// Receive the next output or exit event from any session, tagged with its session ID
+    pub async fn receive_message(&self) -> Option<(SessionId, ProcessMessage)> {
+        self.message_receiver.lock().await.recv().await
+    }
+
+    pub async fn close_all_sessions(&self) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        for (session_id, session_handle) in sessions.drain() {
+            session_handle._forward_handle.abort();
+            if let Err(e) = session_handle.client.close().await {
+                error!("Failed to close session '{}': {}", session_id, e);
+            }
+        }
+
+        info!("Closed all process sessions");
+        Ok(())
+    }
+
+    pub async fn is_session_connected(&self, session_id: &SessionId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(session_id)
+    }
+
+    pub async fn get_session_count(&self) -> usize {
+        let sessions = self.sessions.read().await;
+        sessions.len()
+    }
+
+    pub fn get_max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    fn spawn_message_forwarder(
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<ProcessMessage>>,
+        sender: mpsc::Sender<(SessionId, ProcessMessage)>,
+        sessions: SessionMap,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+
+            while let Some(message) = receiver.recv().await {
+                if matches!(message.event, ProcessEvent::Exited(_)) {
+                    if let Some(handle) = sessions.write().await.get_mut(&session_id) {
+                        handle.info.status = SessionStatus::Disconnected;
+                    }
+                }
+                if sender.send((session_id.clone(), message)).await.is_err() {
+                    return;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::domain::config::ConnectionConfig;
+    use std::time::Duration;
+
+    fn create_test_device_config(name: &str, script: &str) -> DeviceConfig {
+        DeviceConfig {
+            description: "Test process device".to_string(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Process {
+                    command: "sh".to_string(),
+                    args: vec!["-c".to_string(), script.to_string()],
+                    env: HashMap::new(),
+                    cwd: None,
+                },
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_session_round_trip() {
+        let manager = ProcessManager::new(5);
+        let session_id = manager.create_session(&create_test_device_config("echo", "cat")).await.unwrap();
+        let info = manager.get_session_info(&session_id).await.unwrap();
+        assert_eq!(info.command, "sh");
+        assert!(info.pid.is_some());
+
+        manager.send_data(&session_id, b"hello\n".to_vec()).await.unwrap();
+        let (id, message) = tokio::time::timeout(Duration::from_secs(2), manager.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, session_id);
+        assert!(matches!(message.event, ProcessEvent::Output { ref data, .. } if data == b"hello\n"));
+
+        manager.close_session(&session_id).await.unwrap();
+        assert_eq!(manager.get_session_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_exit_marks_session_disconnected() {
+        let manager = ProcessManager::new(5);
+        let session_id = manager.create_session(&create_test_device_config("oneshot", "exit 7")).await.unwrap();
+
+        let (_, message) = tokio::time::timeout(Duration::from_secs(2), manager.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        match message.event {
+            ProcessEvent::Exited(status) => assert_eq!(status.code(), Some(7)),
+            other => panic!("Unexpected event: {:?}", other),
+        }
+
+        let info = manager.get_session_info(&session_id).await.unwrap();
+        assert!(matches!(info.status, SessionStatus::Disconnected));
+    }
+}
@@ -0,0 +1,5 @@
+// Process module - subprocess communication over standard streams
+pub mod client;
+pub mod manager;
+
+pub use manager::ProcessManager;
@@ -0,0 +1,156 @@
+use crate::domain::{config::{ConnectionConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::pty::device::{self, Pty, PtyIo};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Data read from a pseudo-terminal
+#[derive(Debug, Clone)]
+pub struct PtyMessage {
+    pub timestamp: std::time::SystemTime,
+    pub data: Vec<u8>,
+}
+
+/// Client for a pseudo-terminal
+///
+/// Either allocates a new pseudo-terminal whose slave path other programs
+/// open as if it were a serial port, or attaches to an existing terminal
+/// device such as one end of a [`VirtualSerialPair`](super::VirtualSerialPair).
+pub struct PtyClient {
+    io: Arc<PtyIo>,
+    path: PathBuf,
+    link: Option<PathBuf>,
+    _pty: Option<Pty>,
+    message_receiver: Option<QueueReceiver<PtyMessage>>,
+    /// Messages discarded because they were not received in time
+    dropped: DropCounter,
+    _rx_handle: tokio::task::JoinHandle<()>,
+}
+
+impl PtyClient {
+    pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+
+    /// Open the terminal with received data buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, the terminal is
+    /// not read until there is room again and writers on the other side
+    /// stall once the kernel buffer fills.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (path, link) = match config {
+            ConnectionConfig::Pty { path, link } => (path.as_deref(), link.as_deref().map(PathBuf::from)),
+            _ => return Err(TermComError::Communication {
+                message: "Invalid connection type for PTY client".to_string(),
+            }),
+        };
+
+        let (io, path, pty) = match path {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let io = Arc::new(device::attach(&path)?);
+                info!("Attached to pseudo-terminal {}", path.display());
+                (io, path, None)
+            }
+            None => {
+                let pty = Pty::allocate()?;
+                let path = pty.path.clone();
+                let io = Arc::clone(&pty.master);
+                info!("Created pseudo-terminal {}", path.display());
+                (io, path, Some(pty))
+            }
+        };
+
+        if let Some(ref link) = link {
+            device::create_link(&path, link)?;
+            info!("Linked {} to {}", link.display(), path.display());
+        }
+
+        let (message_sender, message_receiver) = queue::bounded(queue);
+        let rx_io = Arc::clone(&io);
+        let rx_path = path.clone();
+        let rx_handle = tokio::spawn(async move {
+            let mut buffer = vec![0u8; 4096];
+
+            loop {
+                match rx_io.read(&mut buffer).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        debug!("Received {} bytes from {}", n, rx_path.display());
+                        let message = PtyMessage {
+                            timestamp: std::time::SystemTime::now(),
+                            data: buffer[..n].to_vec(),
+                        };
+                        if message_sender.send(message).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        // The other side hanging up reads as EIO
+                        warn!("Failed to read from {}: {}", rx_path.display(), e);
+                        break;
+                    }
+                }
+            }
+
+            info!("Pseudo-terminal {} closed", rx_path.display());
+        });
+
+        Ok(Self {
+            io,
+            path,
+            link,
+            _pty: pty,
+            dropped: message_receiver.drop_counter(),
+            message_receiver: Some(message_receiver),
+            _rx_handle: rx_handle,
+        })
+    }
+
+    pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
+        self.io.write_all(&data).await.map_err(|e| TermComError::Communication {
+            message: format!("Failed to write to {}: {}", self.path.display(), e),
+        })?;
+
+        debug!("Sent {} bytes to {}", data.len(), self.path.display());
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> Option<PtyMessage> {
+        match self.message_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+
+    /// Take the message receiver so messages can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_message_receiver(&mut self) -> Option<QueueReceiver<PtyMessage>> {
+        self.message_receiver.take()
+    }
+
+    /// Messages discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    /// Path of the terminal device other programs open
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_link(&self) -> Option<&Path> {
+        self.link.as_deref()
+    }
+
+    pub async fn close(self) -> TermComResult<()> {
+        self._rx_handle.abort();
+        if let Some(ref link) = self.link {
+            device::remove_link(&self.path, link);
+        }
+        info!("Pseudo-terminal {} released", self.path.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use crate::domain::error::{TermComError, TermComResult};
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::unix::AsyncFd;
+
+/// Non-blocking handle on one side of a pseudo-terminal
+pub struct PtyIo {
+    fd: AsyncFd<File>,
+}
+
+impl PtyIo {
+    fn new(file: File) -> io::Result<Self> {
+        let flags = fcntl(file.as_raw_fd(), FcntlArg::F_GETFL)?;
+        let flags = OFlag::from_bits_truncate(flags) | OFlag::O_NONBLOCK;
+        fcntl(file.as_raw_fd(), FcntlArg::F_SETFL(flags))?;
+
+        Ok(Self { fd: AsyncFd::new(file)? })
+    }
+
+    /// Read whatever is available, waiting until at least one byte is
+    pub async fn read(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.fd.readable().await?;
+            match guard.try_io(|fd| fd.get_ref().read(buffer)) {
+                Ok(result) => return result,
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    pub async fn write_all(&self, mut data: &[u8]) -> io::Result<()> {
+        while !data.is_empty() {
+            let mut guard = self.fd.writable().await?;
+            match guard.try_io(|fd| fd.get_ref().write(data)) {
+                Ok(Ok(0)) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(Ok(n)) => data = &data[n..],
+                Ok(Err(e)) => return Err(e),
+                Err(_would_block) => continue,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A newly allocated pseudo-terminal
+///
+/// The master side is used for I/O while the slave side is what other
+/// programs open through `path`. The slave stays open for the lifetime of
+/// the value so the master does not see a hangup whenever a program closes
+/// its end.
+pub struct Pty {
+    pub master: Arc<PtyIo>,
+    pub path: PathBuf,
+    _slave: File,
+}
+
+impl Pty {
+    pub fn allocate() -> TermComResult<Self> {
+        let pty = nix::pty::openpty(None, None).map_err(|e| TermComError::Communication {
+            message: format!("Failed to allocate pseudo-terminal: {}", e),
+        })?;
+
+        // SAFETY: openpty returned two fresh descriptors that nothing else owns
+        let (master, slave) = unsafe { (File::from_raw_fd(pty.master), File::from_raw_fd(pty.slave)) };
+
+        make_raw(&slave)?;
+        let path = nix::unistd::ttyname(slave.as_raw_fd()).map_err(|e| TermComError::Communication {
+            message: format!("Failed to resolve pseudo-terminal path: {}", e),
+        })?;
+
+        Ok(Self {
+            master: Arc::new(PtyIo::new(master)?),
+            path,
+            _slave: slave,
+        })
+    }
+}
+
+/// Open an existing terminal device in raw mode
+pub fn attach(path: &Path) -> TermComResult<PtyIo> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(OFlag::O_NOCTTY.bits())
+        .open(path)
+        .map_err(|e| TermComError::Communication {
+            message: format!("Failed to open {}: {}", path.display(), e),
+        })?;
+
+    make_raw(&file).map_err(|_| TermComError::Communication {
+        message: format!("{} is not a terminal", path.display()),
+    })?;
+
+    Ok(PtyIo::new(file)?)
+}
+
+/// Point `link` at `target`, replacing an existing symlink but no other file
+pub fn create_link(target: &Path, link: &Path) -> TermComResult<()> {
+    if link.symlink_metadata().is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        std::fs::remove_file(link)?;
+    }
+
+    std::os::unix::fs::symlink(target, link).map_err(|e| TermComError::Communication {
+        message: format!("Failed to link {} to {}: {}", link.display(), target.display(), e),
+    })
+}
+
+/// Remove `link` if it still points at `target`
+pub fn remove_link(target: &Path, link: &Path) {
+    if std::fs::read_link(link).is_ok_and(|current| current == target) {
+        let _ = std::fs::remove_file(link);
+    }
+}
+
+/// Turn off echo, line editing and byte translation on a terminal
+fn make_raw(file: &File) -> TermComResult<()> {
+    let mut termios = tcgetattr(file.as_raw_fd()).map_err(io::Error::from)?;
+    cfmakeraw(&mut termios);
+    tcsetattr(file.as_raw_fd(), SetArg::TCSANOW, &termios).map_err(io::Error::from)?;
+    Ok(())
+}
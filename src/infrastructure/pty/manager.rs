@@ -0,0 +1,276 @@
+use crate::domain::{config::{DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::pty::client::{PtyClient, PtyMessage};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use std::sync::Arc;
+use tracing::{error, info};
+
+pub type SessionId = String;
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub device_name: String,
+    pub status: SessionStatus,
+    pub created_at: std::time::SystemTime,
+    pub last_activity: std::time::SystemTime,
+    pub path: PathBuf,
+    /// Data discarded because they were not received in time
+    pub dropped_messages: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionStatus {
+    Connected,
+    Disconnected,
+    Error(String),
+}
+
+pub struct SessionHandle {
+    client: PtyClient,
+    info: SessionInfo,
+    _forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.client.dropped_messages(), ..self.info.clone() }
+    }
+}
+
+type SessionMap = Arc<RwLock<HashMap<SessionId, SessionHandle>>>;
+
+pub struct PtyManager {
+    sessions: SessionMap,
+    max_sessions: usize,
+    queue: QueueConfig,
+    message_receiver: Mutex<mpsc::Receiver<(SessionId, PtyMessage)>>,
+    message_sender: mpsc::Sender<(SessionId, PtyMessage)>,
+}
+
+impl PtyManager {
+    pub fn new(max_sessions: usize) -> Self {
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+
+    /// Create a manager whose sessions buffer data according to `queue`
+    ///
+    /// Data of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(queue.capacity.max(1));
+
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            max_sessions,
+            queue,
+            message_receiver: Mutex::new(message_receiver),
+            message_sender,
+        }
+    }
+
+    pub async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<SessionId> {
+        let sessions = self.sessions.read().await;
+
+        if sessions.len() >= self.max_sessions {
+            return Err(TermComError::Communication {
+                message: format!("Maximum number of sessions ({}) reached", self.max_sessions),
+            });
+        }
+
+        // Check if device is already connected
+        if sessions.values().any(|handle| handle.info.device_name == device_config.name) {
+            return Err(TermComError::Communication {
+                message: format!("Device '{}' is already connected", device_config.name),
+            });
+        }
+
+        drop(sessions);
+
+        let mut client = PtyClient::with_queue(&device_config.connection, self.queue).await?;
+        let session_id = format!("pty_{}", uuid::Uuid::new_v4().simple());
+
+        let session_info = SessionInfo {
+            id: session_id.clone(),
+            device_name: device_config.name.clone(),
+            status: SessionStatus::Connected,
+            created_at: std::time::SystemTime::now(),
+            last_activity: std::time::SystemTime::now(),
+            path: client.get_path().to_path_buf(),
+            dropped_messages: 0,
+        };
+
+        let forward_handle = Self::spawn_message_forwarder(
+            session_id.clone(),
+            client.take_message_receiver(),
+            self.message_sender.clone(),
+            Arc::clone(&self.sessions),
+        );
+
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session_id.clone(), SessionHandle {
+            client,
+            info: session_info,
+            _forward_handle: forward_handle,
+        });
+
+        info!("Created PTY session '{}' for device '{}'", session_id, device_config.name);
+
+        Ok(session_id)
+    }
+
+    pub async fn close_session(&self, session_id: &SessionId) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle._forward_handle.abort();
+            session_handle.client.close().await?;
+            info!("Closed PTY session '{}'", session_id);
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_data(&self, session_id: &SessionId, data: Vec<u8>) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.get_mut(session_id) {
+            session_handle.client.send(data).await?;
+            session_handle.info.last_activity = std::time::SystemTime::now();
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_command(&self, session_id: &SessionId, command: &str) -> TermComResult<()> {
+        let data = command.as_bytes().to_vec();
+        self.send_data(session_id, data).await
+    }
+
+    pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(SessionHandle::info)
+    }
+
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.values().map(SessionHandle::info).collect()
+    }
+
+    /// Receive the next chunk of data from any session, tagged with its session ID
+    pub async fn receive_message(&self) -> Option<(SessionId, PtyMessage)> {
+        self.message_receiver.lock().await.recv().await
+    }
+
+    pub async fn close_all_sessions(&self) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        for (session_id, session_handle) in sessions.drain() {
+            session_handle._forward_handle.abort();
+            if let Err(e) = session_handle.client.close().await {
+                error!("Failed to close session '{}': {}", session_id, e);
+            }
+        }
+
+        info!("Closed all PTY sessions");
+        Ok(())
+    }
+
+    pub async fn is_session_connected(&self, session_id: &SessionId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(session_id)
+    }
+
+    pub async fn get_session_count(&self) -> usize {
+        let sessions = self.sessions.read().await;
+        sessions.len()
+    }
+
+    pub fn get_max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    fn spawn_message_forwarder(
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<PtyMessage>>,
+        sender: mpsc::Sender<(SessionId, PtyMessage)>,
+        sessions: SessionMap,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+
+            while let Some(message) = receiver.recv().await {
+                if sender.send((session_id.clone(), message)).await.is_err() {
+                    return;
+                }
+            }
+
+            // The reader stopped, so the other side hung up
+            if let Some(handle) = sessions.write().await.get_mut(&session_id) {
+                handle.info.status = SessionStatus::Disconnected;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::ConnectionConfig;
+    use crate::infrastructure::pty::device;
+    use std::time::Duration;
+
+    fn create_test_device_config(name: &str, path: Option<String>) -> DeviceConfig {
+        DeviceConfig {
+            description: "Test PTY device".to_string(),
+            ..DeviceConfig::new(name, ConnectionConfig::Pty { path, link: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pty_session_round_trip() {
+        let manager = PtyManager::new(5);
+        let session_id = manager.create_session(&create_test_device_config("simulator", None)).await.unwrap();
+        let path = manager.get_session_info(&session_id).await.unwrap().path;
+
+        // Play the program under test on the slave side
+        let program = device::attach(&path).unwrap();
+        program.write_all(b"boot ok").await.unwrap();
+
+        let (id, message) = tokio::time::timeout(Duration::from_secs(2), manager.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, session_id);
+        assert_eq!(message.data, b"boot ok");
+
+        manager.send_data(&session_id, b"reset\n".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let n = tokio::time::timeout(Duration::from_secs(2), program.read(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(&buffer[..n], b"reset\n");
+
+        manager.close_session(&session_id).await.unwrap();
+        assert_eq!(manager.get_session_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_attach_rejects_non_terminals() {
+        let manager = PtyManager::new(5);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let config = create_test_device_config("plain", Some(file.path().display().to_string()));
+
+        let error = manager.create_session(&config).await.unwrap_err();
+        assert!(error.to_string().contains("not a terminal"));
+    }
+}
@@ -0,0 +1,8 @@
+// PTY module - pseudo-terminal communication implementation
+pub mod client;
+pub mod device;
+pub mod manager;
+pub mod pair;
+
+pub use manager::PtyManager;
+pub use pair::VirtualSerialPair;
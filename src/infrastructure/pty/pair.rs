@@ -0,0 +1,125 @@
+use crate::domain::error::TermComResult;
+use crate::infrastructure::pty::device::{self, Pty, PtyIo};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Two pseudo-terminals whose data is relayed to each other, like `socat pty,pty`
+///
+/// Whatever a program writes to one path can be read from the other, which
+/// makes the pair behave like two serial ports joined by a null-modem cable.
+/// The relay stops when the pair is dropped.
+pub struct VirtualSerialPair {
+    ends: [Pty; 2],
+    links: [Option<PathBuf>; 2],
+    relay_handles: [tokio::task::JoinHandle<()>; 2],
+}
+
+impl VirtualSerialPair {
+    /// Create a pair of pseudo-terminals
+    pub fn open() -> TermComResult<Self> {
+        Self::open_with_links(None, None)
+    }
+
+    /// Create a pair of pseudo-terminals with symlinks pointing at each end
+    pub fn open_with_links(first_link: Option<&Path>, second_link: Option<&Path>) -> TermComResult<Self> {
+        let first = Pty::allocate()?;
+        let second = Pty::allocate()?;
+
+        let links = [first_link.map(Path::to_path_buf), second_link.map(Path::to_path_buf)];
+        for (end, link) in [&first, &second].into_iter().zip(&links) {
+            if let Some(link) = link {
+                device::create_link(&end.path, link)?;
+            }
+        }
+
+        let relay_handles = [
+            Self::spawn_relay(Arc::clone(&first.master), Arc::clone(&second.master)),
+            Self::spawn_relay(Arc::clone(&second.master), Arc::clone(&first.master)),
+        ];
+
+        info!("Virtual serial pair {} <-> {}", first.path.display(), second.path.display());
+
+        Ok(Self {
+            ends: [first, second],
+            links,
+            relay_handles,
+        })
+    }
+
+    /// Path of the first end
+    pub fn first_path(&self) -> &Path {
+        &self.ends[0].path
+    }
+
+    /// Path of the second end
+    pub fn second_path(&self) -> &Path {
+        &self.ends[1].path
+    }
+
+    fn spawn_relay(from: Arc<PtyIo>, to: Arc<PtyIo>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 4096];
+
+            loop {
+                let n = match from.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                if let Err(e) = to.write_all(&buffer[..n]).await {
+                    debug!("Virtual serial pair relay stopped: {}", e);
+                    break;
+                }
+            }
+        })
+    }
+}
+
+impl Drop for VirtualSerialPair {
+    fn drop(&mut self) {
+        for handle in &self.relay_handles {
+            handle.abort();
+        }
+        for (end, link) in self.ends.iter().zip(&self.links) {
+            if let Some(link) = link {
+                device::remove_link(&end.path, link);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn read_exact(io: &PtyIo, expected_len: usize) -> Vec<u8> {
+        let mut received = Vec::new();
+        let mut buffer = [0u8; 64];
+        while received.len() < expected_len {
+            let n = tokio::time::timeout(Duration::from_secs(2), io.read(&mut buffer)).await.unwrap().unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_pair_relays_both_ways() {
+        let dir = tempfile::tempdir().unwrap();
+        let first_link = dir.path().join("ttyV0");
+        let pair = VirtualSerialPair::open_with_links(Some(&first_link), None).unwrap();
+        assert_eq!(std::fs::read_link(&first_link).unwrap(), pair.first_path());
+
+        let first = device::attach(&first_link).unwrap();
+        let second = device::attach(pair.second_path()).unwrap();
+
+        first.write_all(b"ping\r\n").await.unwrap();
+        assert_eq!(read_exact(&second, 6).await, b"ping\r\n");
+
+        second.write_all(&[0x00, 0xff, 0x03]).await.unwrap();
+        assert_eq!(read_exact(&first, 3).await, [0x00, 0xff, 0x03]);
+
+        drop(pair);
+        assert!(first_link.symlink_metadata().is_err());
+    }
+}
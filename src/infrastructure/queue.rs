@@ -0,0 +1,325 @@
+use crate::domain::config::{OverflowPolicy, QueueConfig};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+/// What happened when a message was queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// The message was queued
+    Queued,
+    /// The message was queued after the oldest one was discarded
+    DroppedOldest,
+    /// The queue was full and the message was discarded
+    DroppedNewest,
+}
+
+impl Delivery {
+    /// Whether a message was lost
+    pub fn dropped(self) -> bool {
+        self != Delivery::Queued
+    }
+}
+
+/// The receiver is gone; holds the message that could not be queued
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Closed<T>(pub T);
+
+struct State<T> {
+    items: VecDeque<T>,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    config: QueueConfig,
+    dropped: Arc<AtomicU64>,
+    /// Wakes the receiver when a message is queued or the last sender leaves
+    readable: Notify,
+    /// Wakes async senders waiting for room
+    writable: Notify,
+    /// Wakes senders on plain threads waiting for room
+    writable_blocking: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Queue `item` according to the overflow policy
+    ///
+    /// Gives the message back when the policy is to block and the queue is full.
+    fn push(&self, state: &mut State<T>, item: T) -> Result<Delivery, T> {
+        if state.items.len() < self.config.capacity {
+            state.items.push_back(item);
+            self.readable.notify_one();
+            return Ok(Delivery::Queued);
+        }
+
+        match self.config.overflow {
+            OverflowPolicy::Block => Err(item),
+            OverflowPolicy::DropOldest => {
+                state.items.pop_front();
+                state.items.push_back(item);
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                self.readable.notify_one();
+                Ok(Delivery::DroppedOldest)
+            }
+            OverflowPolicy::DropNewest => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                Ok(Delivery::DroppedNewest)
+            }
+        }
+    }
+
+    fn wake_senders(&self) {
+        self.writable.notify_waiters();
+        self.writable_blocking.notify_all();
+    }
+}
+
+/// Create a bounded message queue
+///
+/// A capacity of zero is treated as one.
+pub fn bounded<T>(config: QueueConfig) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::new(),
+            senders: 1,
+            receiver_alive: true,
+        }),
+        config: QueueConfig { capacity: config.capacity.max(1), ..config },
+        dropped: Arc::default(),
+        readable: Notify::new(),
+        writable: Notify::new(),
+        writable_blocking: Condvar::new(),
+    });
+
+    (QueueSender { shared: Arc::clone(&shared) }, QueueReceiver { shared })
+}
+
+/// Sending side of a bounded queue
+pub struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueSender<T> {
+    /// Queue a message, waiting for room if the policy is to block
+    pub async fn send(&self, mut item: T) -> Result<Delivery, Closed<T>> {
+        loop {
+            let writable = self.shared.writable.notified();
+            tokio::pin!(writable);
+            // Register before looking, so room made in between is not missed
+            writable.as_mut().enable();
+
+            {
+                let mut state = self.shared.lock();
+                if !state.receiver_alive {
+                    return Err(Closed(item));
+                }
+                match self.shared.push(&mut state, item) {
+                    Ok(delivery) => return Ok(delivery),
+                    Err(returned) => item = returned,
+                }
+            }
+
+            writable.await;
+        }
+    }
+
+    /// Queue a message from a thread outside the async runtime
+    ///
+    /// Blocks the thread while waiting for room.
+    pub fn blocking_send(&self, mut item: T) -> Result<Delivery, Closed<T>> {
+        let mut state = self.shared.lock();
+        loop {
+            if !state.receiver_alive {
+                return Err(Closed(item));
+            }
+            match self.shared.push(&mut state, item) {
+                Ok(delivery) => return Ok(delivery),
+                Err(returned) => item = returned,
+            }
+            state = self.shared.writable_blocking.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Messages discarded so far because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Count messages that were lost before they reached the queue
+    pub fn add_dropped(&self, count: u64) {
+        self.shared.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Whether the receiver is gone
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.shared.readable.notify_one();
+        }
+    }
+}
+
+/// Receiving side of a bounded queue
+pub struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueReceiver<T> {
+    /// Receive the next message, or `None` once every sender is gone and
+    /// the queue is empty
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            {
+                let mut state = self.shared.lock();
+                if let Some(item) = state.items.pop_front() {
+                    drop(state);
+                    self.shared.wake_senders();
+                    return Some(item);
+                }
+                if state.senders == 0 {
+                    return None;
+                }
+            }
+
+            self.shared.readable.notified().await;
+        }
+    }
+
+    /// Take the next message if one is queued
+    pub fn try_recv(&mut self) -> Option<T> {
+        let item = self.shared.lock().items.pop_front();
+        if item.is_some() {
+            self.shared.wake_senders();
+        }
+        item
+    }
+
+    /// Messages discarded so far because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Messages waiting to be received
+    pub fn len(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+
+    /// Whether no messages are waiting
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counter of discarded messages that stays readable after the
+    /// receiver is moved away
+    pub fn drop_counter(&self) -> DropCounter {
+        DropCounter(Arc::clone(&self.shared.dropped))
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receiver_alive = false;
+        state.items.clear();
+        drop(state);
+        self.shared.wake_senders();
+    }
+}
+
+/// Read-only view of how many messages a queue discarded
+#[derive(Debug, Clone, Default)]
+pub struct DropCounter(Arc<AtomicU64>);
+
+impl DropCounter {
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn queue(capacity: usize, overflow: OverflowPolicy) -> (QueueSender<u32>, QueueReceiver<u32>) {
+        bounded(QueueConfig { capacity, overflow })
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let (sender, mut receiver) = queue(2, OverflowPolicy::DropOldest);
+        for i in 0..5 {
+            sender.send(i).await.unwrap();
+        }
+        assert_eq!(receiver.dropped(), 3);
+        assert_eq!((receiver.recv().await, receiver.recv().await), (Some(3), Some(4)));
+
+        let (sender, mut receiver) = queue(2, OverflowPolicy::DropNewest);
+        assert_eq!(sender.send(0).await, Ok(Delivery::Queued));
+        assert_eq!(sender.send(1).await, Ok(Delivery::Queued));
+        assert_eq!(sender.blocking_send(2), Ok(Delivery::DroppedNewest));
+        assert_eq!(sender.dropped(), 1);
+        let counter = receiver.drop_counter();
+        assert_eq!((receiver.recv().await, receiver.recv().await), (Some(0), Some(1)));
+
+        // Messages lost before reaching the queue count as well
+        sender.add_dropped(2);
+        drop(sender);
+        assert_eq!(receiver.recv().await, None);
+        assert_eq!(counter.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_room() {
+        let (sender, mut receiver) = queue(1, OverflowPolicy::Block);
+        sender.send(0).await.unwrap();
+
+        let blocked = tokio::spawn(async move {
+            sender.send(1).await.unwrap();
+            sender
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv().await, Some(0));
+        let sender = tokio::time::timeout(Duration::from_secs(1), blocked).await.unwrap().unwrap();
+        assert_eq!(receiver.try_recv(), Some(1));
+        assert_eq!(receiver.dropped(), 0);
+
+        // Threads outside the runtime block too
+        sender.send(2).await.unwrap();
+        let thread = std::thread::spawn(move || sender.blocking_send(3));
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+        assert_eq!(thread.join().unwrap(), Ok(Delivery::Queued));
+
+        // Nobody is left to receive
+        let (sender, receiver) = queue(1, OverflowPolicy::Block);
+        sender.send(0).await.unwrap();
+        let blocked = tokio::spawn(async move { sender.send(1).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(receiver);
+        assert_eq!(blocked.await.unwrap(), Err(Closed(1)));
+    }
+}
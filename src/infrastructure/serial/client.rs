@@ -1,10 +1,23 @@
-use crate::domain::{config::ConnectionConfig, error::{TermComError, TermComResult}};
+use crate::domain::{
+    config::{ConnectionConfig, FlowControlConfig, ParityConfig, QueueConfig, Rs485Config},
+    error::{TermComError, TermComResult},
+};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver, QueueSender};
+use crate::infrastructure::serial::ports::resolve_port;
+use crate::infrastructure::serial::rs485::{self, EchoFilter, Rs485Control};
 use serialport::SerialPort;
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+/// How often the reader thread checks whether the client was closed
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Largest chunk handed out by one read
+const READ_BUFFER_SIZE: usize = 4096;
+
 #[derive(Debug, Clone)]
 pub struct SerialMessage {
     pub timestamp: std::time::SystemTime,
@@ -14,156 +27,124 @@ pub struct SerialMessage {
 
 #[derive(Debug, Clone)]
 pub enum MessageDirection {
-    Sent,
     Received,
+    /// The port stopped working, e.g. because the adapter was unplugged;
+    /// `data` holds the reason
+    Disconnected,
+}
+
+/// State of the modem status input lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModemStatus {
+    /// Clear To Send
+    pub cts: bool,
+    /// Data Set Ready
+    pub dsr: bool,
+    /// Ring Indicator
+    pub ri: bool,
+    /// Carrier Detect
+    pub cd: bool,
 }
 
 pub struct SerialClient {
     port: Arc<Mutex<Box<dyn SerialPort + Send>>>,
-    tx_sender: mpsc::UnboundedSender<Vec<u8>>,
-    message_receiver: mpsc::UnboundedReceiver<SerialMessage>,
-    _tx_handle: tokio::task::JoinHandle<()>,
-    _rx_handle: tokio::task::JoinHandle<()>,
+    /// Path of the opened port, after resolving a USB match
+    port_name: String,
+    /// How the transmitter is switched, for RS-485 ports
+    rs485_control: Option<Rs485Control>,
+    tx_sender: mpsc::Sender<Vec<u8>>,
+    message_receiver: Option<QueueReceiver<SerialMessage>>,
+    /// Messages discarded because nobody kept up with the port
+    dropped: DropCounter,
+    workers: Workers,
 }
 
 impl SerialClient {
     pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
-        let serial_config = match config {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+    
+    /// Open the port with messages buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, the port is not
+    /// read until there is room again. Sends wait once `queue.capacity`
+    /// writes are queued, so a slow line holds up the sender.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (port_name, serial_config, rs485_config) = match config {
             ConnectionConfig::Serial { 
                 port, 
                 baud_rate, 
                 data_bits, 
                 stop_bits, 
                 parity, 
-                flow_control 
+                flow_control,
+                rs485,
             } => {
-                let mut builder = serialport::new(port, *baud_rate);
-                
-                builder = builder.data_bits(match data_bits {
-                    5 => serialport::DataBits::Five,
-                    6 => serialport::DataBits::Six,
-                    7 => serialport::DataBits::Seven,
-                    8 => serialport::DataBits::Eight,
-                    _ => return Err(TermComError::Communication {
-                        message: format!("Invalid data bits: {}", data_bits),
-                    }),
-                });
-                
-                builder = builder.stop_bits(match stop_bits {
-                    1 => serialport::StopBits::One,
-                    2 => serialport::StopBits::Two,
-                    _ => return Err(TermComError::Communication {
-                        message: format!("Invalid stop bits: {}", stop_bits),
-                    }),
-                });
-                
-                builder = builder.parity(match parity {
-                    crate::domain::config::ParityConfig::None => serialport::Parity::None,
-                    crate::domain::config::ParityConfig::Even => serialport::Parity::Even,
-                    crate::domain::config::ParityConfig::Odd => serialport::Parity::Odd,
-                });
-                
-                builder = builder.flow_control(match flow_control {
-                    crate::domain::config::FlowControlConfig::None => serialport::FlowControl::None,
-                    crate::domain::config::FlowControlConfig::Software => serialport::FlowControl::Software,
-                    crate::domain::config::FlowControlConfig::Hardware => serialport::FlowControl::Hardware,
-                });
-                
-                builder = builder.timeout(Duration::from_millis(100));
-                
-                builder
+                let port_name = resolve_port(port)?;
+                let builder = serialport::new(&port_name, *baud_rate)
+                    .data_bits(to_data_bits(*data_bits)?)
+                    .stop_bits(to_stop_bits(*stop_bits)?)
+                    .parity(to_parity(*parity))
+                    .flow_control(to_flow_control(*flow_control))
+                    .timeout(Duration::from_millis(100));
+                (port_name, builder, *rs485)
             }
             _ => return Err(TermComError::Communication {
                 message: "Invalid connection type for serial client".to_string(),
             }),
         };
         
-        let port = serial_config.open().map_err(|e| TermComError::Communication {
-            message: format!("Failed to open serial port: {}", e),
-        })?;
+        let (port, rs485_control) = open_port(serial_config, rs485_config.as_ref())?;
         
         info!("Serial port opened successfully");
         
+        Self::start(port, port_name, rs485_config, rs485_control, queue)
+    }
+    
+    /// Start the reader and writer threads of an open port
+    fn start(
+        port: Box<dyn SerialPort + Send>,
+        port_name: String,
+        rs485_config: Option<Rs485Config>,
+        rs485_control: Option<Rs485Control>,
+        queue: QueueConfig,
+    ) -> TermComResult<Self> {
+        let mut reader = port.try_clone().map_err(|e| TermComError::Communication {
+            message: format!("Failed to clone serial port for reading: {}", e),
+        })?;
+        reader.set_timeout(READ_TIMEOUT)?;
+        
         let port: Arc<Mutex<Box<dyn SerialPort + Send>>> = Arc::new(Mutex::new(port));
-        let (tx_sender, mut tx_receiver) = mpsc::unbounded_channel::<Vec<u8>>();
-        let (message_sender, message_receiver) = mpsc::unbounded_channel::<SerialMessage>();
-        let message_sender_tx = message_sender.clone();
-        let message_sender_rx = message_sender;
-        
-        // Clone port for background tasks
-        let port_tx = Arc::clone(&port);
-        let port_rx = Arc::clone(&port);
-        
-        // TX task - handles outgoing messages
-        let tx_handle = tokio::spawn(async move {
-            while let Some(data) = tx_receiver.recv().await {
-                let mut port = port_tx.lock().await;
-                match port.write_all(&data) {
-                    Ok(_) => {
-                        debug!("Sent {} bytes over serial", data.len());
-                        if let Err(e) = message_sender_tx.send(SerialMessage {
-                            timestamp: std::time::SystemTime::now(),
-                            direction: MessageDirection::Sent,
-                            data,
-                        }) {
-                            error!("Failed to send message to channel: {}", e);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to write to serial port: {}", e);
-                    }
-                }
-            }
-        });
+        let echo = Arc::new(std::sync::Mutex::new(EchoFilter::default()));
+        let suppress_echo = rs485_control == Some(Rs485Control::Software)
+            && rs485_config.as_ref().is_some_and(|config| config.suppress_echo);
+        let (tx_sender, tx_receiver) = mpsc::channel::<Vec<u8>>(queue.capacity.max(1));
+        let (message_sender, message_receiver) = queue::bounded::<SerialMessage>(queue);
+        let stop = Arc::new(AtomicBool::new(false));
         
-        // RX task - handles incoming messages
-        let rx_handle = tokio::spawn(async move {
-            let mut buffer = vec![0u8; 1024];
-            
-            loop {
-                tokio::time::sleep(Duration::from_millis(10)).await;
-                
-                let mut port = port_rx.lock().await;
-                match port.read(&mut buffer) {
-                    Ok(0) => {
-                        // No data available, continue
-                        continue;
-                    }
-                    Ok(n) => {
-                        let data = buffer[..n].to_vec();
-                        debug!("Received {} bytes over serial", n);
-                        
-                        if let Err(e) = message_sender_rx.send(SerialMessage {
-                            timestamp: std::time::SystemTime::now(),
-                            direction: MessageDirection::Received,
-                            data,
-                        }) {
-                            error!("Failed to send received message to channel: {}", e);
-                        }
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                        // Timeout is expected, continue
-                        continue;
-                    }
-                    Err(e) => {
-                        error!("Failed to read from serial port: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+        let writer = Writer {
+            port: Arc::clone(&port),
+            rs485: rs485_config.filter(|_| rs485_control == Some(Rs485Control::Software)),
+            echo: suppress_echo.then(|| Arc::clone(&echo)),
+        };
+        let tx_handle = spawn_thread("serial-tx", move || writer.run(tx_receiver))?;
+        
+        let rx_stop = Arc::clone(&stop);
+        let rx_handle = spawn_thread("serial-rx", move || read_loop(reader, echo, message_sender, rx_stop))?;
         
         Ok(Self {
             port,
+            port_name,
+            rs485_control,
             tx_sender,
-            message_receiver,
-            _tx_handle: tx_handle,
-            _rx_handle: rx_handle,
+            dropped: message_receiver.drop_counter(),
+            message_receiver: Some(message_receiver),
+            workers: Workers { stop, tx: Some(tx_handle), rx: Some(rx_handle) },
         })
     }
     
     pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
-        self.tx_sender.send(data).map_err(|e| TermComError::Communication {
+        self.tx_sender.send(data).await.map_err(|e| TermComError::Communication {
             message: format!("Failed to send data to serial tx channel: {}", e),
         })?;
         
@@ -171,7 +152,17 @@ impl SerialClient {
     }
     
     pub async fn receive(&mut self) -> Option<SerialMessage> {
-        self.message_receiver.recv().await
+        match self.message_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+    
+    /// Take the message receiver so messages can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_message_receiver(&mut self) -> Option<QueueReceiver<SerialMessage>> {
+        self.message_receiver.take()
     }
     
     pub async fn send_command(&self, command: &str) -> TermComResult<()> {
@@ -179,6 +170,115 @@ impl SerialClient {
         self.send(data).await
     }
     
+    /// Drive the Data Terminal Ready line
+    pub async fn set_dtr(&self, level: bool) -> TermComResult<()> {
+        self.port.lock().await.write_data_terminal_ready(level).map_err(|e| TermComError::Communication {
+            message: format!("Failed to set DTR: {}", e),
+        })?;
+        
+        debug!("Set DTR {}", if level { "on" } else { "off" });
+        Ok(())
+    }
+    
+    /// Drive the Request To Send line
+    pub async fn set_rts(&self, level: bool) -> TermComResult<()> {
+        self.port.lock().await.write_request_to_send(level).map_err(|e| TermComError::Communication {
+            message: format!("Failed to set RTS: {}", e),
+        })?;
+        
+        debug!("Set RTS {}", if level { "on" } else { "off" });
+        Ok(())
+    }
+    
+    /// Read the modem status lines
+    pub async fn modem_status(&self) -> TermComResult<ModemStatus> {
+        let mut port = self.port.lock().await;
+        let read_error = |line: &str, e: serialport::Error| TermComError::Communication {
+            message: format!("Failed to read {}: {}", line, e),
+        };
+        
+        Ok(ModemStatus {
+            cts: port.read_clear_to_send().map_err(|e| read_error("CTS", e))?,
+            dsr: port.read_data_set_ready().map_err(|e| read_error("DSR", e))?,
+            ri: port.read_ring_indicator().map_err(|e| read_error("RI", e))?,
+            cd: port.read_carrier_detect().map_err(|e| read_error("CD", e))?,
+        })
+    }
+    
+    /// Hold the line in the break condition for `duration`
+    ///
+    /// The port stays locked for the whole break so queued data is not
+    /// written in the middle of it.
+    pub async fn send_break(&self, duration: Duration) -> TermComResult<()> {
+        let port = self.port.lock().await;
+        port.set_break().map_err(|e| TermComError::Communication {
+            message: format!("Failed to start break: {}", e),
+        })?;
+        
+        tokio::time::sleep(duration).await;
+        
+        port.clear_break().map_err(|e| TermComError::Communication {
+            message: format!("Failed to end break: {}", e),
+        })?;
+        
+        debug!("Sent break for {:?}", duration);
+        Ok(())
+    }
+    
+    /// Apply the line settings of `config` to the open port
+    ///
+    /// Data already written is drained at the old settings first. The port
+    /// name in `config` is ignored.
+    pub async fn reconfigure(&self, config: &ConnectionConfig) -> TermComResult<()> {
+        let ConnectionConfig::Serial { baud_rate, data_bits, stop_bits, parity, flow_control, .. } = config else {
+            return Err(TermComError::Communication {
+                message: "Invalid connection type for serial client".to_string(),
+            });
+        };
+        let data_bits = to_data_bits(*data_bits)?;
+        let stop_bits = to_stop_bits(*stop_bits)?;
+        let setting_error = |setting: &str, e: serialport::Error| TermComError::Communication {
+            message: format!("Failed to set {}: {}", setting, e),
+        };
+        
+        let mut port = self.port.lock().await;
+        port.flush().map_err(|e| TermComError::Communication {
+            message: format!("Failed to drain serial port: {}", e),
+        })?;
+        port.set_baud_rate(*baud_rate).map_err(|e| setting_error("baud rate", e))?;
+        port.set_data_bits(data_bits).map_err(|e| setting_error("data bits", e))?;
+        port.set_stop_bits(stop_bits).map_err(|e| setting_error("stop bits", e))?;
+        port.set_parity(to_parity(*parity)).map_err(|e| setting_error("parity", e))?;
+        port.set_flow_control(to_flow_control(*flow_control)).map_err(|e| setting_error("flow control", e))?;
+        
+        debug!("Reconfigured serial port to {} baud", baud_rate);
+        Ok(())
+    }
+    
+    /// Path of the opened port
+    ///
+    /// For ports configured as a USB match this is the path it resolved to.
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+    
+    /// How the transmitter is switched, when the port is in RS-485 mode
+    pub fn rs485_control(&self) -> Option<Rs485Control> {
+        self.rs485_control
+    }
+    
+    /// Discard data received by the port but not read yet
+    pub async fn clear_input(&self) -> TermComResult<()> {
+        self.port.lock().await.clear(serialport::ClearBuffer::Input).map_err(|e| TermComError::Communication {
+            message: format!("Failed to clear serial input: {}", e),
+        })
+    }
+    
+    /// Baud rate the port is currently running at
+    pub async fn baud_rate(&self) -> TermComResult<u32> {
+        Ok(self.port.lock().await.baud_rate()?)
+    }
+    
     pub async fn is_connected(&self) -> bool {
         // Try to access the port to check if it's still valid
         let _port = self.port.lock().await;
@@ -187,26 +287,238 @@ impl SerialClient {
         true
     }
     
+    /// Messages discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+    
     pub async fn close(self) -> TermComResult<()> {
-        // Drop the sender to signal background tasks to exit
+        // Nobody reads messages from here on; dropping the receiver also
+        // releases threads blocked on a full queue
+        drop(self.message_receiver);
+        
+        // Dropping the sender lets the writer finish queued data and exit
         drop(self.tx_sender);
+        self.workers.join().await;
         
-        // Wait for background tasks to complete
-        if let Err(e) = self._tx_handle.await {
-            warn!("TX task completed with error: {}", e);
+        info!("Serial client closed");
+        Ok(())
+    }
+}
+
+/// Writes queued data on its own thread
+///
+/// Writes block until the port has taken the data, so they are kept off
+/// the async runtime. The port is shared with the control line and
+/// settings operations, which take it between writes.
+struct Writer {
+    port: Arc<Mutex<Box<dyn SerialPort + Send>>>,
+    /// RS-485 settings, when RTS is toggled around each write
+    rs485: Option<Rs485Config>,
+    /// Filter told about each write, when the echo is dropped
+    echo: Option<Arc<std::sync::Mutex<EchoFilter>>>,
+}
+
+impl Writer {
+    fn run(self, mut queue: mpsc::Receiver<Vec<u8>>) {
+        while let Some(data) = queue.blocking_recv() {
+            // The reader has its own handle and sees the echo while the
+            // write is still in progress, so it is expected beforehand
+            if let Some(echo) = &self.echo {
+                echo.lock().unwrap().expect(&data);
+            }
+            
+            let mut port = self.port.blocking_lock();
+            let result = match &self.rs485 {
+                Some(config) => rs485::transmit(&mut **port, &data, config),
+                None => port.write_all(&data),
+            };
+            drop(port);
+            
+            if let Some(echo) = &self.echo {
+                let mut echo = echo.lock().unwrap();
+                match result {
+                    Ok(()) => echo.written(std::time::Instant::now() + rs485::ECHO_TIMEOUT),
+                    Err(_) => echo.clear(),
+                }
+            }
+            if let Err(e) = result {
+                error!("Failed to write to serial port: {}", e);
+                continue;
+            }
+            
+            debug!("Sent {} bytes over serial", data.len());
         }
+    }
+}
+
+/// Read from the port on its own thread until it fails or the client closes
+///
+/// Reads return as soon as data arrives; the timeout only bounds how long
+/// a close waits for the thread.
+fn read_loop(
+    mut reader: Box<dyn SerialPort>,
+    echo: Arc<std::sync::Mutex<EchoFilter>>,
+    messages: QueueSender<SerialMessage>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    
+    while !stop.load(Ordering::Relaxed) {
+        match reader.read(&mut buffer) {
+            Ok(0) => continue,
+            Ok(n) => {
+                let data = echo.lock().unwrap().filter(&buffer[..n], std::time::Instant::now());
+                if data.is_empty() {
+                    debug!("Dropped {} echoed bytes", n);
+                    continue;
+                }
+                debug!("Received {} bytes over serial", data.len());
+                
+                if messages.blocking_send(SerialMessage {
+                    timestamp: std::time::SystemTime::now(),
+                    direction: MessageDirection::Received,
+                    data,
+                }).is_err() {
+                    // Nobody is listening any more
+                    break;
+                }
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(e) => {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                error!("Failed to read from serial port: {}", e);
+                let _ = messages.blocking_send(SerialMessage {
+                    timestamp: std::time::SystemTime::now(),
+                    direction: MessageDirection::Disconnected,
+                    data: e.to_string().into_bytes(),
+                });
+                break;
+            }
+        }
+    }
+}
+
+/// Reader and writer threads of a client
+///
+/// Dropping stops the reader without waiting for it.
+struct Workers {
+    stop: Arc<AtomicBool>,
+    tx: Option<std::thread::JoinHandle<()>>,
+    rx: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Workers {
+    /// Stop the reader and wait for both threads to exit
+    ///
+    /// The writer exits once its queue is closed and drained.
+    async fn join(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let handles: Vec<_> = [self.tx.take(), self.rx.take()].into_iter().flatten().collect();
         
-        self._rx_handle.abort();
+        let joined = tokio::task::spawn_blocking(move || {
+            handles.into_iter().filter_map(|handle| handle.join().err()).count()
+        }).await;
+        match joined {
+            Ok(0) => {}
+            Ok(panicked) => warn!("{} serial I/O thread(s) panicked", panicked),
+            Err(e) => warn!("Failed to wait for serial I/O threads: {}", e),
+        }
+    }
+}
+
+impl Drop for Workers {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn spawn_thread(name: &str, f: impl FnOnce() + Send + 'static) -> TermComResult<std::thread::JoinHandle<()>> {
+    std::thread::Builder::new().name(name.to_string()).spawn(f).map_err(|e| TermComError::Communication {
+        message: format!("Failed to start {} thread: {}", name, e),
+    })
+}
+
+/// Open the port and put it into RS-485 mode when configured
+fn open_port(
+    builder: serialport::SerialPortBuilder,
+    rs485: Option<&Rs485Config>,
+) -> TermComResult<(Box<dyn SerialPort + Send>, Option<Rs485Control>)> {
+    let open_error = |e: serialport::Error| TermComError::Communication {
+        message: format!("Failed to open serial port: {}", e),
+    };
+    
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
         
-        info!("Serial client closed");
-        Ok(())
+        let mut port = builder.open_native().map_err(open_error)?;
+        let control = match rs485 {
+            Some(config) => {
+                let fd = port.as_raw_fd();
+                Some(rs485::enable(&mut port, config, |config| rs485::kernel::enable(fd, config))?)
+            }
+            None => None,
+        };
+        Ok((Box::new(port), control))
+    }
+    
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mut port = builder.open().map_err(open_error)?;
+        let control = match rs485 {
+            Some(config) => Some(rs485::enable(&mut *port, config, |_| {
+                Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "kernel RS-485 mode needs Linux"))
+            })?),
+            None => None,
+        };
+        Ok((port, control))
+    }
+}
+
+fn to_data_bits(data_bits: u8) -> TermComResult<serialport::DataBits> {
+    match data_bits {
+        5 => Ok(serialport::DataBits::Five),
+        6 => Ok(serialport::DataBits::Six),
+        7 => Ok(serialport::DataBits::Seven),
+        8 => Ok(serialport::DataBits::Eight),
+        _ => Err(TermComError::Communication {
+            message: format!("Invalid data bits: {}", data_bits),
+        }),
+    }
+}
+
+fn to_stop_bits(stop_bits: u8) -> TermComResult<serialport::StopBits> {
+    match stop_bits {
+        1 => Ok(serialport::StopBits::One),
+        2 => Ok(serialport::StopBits::Two),
+        _ => Err(TermComError::Communication {
+            message: format!("Invalid stop bits: {}", stop_bits),
+        }),
+    }
+}
+
+fn to_parity(parity: ParityConfig) -> serialport::Parity {
+    match parity {
+        ParityConfig::None => serialport::Parity::None,
+        ParityConfig::Even => serialport::Parity::Even,
+        ParityConfig::Odd => serialport::Parity::Odd,
+    }
+}
+
+fn to_flow_control(flow_control: FlowControlConfig) -> serialport::FlowControl {
+    match flow_control {
+        FlowControlConfig::None => serialport::FlowControl::None,
+        FlowControlConfig::Software => serialport::FlowControl::Software,
+        FlowControlConfig::Hardware => serialport::FlowControl::Hardware,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::config::{ParityConfig, FlowControlConfig};
     
     fn create_test_config() -> ConnectionConfig {
         ConnectionConfig::Serial {
@@ -216,6 +528,7 @@ mod tests {
             stop_bits: 1,
             parity: ParityConfig::None,
             flow_control: FlowControlConfig::None,
+            rs485: None,
         }
     }
     
@@ -228,15 +541,252 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serial_client_round_trip_over_virtual_pair() {
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        let pair = VirtualSerialPair::open().unwrap();
+        let mut config = create_test_config();
+        if let ConnectionConfig::Serial { port, baud_rate, .. } = &mut config {
+            *port = pair.first_path().display().to_string();
+            *baud_rate = 115200;
+        }
+        
+        let mut client = SerialClient::new(&config).await.unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        
+        device.write_all(b"READY\r\n").await.unwrap();
+        let message = tokio::time::timeout(Duration::from_secs(2), client.receive()).await.unwrap().unwrap();
+        assert!(matches!(message.direction, MessageDirection::Received));
+        assert_eq!(message.data, b"READY\r\n");
+        
+        client.send(b"AT\r".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let n = tokio::time::timeout(Duration::from_secs(2), device.read(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(&buffer[..n], b"AT\r");
+        
+        // Sent data takes no room in the receive queue
+        assert!(tokio::time::timeout(Duration::from_millis(100), client.receive()).await.is_err());
+        
+        client.close().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_send_waits_for_a_slow_line() {
+        use crate::domain::config::OverflowPolicy;
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        let pair = VirtualSerialPair::open().unwrap();
+        let mut config = create_test_config();
+        if let ConnectionConfig::Serial { port, .. } = &mut config {
+            *port = pair.first_path().display().to_string();
+        }
+        
+        let queue = QueueConfig { capacity: 1, overflow: OverflowPolicy::DropNewest };
+        let client = SerialClient::with_queue(&config, queue).await.unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        
+        // Nobody reads the other end, so the writer stalls on the first
+        // chunk and the second one fills the queue
+        let chunk = vec![0x55u8; 1 << 20];
+        client.send(chunk.clone()).await.unwrap();
+        client.send(chunk.clone()).await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(100), client.send(chunk.clone())).await.is_err());
+        
+        // Nothing queued is lost once the line drains
+        let mut received = 0;
+        let mut buffer = vec![0u8; 65536];
+        while received < 2 * chunk.len() {
+            received += tokio::time::timeout(Duration::from_secs(5), device.read(&mut buffer)).await.unwrap().unwrap();
+        }
+        assert_eq!(received, 2 * chunk.len());
+        
+        client.close().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_break_over_virtual_pair() {
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        let pair = VirtualSerialPair::open().unwrap();
+        let mut config = create_test_config();
+        if let ConnectionConfig::Serial { port, .. } = &mut config {
+            *port = pair.first_path().display().to_string();
+        }
+        
+        let client = SerialClient::new(&config).await.unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        
+        let started = std::time::Instant::now();
+        client.send_break(Duration::from_millis(50)).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        
+        // The port keeps working after the break
+        client.send(b"AT\r".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let n = tokio::time::timeout(Duration::from_secs(2), device.read(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(&buffer[..n], b"AT\r");
+        
+        // Pseudo-terminals have no modem lines
+        #[cfg(target_os = "linux")]
+        {
+            let error = client.set_dtr(false).await.unwrap_err();
+            assert!(error.to_string().contains("Failed to set DTR"));
+            assert!(client.modem_status().await.is_err());
+        }
+        
+        client.close().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconfigure_over_virtual_pair() {
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        let pair = VirtualSerialPair::open().unwrap();
+        let mut config = create_test_config();
+        if let ConnectionConfig::Serial { port, .. } = &mut config {
+            *port = pair.first_path().display().to_string();
+        }
+        
+        let client = SerialClient::new(&config).await.unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        assert_eq!(client.baud_rate().await.unwrap(), 9600);
+        
+        if let ConnectionConfig::Serial { baud_rate, parity, .. } = &mut config {
+            *baud_rate = 115200;
+            *parity = ParityConfig::Even;
+        }
+        client.reconfigure(&config).await.unwrap();
+        assert_eq!(client.baud_rate().await.unwrap(), 115200);
+        
+        // The port keeps working at the new settings
+        client.send(b"AT\r".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let n = tokio::time::timeout(Duration::from_secs(2), device.read(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(&buffer[..n], b"AT\r");
+        
+        if let ConnectionConfig::Serial { data_bits, .. } = &mut config {
+            *data_bits = 9;
+        }
+        assert!(client.reconfigure(&config).await.is_err());
+        
+        client.close().await.unwrap();
+    }
+    
+    /// Port whose RTS line can be driven, as pseudo-terminals have none
+    #[cfg(unix)]
+    struct WithRts(Box<dyn SerialPort>);
+    
+    #[cfg(unix)]
+    impl std::io::Read for WithRts {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            self.0.read(buffer)
+        }
+    }
+    
+    #[cfg(unix)]
+    impl std::io::Write for WithRts {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.0.write(data)
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.0.flush()
+        }
+    }
+    
+    #[cfg(unix)]
+    impl SerialPort for WithRts {
+        fn name(&self) -> Option<String> { self.0.name() }
+        fn baud_rate(&self) -> serialport::Result<u32> { self.0.baud_rate() }
+        fn data_bits(&self) -> serialport::Result<serialport::DataBits> { self.0.data_bits() }
+        fn flow_control(&self) -> serialport::Result<serialport::FlowControl> { self.0.flow_control() }
+        fn parity(&self) -> serialport::Result<serialport::Parity> { self.0.parity() }
+        fn stop_bits(&self) -> serialport::Result<serialport::StopBits> { self.0.stop_bits() }
+        fn timeout(&self) -> Duration { self.0.timeout() }
+        fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> { self.0.set_baud_rate(baud_rate) }
+        fn set_data_bits(&mut self, data_bits: serialport::DataBits) -> serialport::Result<()> { self.0.set_data_bits(data_bits) }
+        fn set_flow_control(&mut self, flow_control: serialport::FlowControl) -> serialport::Result<()> { self.0.set_flow_control(flow_control) }
+        fn set_parity(&mut self, parity: serialport::Parity) -> serialport::Result<()> { self.0.set_parity(parity) }
+        fn set_stop_bits(&mut self, stop_bits: serialport::StopBits) -> serialport::Result<()> { self.0.set_stop_bits(stop_bits) }
+        fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> { self.0.set_timeout(timeout) }
+        fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> { Ok(()) }
+        fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> { self.0.write_data_terminal_ready(level) }
+        fn read_clear_to_send(&mut self) -> serialport::Result<bool> { self.0.read_clear_to_send() }
+        fn read_data_set_ready(&mut self) -> serialport::Result<bool> { self.0.read_data_set_ready() }
+        fn read_ring_indicator(&mut self) -> serialport::Result<bool> { self.0.read_ring_indicator() }
+        fn read_carrier_detect(&mut self) -> serialport::Result<bool> { self.0.read_carrier_detect() }
+        fn bytes_to_read(&self) -> serialport::Result<u32> { self.0.bytes_to_read() }
+        fn bytes_to_write(&self) -> serialport::Result<u32> { self.0.bytes_to_write() }
+        fn clear(&self, buffer_to_clear: serialport::ClearBuffer) -> serialport::Result<()> { self.0.clear(buffer_to_clear) }
+        fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> { self.0.try_clone() }
+        fn set_break(&self) -> serialport::Result<()> { self.0.set_break() }
+        fn clear_break(&self) -> serialport::Result<()> { self.0.clear_break() }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rs485_echo_is_dropped_before_reply() {
+        use crate::domain::config::Rs485Mode;
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        let pair = VirtualSerialPair::open().unwrap();
+        let port = serialport::new(pair.first_path().to_string_lossy(), 9600).open().unwrap();
+        let rs485 = Rs485Config {
+            mode: Rs485Mode::Software,
+            delay_after_send_ms: 100,
+            suppress_echo: true,
+            ..Rs485Config::default()
+        };
+        let mut client = SerialClient::start(
+            Box::new(WithRts(port)),
+            pair.first_path().display().to_string(),
+            Some(rs485),
+            Some(Rs485Control::Software),
+            QueueConfig::default(),
+        )
+        .unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        
+        // Modbus "write single register" replies repeat the request. The
+        // echo comes back while the transmitter is still held on.
+        let request = [0x01, 0x06, 0x00, 0x01, 0x00, 0x03, 0x98, 0x0b];
+        client.send(request.to_vec()).await.unwrap();
+        let mut received = Vec::new();
+        while received.len() < request.len() {
+            let mut buffer = [0u8; 16];
+            let n = tokio::time::timeout(Duration::from_secs(2), device.read(&mut buffer)).await.unwrap().unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+        assert_eq!(received, request);
+        device.write_all(&request).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        device.write_all(&request).await.unwrap();
+        
+        let mut replies = Vec::new();
+        while let Ok(Some(message)) = tokio::time::timeout(Duration::from_millis(300), client.receive()).await {
+            if matches!(message.direction, MessageDirection::Received) {
+                replies.extend(message.data);
+            }
+        }
+        assert_eq!(replies, request);
+        
+        client.close().await.unwrap();
+    }
+    
     #[test]
     fn test_serial_message_creation() {
         let message = SerialMessage {
             timestamp: std::time::SystemTime::now(),
-            direction: MessageDirection::Sent,
+            direction: MessageDirection::Received,
             data: vec![0x01, 0x02, 0x03],
         };
         
         assert_eq!(message.data.len(), 3);
-        assert!(matches!(message.direction, MessageDirection::Sent));
+        assert!(matches!(message.direction, MessageDirection::Received));
     }
 }
\ No newline at end of file
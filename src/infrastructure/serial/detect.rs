@@ -0,0 +1,286 @@
+use crate::domain::{
+    config::{ConnectionConfig, SerialSettings},
+    error::{TermComError, TermComResult},
+};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::serial::client::{MessageDirection, SerialClient, SerialMessage};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Baud rates tried when none are given, most common first
+pub const COMMON_BAUD_RATES: &[u32] = &[
+    115200, 9600, 57600, 38400, 19200, 230400, 460800, 921600, 74880, 4800, 2400, 1200,
+];
+
+/// Bytes that must be received before the printable ratio of a rate counts
+pub const MIN_SAMPLE_BYTES: usize = 8;
+
+/// Printable ratio a rate needs to be reported as a match
+pub const MIN_PRINTABLE_RATIO: f64 = 0.9;
+
+/// How to look for a device's baud rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaudDetectOptions {
+    /// Rates to try, in order of preference
+    pub rates: Vec<u32>,
+    /// Data sent after switching to each rate, for devices that only talk when asked
+    pub probe: Option<Vec<u8>>,
+    /// Text that identifies the right rate
+    ///
+    /// When set, a rate matches only if the text shows up in its output and
+    /// detection stops at the first match. Otherwise rates are scored by the
+    /// share of printable ASCII they receive.
+    pub expect: Option<String>,
+    /// How long to listen at each rate
+    pub listen: Duration,
+}
+
+impl Default for BaudDetectOptions {
+    fn default() -> Self {
+        Self {
+            rates: COMMON_BAUD_RATES.to_vec(),
+            probe: None,
+            expect: None,
+            listen: Duration::from_millis(500),
+        }
+    }
+}
+
+/// What was received at one baud rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaudCandidate {
+    /// Baud rate tried
+    pub baud_rate: u32,
+    /// Bytes received while listening
+    pub bytes: usize,
+    /// Share of received bytes that are printable ASCII or whitespace
+    pub printable_ratio: f64,
+    /// Whether the expected text showed up
+    pub matched: bool,
+    /// Score from 0 to 1; higher is more likely the right rate
+    pub score: f64,
+}
+
+impl BaudCandidate {
+    /// Score the data received at `baud_rate`
+    pub fn score(baud_rate: u32, data: &[u8], expect: Option<&str>) -> Self {
+        let printable = data
+            .iter()
+            .filter(|byte| byte.is_ascii_graphic() || matches!(byte, b' ' | b'\r' | b'\n' | b'\t'))
+            .count();
+        let printable_ratio = if data.is_empty() { 0.0 } else { printable as f64 / data.len() as f64 };
+
+        let matched = expect.is_some_and(|expect| contains(data, expect.as_bytes()));
+        let score = match expect {
+            Some(_) if matched => 1.0,
+            None if data.len() >= MIN_SAMPLE_BYTES => printable_ratio,
+            _ => 0.0,
+        };
+
+        Self { baud_rate, bytes: data.len(), printable_ratio, matched, score }
+    }
+
+    /// Whether this rate is good enough to report as a match
+    pub fn is_match(&self) -> bool {
+        self.matched || self.score >= MIN_PRINTABLE_RATIO
+    }
+}
+
+/// Result of a baud rate detection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaudDetection {
+    /// Every rate tried, in the order tried
+    pub candidates: Vec<BaudCandidate>,
+}
+
+impl BaudDetection {
+    /// Best matching candidate, if any rate matched
+    ///
+    /// Ties go to the rate that received more data, then to the rate tried first.
+    pub fn best(&self) -> Option<&BaudCandidate> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.is_match())
+            .fold(None, |best: Option<&BaudCandidate>, candidate| match best {
+                Some(best) if (best.score, best.bytes) >= (candidate.score, candidate.bytes) => Some(best),
+                _ => Some(candidate),
+            })
+    }
+
+    /// Best matching baud rate, if any rate matched
+    pub fn baud_rate(&self) -> Option<u32> {
+        self.best().map(|candidate| candidate.baud_rate)
+    }
+}
+
+/// Find the baud rate a device on a serial port talks at
+///
+/// The port is opened once with the other settings of `connection` and
+/// switched through `options.rates`, listening at each for
+/// `options.listen` after sending the probe.
+pub async fn detect_baud_rate(connection: &ConnectionConfig, options: &BaudDetectOptions) -> TermComResult<BaudDetection> {
+    let first_rate = *options.rates.first().ok_or_else(|| TermComError::Config {
+        message: "No baud rates to try".to_string(),
+    })?;
+
+    let mut connection = connection.clone();
+    SerialSettings::baud_rate(first_rate).apply(&mut connection)?;
+    let mut client = SerialClient::new(&connection).await?;
+    let mut messages = client.take_message_receiver().ok_or_else(|| TermComError::Communication {
+        message: "Serial client has no message receiver".to_string(),
+    })?;
+
+    let result = try_rates(&client, &mut messages, &mut connection, options).await;
+    drop(messages);
+    client.close().await?;
+    result
+}
+
+async fn try_rates(
+    client: &SerialClient,
+    messages: &mut QueueReceiver<SerialMessage>,
+    connection: &mut ConnectionConfig,
+    options: &BaudDetectOptions,
+) -> TermComResult<BaudDetection> {
+    let mut candidates = Vec::new();
+
+    for &baud_rate in &options.rates {
+        SerialSettings::baud_rate(baud_rate).apply(connection)?;
+        client.reconfigure(connection).await?;
+        client.clear_input().await?;
+
+        // Drop whatever was read at the previous rate
+        while messages.try_recv().is_some() {}
+
+        if let Some(ref probe) = options.probe {
+            client.send(probe.clone()).await?;
+        }
+
+        let mut data = Vec::new();
+        let deadline = Instant::now() + options.listen;
+        while let Ok(Some(message)) = tokio::time::timeout_at(deadline, messages.recv()).await {
+            match message.direction {
+                MessageDirection::Received => {}
+                MessageDirection::Disconnected => {
+                    return Err(TermComError::Communication {
+                        message: format!("Serial port lost during baud rate detection: {}", String::from_utf8_lossy(&message.data)),
+                    });
+                }
+            }
+            data.extend_from_slice(&message.data);
+            if options.expect.as_deref().is_some_and(|expect| contains(&data, expect.as_bytes())) {
+                break;
+            }
+        }
+
+        let candidate = BaudCandidate::score(baud_rate, &data, options.expect.as_deref());
+        debug!("Baud rate {}: {} bytes, score {:.2}", baud_rate, candidate.bytes, candidate.score);
+
+        let found = candidate.matched;
+        candidates.push(candidate);
+        if found {
+            break;
+        }
+    }
+
+    Ok(BaudDetection { candidates })
+}
+
+fn contains(data: &[u8], pattern: &[u8]) -> bool {
+    !pattern.is_empty() && data.windows(pattern.len()).any(|window| window == pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring() {
+        let text = BaudCandidate::score(115200, b"ets Jun  8 2016 00:22:57\r\n", None);
+        assert_eq!(text.printable_ratio, 1.0);
+        assert!(text.is_match());
+
+        let garbage = BaudCandidate::score(9600, &[0x00, 0xf8, 0x80, b'x', 0xfe, 0x86, 0x1e, 0x98, 0xe0, b'r'], None);
+        assert_eq!(garbage.printable_ratio, 0.2);
+        assert!(!garbage.is_match());
+
+        // Too little data to judge
+        let short = BaudCandidate::score(57600, b"OK", None);
+        assert_eq!(short.score, 0.0);
+
+        let expected = BaudCandidate::score(74880, b"\xf8rst:0x1 (POWERON)", Some("rst:"));
+        assert!(expected.matched);
+        assert_eq!(expected.score, 1.0);
+        assert!(!BaudCandidate::score(74880, b"printable but wrong", Some("rst:")).is_match());
+
+        let detection = BaudDetection { candidates: vec![garbage, short, text.clone()] };
+        assert_eq!(detection.best(), Some(&text));
+        assert_eq!(BaudDetection { candidates: Vec::new() }.baud_rate(), None);
+    }
+
+    #[test]
+    fn test_ties_prefer_more_data_then_earlier_rates() {
+        let detection = BaudDetection {
+            candidates: vec![
+                BaudCandidate::score(9600, b"hello world", None),
+                BaudCandidate::score(19200, b"hello world", None),
+                BaudCandidate::score(38400, b"hello world!", None),
+            ],
+        };
+        assert_eq!(detection.baud_rate(), Some(38400));
+
+        let detection = BaudDetection { candidates: detection.candidates[..2].to_vec() };
+        assert_eq!(detection.baud_rate(), Some(9600));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_detects_rate_over_virtual_pair() {
+        use crate::domain::config::{FlowControlConfig, ParityConfig};
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+
+        let pair = VirtualSerialPair::open().unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+
+        // Pseudo-terminals ignore the baud rate, so the device answers the
+        // first two probes with line noise and the third one with text
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 64];
+            for probe in 0.. {
+                if device.read(&mut buffer).await.is_err() {
+                    break;
+                }
+                let reply: &[u8] = if probe < 2 { b"\x00\xf8\x80\xfe\x86\x1e\x98\xe0\x00\xfc" } else { b"READY v1.2\r\n" };
+                if device.write_all(reply).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let connection = ConnectionConfig::Serial {
+            port: pair.first_path().display().to_string(),
+            baud_rate: 9600,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        };
+        let options = BaudDetectOptions {
+            rates: vec![9600, 57600, 115200],
+            probe: Some(b"\r".to_vec()),
+            expect: None,
+            listen: Duration::from_millis(200),
+        };
+
+        let detection = detect_baud_rate(&connection, &options).await.unwrap();
+        assert_eq!(detection.candidates.len(), 3);
+        assert_eq!(detection.baud_rate(), Some(115200));
+        assert!(detection.candidates[0].printable_ratio < 0.5);
+
+        let empty = BaudDetectOptions { rates: Vec::new(), ..BaudDetectOptions::default() };
+        assert!(detect_baud_rate(&connection, &empty).await.is_err());
+    }
+}
@@ -1,7 +1,9 @@
-use crate::domain::{config::DeviceConfig, error::{TermComError, TermComResult}};
-use crate::infrastructure::serial::client::{SerialClient, SerialMessage};
+use crate::domain::{config::{ConnectionConfig, DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::serial::client::{MessageDirection, ModemStatus, SerialClient, SerialMessage};
+use std::time::Duration;
 use std::collections::HashMap;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock, RwLockReadGuard};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
@@ -11,9 +13,13 @@ pub type SessionId = String;
 pub struct SessionInfo {
     pub id: SessionId,
     pub device_name: String,
+    /// Path of the opened port, after resolving a USB match
+    pub port_name: String,
     pub status: SessionStatus,
     pub created_at: std::time::SystemTime,
     pub last_activity: std::time::SystemTime,
+    /// Messages discarded because they were not received in time
+    pub dropped_messages: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -26,25 +32,43 @@ pub enum SessionStatus {
 pub struct SessionHandle {
     client: SerialClient,
     info: SessionInfo,
-    #[allow(dead_code)]
-    message_sender: mpsc::UnboundedSender<SerialMessage>,
+    /// Connection the port was opened with, reused when reconnecting
+    connection: ConnectionConfig,
+    forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.client.dropped_messages(), ..self.info.clone() }
+    }
 }
 
 pub struct SerialManager {
     sessions: Arc<RwLock<HashMap<SessionId, SessionHandle>>>,
     max_sessions: usize,
-    message_receiver: mpsc::UnboundedReceiver<SerialMessage>,
-    message_sender: mpsc::UnboundedSender<SerialMessage>,
+    queue: QueueConfig,
+    message_receiver: Mutex<mpsc::Receiver<(SessionId, SerialMessage)>>,
+    message_sender: mpsc::Sender<(SessionId, SerialMessage)>,
 }
 
 impl SerialManager {
     pub fn new(max_sessions: usize) -> Self {
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+    
+    /// Create a manager whose sessions buffer messages according to `queue`
+    ///
+    /// Messages of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(queue.capacity.max(1));
         
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             max_sessions,
-            message_receiver,
+            queue,
+            message_receiver: Mutex::new(message_receiver),
             message_sender,
         }
     }
@@ -67,27 +91,33 @@ impl SerialManager {
         
         drop(sessions);
         
-        let client = SerialClient::new(&device_config.connection).await?;
+        let mut client = SerialClient::with_queue(&device_config.connection, self.queue).await?;
         let session_id = format!("serial_{}", uuid::Uuid::new_v4().simple());
+        let port_name = client.port_name().to_string();
         
         let session_info = SessionInfo {
             id: session_id.clone(),
             device_name: device_config.name.clone(),
+            port_name: port_name.clone(),
             status: SessionStatus::Connected,
             created_at: std::time::SystemTime::now(),
             last_activity: std::time::SystemTime::now(),
+            dropped_messages: 0,
         };
         
+        let forward_handle = self.spawn_message_forwarder(session_id.clone(), client.take_message_receiver());
+        
         let session_handle = SessionHandle {
             client,
             info: session_info,
-            message_sender: self.message_sender.clone(),
+            connection: device_config.connection.clone(),
+            forward_handle,
         };
         
         let mut sessions = self.sessions.write().await;
         sessions.insert(session_id.clone(), session_handle);
         
-        info!("Created serial session '{}' for device '{}'", session_id, device_config.name);
+        info!("Created serial session '{}' for device '{}' on {}", session_id, device_config.name, port_name);
         
         Ok(session_id)
     }
@@ -96,6 +126,7 @@ impl SerialManager {
         let mut sessions = self.sessions.write().await;
         
         if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle.forward_handle.abort();
             session_handle.client.close().await?;
             info!("Closed serial session '{}'", session_id);
             Ok(())
@@ -110,6 +141,11 @@ impl SerialManager {
         let sessions = self.sessions.read().await;
         
         if let Some(session_handle) = sessions.get(session_id) {
+            if matches!(session_handle.info.status, SessionStatus::Disconnected) {
+                return Err(TermComError::Communication {
+                    message: format!("Serial port {} of session '{}' is disconnected", session_handle.info.port_name, session_id),
+                });
+            }
             session_handle.client.send(data).await?;
             
             // Update last activity
@@ -132,18 +168,103 @@ impl SerialManager {
         self.send_data(session_id, data).await
     }
     
+    /// Drive the DTR line of a session's port
+    pub async fn set_dtr(&self, session_id: &SessionId, level: bool) -> TermComResult<()> {
+        self.session(session_id).await?.client.set_dtr(level).await
+    }
+    
+    /// Drive the RTS line of a session's port
+    pub async fn set_rts(&self, session_id: &SessionId, level: bool) -> TermComResult<()> {
+        self.session(session_id).await?.client.set_rts(level).await
+    }
+    
+    /// Read the modem status lines of a session's port
+    pub async fn modem_status(&self, session_id: &SessionId) -> TermComResult<ModemStatus> {
+        self.session(session_id).await?.client.modem_status().await
+    }
+    
+    /// Send a break condition on a session's port
+    pub async fn send_break(&self, session_id: &SessionId, duration: Duration) -> TermComResult<()> {
+        self.session(session_id).await?.client.send_break(duration).await
+    }
+    
+    /// Apply new line settings to a session's open port
+    ///
+    /// The settings are kept for reopening the port after a reconnect.
+    pub async fn reconfigure(&self, session_id: &SessionId, config: &ConnectionConfig) -> TermComResult<()> {
+        self.session(session_id).await?.client.reconfigure(config).await?;
+        
+        let mut sessions = self.sessions.write().await;
+        if let Some(session_handle) = sessions.get_mut(session_id) {
+            session_handle.connection = config.clone();
+        }
+        Ok(())
+    }
+    
+    /// Reopen the port of a session whose device went away
+    ///
+    /// The port is looked up again, so a USB match finds the adapter under
+    /// whatever path it enumerated as this time. The session keeps its ID.
+    /// Sessions that are still connected are left alone.
+    pub async fn reconnect(&self, session_id: &SessionId) -> TermComResult<()> {
+        let connection = {
+            let session_handle = self.session(session_id).await?;
+            if matches!(session_handle.info.status, SessionStatus::Connected) {
+                return Ok(());
+            }
+            session_handle.connection.clone()
+        };
+        
+        let mut client = SerialClient::with_queue(&connection, self.queue).await?;
+        let port_name = client.port_name().to_string();
+        let forward_handle = self.spawn_message_forwarder(session_id.clone(), client.take_message_receiver());
+        
+        let mut sessions = self.sessions.write().await;
+        let Some(session_handle) = sessions.get_mut(session_id) else {
+            drop(sessions);
+            forward_handle.abort();
+            client.close().await?;
+            return Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            });
+        };
+        
+        session_handle.forward_handle.abort();
+        session_handle.forward_handle = forward_handle;
+        let old_client = std::mem::replace(&mut session_handle.client, client);
+        session_handle.info.port_name = port_name.clone();
+        session_handle.info.status = SessionStatus::Connected;
+        session_handle.info.last_activity = std::time::SystemTime::now();
+        drop(sessions);
+        
+        if let Err(e) = old_client.close().await {
+            warn!("Failed to close lost serial port of session '{}': {}", session_id, e);
+        }
+        
+        info!("Reconnected serial session '{}' on {}", session_id, port_name);
+        Ok(())
+    }
+    
+    async fn session(&self, session_id: &SessionId) -> TermComResult<RwLockReadGuard<'_, SessionHandle>> {
+        RwLockReadGuard::try_map(self.sessions.read().await, |sessions| sessions.get(session_id))
+            .map_err(|_| TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+    }
+    
     pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
         let sessions = self.sessions.read().await;
-        sessions.get(session_id).map(|handle| handle.info.clone())
+        sessions.get(session_id).map(SessionHandle::info)
     }
     
     pub async fn list_sessions(&self) -> Vec<SessionInfo> {
         let sessions = self.sessions.read().await;
-        sessions.values().map(|handle| handle.info.clone()).collect()
+        sessions.values().map(SessionHandle::info).collect()
     }
     
-    pub async fn receive_message(&mut self) -> Option<SerialMessage> {
-        self.message_receiver.recv().await
+    /// Receive the next message from any session, tagged with its session ID
+    pub async fn receive_message(&self) -> Option<(SessionId, SerialMessage)> {
+        self.message_receiver.lock().await.recv().await
     }
     
     pub async fn close_all_sessions(&self) -> TermComResult<()> {
@@ -152,6 +273,7 @@ impl SerialManager {
         
         for session_id in session_ids {
             if let Some(session_handle) = sessions.remove(&session_id) {
+                session_handle.forward_handle.abort();
                 if let Err(e) = session_handle.client.close().await {
                     error!("Failed to close session '{}': {}", session_id, e);
                 }
@@ -179,6 +301,35 @@ impl SerialManager {
     pub fn get_max_sessions(&self) -> usize {
         self.max_sessions
     }
+    
+    /// Forward a client's messages, marking the session disconnected when its port is lost
+    fn spawn_message_forwarder(
+        &self,
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<SerialMessage>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let sessions = Arc::clone(&self.sessions);
+        let sender = self.message_sender.clone();
+        
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+            
+            while let Some(message) = receiver.recv().await {
+                if matches!(message.direction, MessageDirection::Disconnected) {
+                    if let Some(session_handle) = sessions.write().await.get_mut(&session_id) {
+                        warn!("Serial session '{}' lost {}", session_id, session_handle.info.port_name);
+                        session_handle.info.status = SessionStatus::Disconnected;
+                    }
+                }
+                
+                if sender.send((session_id.clone(), message)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
 }
 
 impl Drop for SerialManager {
@@ -196,17 +347,19 @@ mod tests {
     
     fn create_test_device_config(name: &str) -> DeviceConfig {
         DeviceConfig {
-            name: name.to_string(),
             description: "Test device".to_string(),
-            connection: ConnectionConfig::Serial {
-                port: "/dev/null".to_string(),
-                baud_rate: 9600,
-                data_bits: 8,
-                stop_bits: 1,
-                parity: ParityConfig::None,
-                flow_control: FlowControlConfig::None,
-            },
-            commands: Vec::new(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Serial {
+                    port: "/dev/null".to_string(),
+                    baud_rate: 9600,
+                    data_bits: 8,
+                    stop_bits: 1,
+                    parity: ParityConfig::None,
+                    flow_control: FlowControlConfig::None,
+                    rs485: None,
+                },
+            )
         }
     }
     
@@ -232,9 +385,11 @@ mod tests {
         let session_info = SessionInfo {
             id: "test_session".to_string(),
             device_name: "test_device".to_string(),
+            port_name: "/dev/ttyUSB0".to_string(),
             status: SessionStatus::Connected,
             created_at: std::time::SystemTime::now(),
             last_activity: std::time::SystemTime::now(),
+            dropped_messages: 0,
         };
         
         assert_eq!(session_info.id, "test_session");
@@ -242,6 +397,55 @@ mod tests {
         assert!(matches!(session_info.status, SessionStatus::Connected));
     }
     
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconnect_after_port_returns() {
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+        
+        // A symlink stands in for a /dev/serial/by-id alias that udev
+        // points at whichever node the adapter gets after replugging
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("usb-FTDI_FT232R_USB_UART_A50285BI-if00-port0");
+        let pair = VirtualSerialPair::open_with_links(Some(&link), None).unwrap();
+        
+        let manager = SerialManager::new(5);
+        let mut device_config = create_test_device_config("board");
+        device_config.connection = ConnectionConfig::Serial {
+            port: link.display().to_string(),
+            baud_rate: 115200,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        };
+        let session_id = manager.create_session(&device_config).await.unwrap();
+        
+        drop(pair);
+        let (_, lost) = tokio::time::timeout(Duration::from_secs(2), manager.receive_message()).await.unwrap().unwrap();
+        assert!(matches!(lost.direction, MessageDirection::Disconnected));
+        assert!(matches!(manager.get_session_info(&session_id).await.unwrap().status, SessionStatus::Disconnected));
+        assert!(manager.send_data(&session_id, b"x".to_vec()).await.is_err());
+        
+        // Nothing to reopen until the device comes back
+        assert!(manager.reconnect(&session_id).await.is_err());
+        
+        let pair = VirtualSerialPair::open_with_links(Some(&link), None).unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        manager.reconnect(&session_id).await.unwrap();
+        
+        let info = manager.get_session_info(&session_id).await.unwrap();
+        assert!(matches!(info.status, SessionStatus::Connected));
+        assert_eq!(info.port_name, link.display().to_string());
+        
+        device.write_all(b"hello again").await.unwrap();
+        let (id, message) = tokio::time::timeout(Duration::from_secs(2), manager.receive_message()).await.unwrap().unwrap();
+        assert_eq!(id, session_id);
+        assert_eq!(message.data, b"hello again");
+        
+        manager.close_all_sessions().await.unwrap();
+    }
+    
     #[tokio::test]
     async fn test_close_nonexistent_session() {
         let manager = SerialManager::new(5);
@@ -1,5 +1,11 @@
 // Serial module - Serial communication implementation
 pub mod client;
+pub mod detect;
 pub mod manager;
+pub mod ports;
+pub mod rs485;
 
-pub use manager::SerialManager;
\ No newline at end of file
+pub use detect::{detect_baud_rate, BaudCandidate, BaudDetectOptions, BaudDetection};
+pub use manager::SerialManager;
+pub use ports::{list_ports, resolve_port, PortInfo, PortKind};
+pub use rs485::{EchoFilter, Rs485Control};
\ No newline at end of file
@@ -0,0 +1,220 @@
+use crate::domain::{
+    config::UsbMatch,
+    error::{TermComError, TermComResult},
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// Directories of stable symlinks that udev keeps for serial ports
+pub const ALIAS_DIRS: &[&str] = &["/dev/serial/by-id", "/dev/serial/by-path"];
+
+/// How a serial port is attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortKind {
+    Usb,
+    Pci,
+    Bluetooth,
+    Unknown,
+}
+
+/// A serial port found on the system
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortInfo {
+    /// Port path, such as `/dev/ttyUSB0` or `COM3`
+    pub port_name: String,
+    /// How the port is attached
+    pub kind: PortKind,
+    /// USB vendor ID
+    pub vid: Option<u16>,
+    /// USB product ID
+    pub pid: Option<u16>,
+    /// USB serial number
+    pub serial_number: Option<String>,
+    /// USB manufacturer string
+    pub manufacturer: Option<String>,
+    /// USB product string
+    pub product: Option<String>,
+    /// Stable symlinks pointing at the port
+    pub aliases: Vec<String>,
+}
+
+impl PortInfo {
+    fn from_serialport(port: serialport::SerialPortInfo) -> Self {
+        let mut info = Self {
+            port_name: port.port_name,
+            kind: PortKind::Unknown,
+            vid: None,
+            pid: None,
+            serial_number: None,
+            manufacturer: None,
+            product: None,
+            aliases: Vec::new(),
+        };
+
+        match port.port_type {
+            serialport::SerialPortType::UsbPort(usb) => {
+                info.kind = PortKind::Usb;
+                info.vid = Some(usb.vid);
+                info.pid = Some(usb.pid);
+                info.serial_number = usb.serial_number;
+                info.manufacturer = usb.manufacturer;
+                info.product = usb.product;
+            }
+            serialport::SerialPortType::PciPort => info.kind = PortKind::Pci,
+            serialport::SerialPortType::BluetoothPort => info.kind = PortKind::Bluetooth,
+            serialport::SerialPortType::Unknown => {}
+        }
+
+        for dir in ALIAS_DIRS {
+            info.aliases.extend(aliases_in(Path::new(dir), Path::new(&info.port_name)));
+        }
+        info
+    }
+
+    /// USB match that selects this port, for USB ports
+    ///
+    /// Includes the serial number when the adapter reports one.
+    pub fn usb_match(&self) -> Option<UsbMatch> {
+        Some(UsbMatch {
+            vid: self.vid?,
+            pid: self.pid?,
+            serial_number: self.serial_number.clone(),
+        })
+    }
+
+    /// Whether the port is the USB adapter described by `usb`
+    pub fn matches(&self, usb: &UsbMatch) -> bool {
+        match (self.vid, self.pid) {
+            (Some(vid), Some(pid)) => usb.matches(vid, pid, self.serial_number.as_deref()),
+            _ => false,
+        }
+    }
+}
+
+/// List the serial ports on the system with their USB details and aliases
+pub fn list_ports() -> TermComResult<Vec<PortInfo>> {
+    let mut ports: Vec<PortInfo> = serialport::available_ports()
+        .map_err(|e| TermComError::Communication {
+            message: format!("Failed to list serial ports: {}", e),
+        })?
+        .into_iter()
+        .map(PortInfo::from_serialport)
+        .collect();
+
+    ports.sort_by(|a, b| a.port_name.cmp(&b.port_name));
+    Ok(ports)
+}
+
+/// Turn a configured serial port into the path to open
+///
+/// Port paths are returned unchanged; a `usb:VID:PID[:SERIAL]` match is
+/// looked up among the ports currently present and must match exactly one.
+pub fn resolve_port(port: &str) -> TermComResult<String> {
+    match UsbMatch::from_port(port)? {
+        Some(usb) => {
+            let path = find_port(&usb, &list_ports()?)?;
+            info!("Resolved {} to {}", usb, path);
+            Ok(path)
+        }
+        None => Ok(port.to_string()),
+    }
+}
+
+/// Pick the port that matches `usb` from `ports`
+pub fn find_port(usb: &UsbMatch, ports: &[PortInfo]) -> TermComResult<String> {
+    let matches: Vec<_> = ports.iter().filter(|port| port.matches(usb)).collect();
+
+    match matches.as_slice() {
+        [port] => Ok(port.port_name.clone()),
+        [] => Err(TermComError::Communication {
+            message: format!("No serial port matches {}", usb),
+        }),
+        _ => Err(TermComError::Communication {
+            message: format!(
+                "{} serial ports match {} ({}); add the serial number to pick one",
+                matches.len(),
+                usb,
+                matches.iter().map(|port| port.port_name.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        }),
+    }
+}
+
+/// Symlinks in `dir` that resolve to `port`
+fn aliases_in(dir: &Path, port: &Path) -> Vec<String> {
+    let (Ok(entries), Ok(target)) = (std::fs::read_dir(dir), port.canonicalize()) else {
+        return Vec::new();
+    };
+
+    let mut aliases: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.canonicalize().is_ok_and(|resolved| resolved == target))
+        .map(|path| path.display().to_string())
+        .collect();
+    aliases.sort();
+    aliases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usb_port(port_name: &str, vid: u16, pid: u16, serial_number: Option<&str>) -> PortInfo {
+        PortInfo {
+            port_name: port_name.to_string(),
+            kind: PortKind::Usb,
+            vid: Some(vid),
+            pid: Some(pid),
+            serial_number: serial_number.map(str::to_string),
+            manufacturer: Some("FTDI".to_string()),
+            product: Some("FT232R USB UART".to_string()),
+            aliases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_port_by_usb_identity() {
+        let ports = vec![
+            usb_port("/dev/ttyUSB0", 0x0403, 0x6001, Some("A50285BI")),
+            usb_port("/dev/ttyUSB1", 0x0403, 0x6001, Some("A9M9DV3R")),
+            usb_port("/dev/ttyACM0", 0x2341, 0x0043, None),
+            PortInfo { kind: PortKind::Pci, vid: None, pid: None, ..usb_port("/dev/ttyS0", 0, 0, None) },
+        ];
+
+        let usb = "usb:0403:6001:A9M9DV3R".parse().unwrap();
+        assert_eq!(find_port(&usb, &ports).unwrap(), "/dev/ttyUSB1");
+        assert_eq!(find_port(&"usb:2341:0043".parse().unwrap(), &ports).unwrap(), "/dev/ttyACM0");
+
+        let error = find_port(&"usb:0403:6001".parse().unwrap(), &ports).unwrap_err().to_string();
+        assert!(error.contains("2 serial ports match usb:0403:6001 (/dev/ttyUSB0, /dev/ttyUSB1)"), "{}", error);
+        assert!(find_port(&"usb:0403:6015".parse().unwrap(), &ports).is_err());
+
+        assert_eq!(ports[0].usb_match().unwrap().to_string(), "usb:0403:6001:A50285BI");
+        assert!(ports[3].usb_match().is_none());
+        assert_eq!(resolve_port("/dev/ttyS0").unwrap(), "/dev/ttyS0");
+        assert!(resolve_port("usb:nope").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_aliases_resolve_to_port() {
+        let dir = tempfile::tempdir().unwrap();
+        let port = dir.path().join("ttyUSB0");
+        std::fs::write(&port, b"").unwrap();
+        std::fs::write(dir.path().join("ttyUSB1"), b"").unwrap();
+
+        let by_id = dir.path().join("by-id");
+        std::fs::create_dir(&by_id).unwrap();
+        std::os::unix::fs::symlink("../ttyUSB0", by_id.join("usb-FTDI_FT232R_USB_UART_A50285BI-if00-port0")).unwrap();
+        std::os::unix::fs::symlink("../ttyUSB1", by_id.join("usb-FTDI_FT232R_USB_UART_A9M9DV3R-if00-port0")).unwrap();
+
+        assert_eq!(
+            aliases_in(&by_id, &port),
+            vec![by_id.join("usb-FTDI_FT232R_USB_UART_A50285BI-if00-port0").display().to_string()]
+        );
+        assert!(aliases_in(&dir.path().join("missing"), &port).is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::domain::{
+    config::{Rs485Config, Rs485Mode},
+    error::{TermComError, TermComResult},
+};
+use serialport::SerialPort;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// How long after a write its echo is waited for
+///
+/// Generous enough for USB adapters that batch received data.
+pub const ECHO_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Who switches the transmitter of an open RS-485 port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rs485Control {
+    /// The kernel driver asserts RTS while it transmits
+    Kernel,
+    /// RTS is toggled around each write
+    Software,
+}
+
+/// Put a freshly opened port into RS-485 mode
+///
+/// `kernel` asks the driver to take over direction control and reports
+/// whether it could. In auto mode a driver without RS-485 support falls
+/// back to software control.
+pub fn enable(
+    port: &mut dyn SerialPort,
+    config: &Rs485Config,
+    kernel: impl FnOnce(&Rs485Config) -> std::io::Result<()>,
+) -> TermComResult<Rs485Control> {
+    if config.mode != Rs485Mode::Software {
+        match kernel(config) {
+            Ok(()) => {
+                info!("Enabled kernel RS-485 mode");
+                return Ok(Rs485Control::Kernel);
+            }
+            Err(e) if config.mode == Rs485Mode::Kernel => {
+                return Err(TermComError::Communication {
+                    message: format!("Failed to enable kernel RS-485 mode: {}", e),
+                });
+            }
+            Err(e) => debug!("Kernel RS-485 mode unavailable ({}), toggling RTS instead", e),
+        }
+    }
+
+    // Idle with the transmitter off so the bus can be heard
+    port.write_request_to_send(!config.rts_on_send).map_err(|e| TermComError::Communication {
+        message: format!("Failed to set RTS for RS-485: {}", e),
+    })?;
+    info!("Enabled software RS-485 mode");
+    Ok(Rs485Control::Software)
+}
+
+/// Write `data` with the transmitter switched on through RTS
+///
+/// The write is drained before the bus is released, so the last byte is
+/// not cut off. Blocks for the whole transmission, delays included.
+pub fn transmit(port: &mut dyn SerialPort, data: &[u8], config: &Rs485Config) -> std::io::Result<()> {
+    port.write_request_to_send(config.rts_on_send)?;
+    sleep_ms(config.delay_before_send_ms);
+
+    let result = port.write_all(data).and_then(|()| port.flush());
+
+    sleep_ms(config.delay_after_send_ms);
+    port.write_request_to_send(!config.rts_on_send)?;
+    result
+}
+
+fn sleep_ms(ms: u32) {
+    if ms > 0 {
+        std::thread::sleep(Duration::from_millis(ms.into()));
+    }
+}
+
+/// Kernel RS-485 support through `TIOCSRS485`
+#[cfg(target_os = "linux")]
+pub mod kernel {
+    use crate::domain::config::Rs485Config;
+    use std::os::unix::io::RawFd;
+
+    const SER_RS485_ENABLED: u32 = 1 << 0;
+    const SER_RS485_RTS_ON_SEND: u32 = 1 << 1;
+    const SER_RS485_RTS_AFTER_SEND: u32 = 1 << 2;
+    const SER_RS485_RX_DURING_TX: u32 = 1 << 4;
+
+    /// `struct serial_rs485` from `linux/serial.h`
+    #[repr(C)]
+    #[derive(Default)]
+    struct SerialRs485 {
+        flags: u32,
+        delay_rts_before_send: u32,
+        delay_rts_after_send: u32,
+        padding: [u32; 5],
+    }
+
+    nix::ioctl_write_ptr_bad!(tiocsrs485, nix::libc::TIOCSRS485, SerialRs485);
+
+    /// Hand direction control of the port behind `fd` to its driver
+    ///
+    /// Unless the echo is wanted, the receiver is kept off while sending.
+    pub fn enable(fd: RawFd, config: &Rs485Config) -> std::io::Result<()> {
+        let mut flags = SER_RS485_ENABLED;
+        flags |= if config.rts_on_send { SER_RS485_RTS_ON_SEND } else { SER_RS485_RTS_AFTER_SEND };
+        if !config.suppress_echo {
+            flags |= SER_RS485_RX_DURING_TX;
+        }
+
+        let rs485 = SerialRs485 {
+            flags,
+            delay_rts_before_send: config.delay_before_send_ms,
+            delay_rts_after_send: config.delay_after_send_ms,
+            ..SerialRs485::default()
+        };
+
+        // SAFETY: the ioctl only reads the fully initialized struct
+        unsafe { tiocsrs485(fd, &rs485) }.map(drop).map_err(std::io::Error::from)
+    }
+}
+
+/// Drops the echo of our own transmissions from received data
+///
+/// Transceivers that keep their receiver on while sending hand every
+/// transmitted byte back. Those bytes arrive before any reply, so they are
+/// stripped from the start of the received stream.
+///
+/// The echo can arrive while the write is still in progress, so it is
+/// expected before writing and only becomes overdue once the write is done.
+#[derive(Debug, Default)]
+pub struct EchoFilter {
+    pending: VecDeque<u8>,
+    /// When the expected echo is overdue; unset while the write is in progress
+    deadline: Option<Instant>,
+}
+
+impl EchoFilter {
+    /// Expect `sent` to be echoed back, before it is written
+    pub fn expect(&mut self, sent: &[u8]) {
+        self.pending.extend(sent);
+        self.deadline = None;
+    }
+
+    /// The write finished; the rest of its echo is due before `deadline`
+    pub fn written(&mut self, deadline: Instant) {
+        if self.is_pending() {
+            self.deadline = Some(deadline);
+        }
+    }
+
+    /// The write failed; its echo is not waited for
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.deadline = None;
+    }
+
+    /// Remove the expected echo from the start of `received`
+    ///
+    /// Data that differs from the echo means the echo was lost, so nothing
+    /// more is dropped until the next write.
+    pub fn filter(&mut self, received: &[u8], now: Instant) -> Vec<u8> {
+        if self.deadline.is_some_and(|deadline| now > deadline) {
+            self.pending.clear();
+        }
+
+        let echoed = received
+            .iter()
+            .zip(&self.pending)
+            .take_while(|(received, expected)| received == expected)
+            .count();
+        self.pending.drain(..echoed);
+        if echoed < received.len() {
+            self.pending.clear();
+        }
+        if self.pending.is_empty() {
+            self.deadline = None;
+        }
+
+        received[echoed..].to_vec()
+    }
+
+    /// Whether echoed bytes are still expected
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_echo_is_dropped_before_reply() {
+        let start = Instant::now();
+        let deadline = start + ECHO_TIMEOUT;
+        let mut filter = EchoFilter::default();
+
+        // Modbus "write single register" replies repeat the request
+        let request = [0x01, 0x06, 0x00, 0x01, 0x00, 0x03, 0x98, 0x0b];
+        filter.expect(&request);
+        assert!(filter.filter(&request[..3], start).is_empty());
+        filter.written(deadline);
+        assert_eq!(filter.filter(&[&request[3..], &request[..]].concat(), start), request);
+        assert!(!filter.is_pending());
+
+        // Data that is not the echo passes through and ends the expectation
+        filter.expect(b"PING");
+        filter.written(deadline);
+        assert_eq!(filter.filter(b"PIXY", start), b"XY");
+        assert_eq!(filter.filter(b"NG", start), b"NG");
+
+        // The echo cannot be overdue while the write is in progress
+        filter.expect(b"PING");
+        assert!(filter.filter(b"PI", deadline + Duration::from_secs(1)).is_empty());
+        filter.written(deadline);
+        assert_eq!(filter.filter(b"NG", deadline + Duration::from_millis(1)), b"NG");
+
+        // Nothing is expected from a failed write
+        filter.expect(b"PING");
+        filter.clear();
+        assert_eq!(filter.filter(b"PING", start), b"PING");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kernel_mode_requires_driver_support() {
+        use crate::infrastructure::pty::VirtualSerialPair;
+
+        let pair = VirtualSerialPair::open().unwrap();
+        let mut port = serialport::new(pair.first_path().to_string_lossy(), 9600).open().unwrap();
+        let unsupported = |_: &Rs485Config| Err(std::io::Error::from(std::io::ErrorKind::Unsupported));
+
+        let kernel = Rs485Config { mode: Rs485Mode::Kernel, ..Rs485Config::default() };
+        let error = enable(&mut *port, &kernel, unsupported).unwrap_err().to_string();
+        assert!(error.contains("Failed to enable kernel RS-485 mode"), "{}", error);
+
+        let auto = Rs485Config::default();
+        assert_eq!(enable(&mut *port, &auto, |_| Ok(())).unwrap(), Rs485Control::Kernel);
+
+        // Pseudo-terminals have no RS-485 support in their driver
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::io::AsRawFd;
+            drop(port);
+            let native = serialport::new(pair.first_path().to_string_lossy(), 9600).open_native().unwrap();
+            assert!(kernel::enable(native.as_raw_fd(), &kernel).is_err());
+        }
+    }
+}
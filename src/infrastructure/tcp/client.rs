@@ -1,7 +1,10 @@
-use crate::domain::{config::ConnectionConfig, error::{TermComError, TermComResult}};
+use crate::domain::{config::{ConnectionConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver, QueueSender};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
@@ -17,18 +20,36 @@ pub struct TcpMessage {
 pub enum MessageDirection {
     Sent,
     Received,
+    /// The peer closed the connection
+    Disconnected,
+    /// Reading or writing failed; `data` holds the reason
+    Error,
 }
 
 pub struct TcpClient {
-    stream: Arc<Mutex<TcpStream>>,
-    tx_sender: mpsc::UnboundedSender<Vec<u8>>,
-    message_receiver: mpsc::UnboundedReceiver<TcpMessage>,
+    tx_sender: mpsc::Sender<Vec<u8>>,
+    message_receiver: Option<QueueReceiver<TcpMessage>>,
+    /// Messages discarded because nobody kept up with the connection
+    dropped: DropCounter,
+    /// Cleared once the peer closed the connection or I/O failed
+    connected: Arc<AtomicBool>,
+    peer_addr: Option<std::net::SocketAddr>,
+    local_addr: Option<std::net::SocketAddr>,
     _tx_handle: tokio::task::JoinHandle<()>,
     _rx_handle: tokio::task::JoinHandle<()>,
 }
 
 impl TcpClient {
     pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+    
+    /// Connect with messages buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, the socket is not
+    /// read until there is room again, which lets TCP flow control slow the
+    /// peer down. Sends wait once `queue.capacity` writes are queued.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
         let (host, port, timeout_ms, keep_alive) = match config {
             ConnectionConfig::Tcp { 
                 host, 
@@ -62,94 +83,31 @@ impl TcpClient {
         
         info!("TCP connection established to {}:{}", host, port);
         
-        let stream = Arc::new(Mutex::new(stream));
-        let (tx_sender, mut tx_receiver) = mpsc::unbounded_channel::<Vec<u8>>();
-        let (message_sender, message_receiver) = mpsc::unbounded_channel::<TcpMessage>();
-        let message_sender_tx = message_sender.clone();
-        let message_sender_rx = message_sender;
+        let peer_addr = stream.peer_addr().ok();
+        let local_addr = stream.local_addr().ok();
+        let (reader, writer) = stream.into_split();
         
-        // Clone stream for background tasks
-        let stream_tx = Arc::clone(&stream);
-        let stream_rx = Arc::clone(&stream);
-        
-        // TX task - handles outgoing messages
-        let tx_handle = tokio::spawn(async move {
-            while let Some(data) = tx_receiver.recv().await {
-                let mut stream = stream_tx.lock().await;
-                match stream.write_all(&data).await {
-                    Ok(_) => {
-                        if let Err(e) = stream.flush().await {
-                            error!("Failed to flush TCP stream: {}", e);
-                        } else {
-                            debug!("Sent {} bytes over TCP", data.len());
-                            if let Err(e) = message_sender_tx.send(TcpMessage {
-                                timestamp: std::time::SystemTime::now(),
-                                direction: MessageDirection::Sent,
-                                data,
-                            }) {
-                                error!("Failed to send message to channel: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to write to TCP stream: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+        let (tx_sender, tx_receiver) = mpsc::channel::<Vec<u8>>(queue.capacity.max(1));
+        let (message_sender, message_receiver) = queue::bounded::<TcpMessage>(queue);
+        let connected = Arc::new(AtomicBool::new(true));
         
-        // RX task - handles incoming messages
-        let rx_handle = tokio::spawn(async move {
-            let mut buffer = vec![0u8; 4096];
-            
-            loop {
-                let mut stream = stream_rx.lock().await;
-                
-                match tokio::time::timeout(
-                    Duration::from_millis(100),
-                    stream.read(&mut buffer)
-                ).await {
-                    Ok(Ok(0)) => {
-                        // Connection closed by peer
-                        info!("TCP connection closed by peer");
-                        break;
-                    }
-                    Ok(Ok(n)) => {
-                        let data = buffer[..n].to_vec();
-                        debug!("Received {} bytes over TCP", n);
-                        
-                        if let Err(e) = message_sender_rx.send(TcpMessage {
-                            timestamp: std::time::SystemTime::now(),
-                            direction: MessageDirection::Received,
-                            data,
-                        }) {
-                            error!("Failed to send received message to channel: {}", e);
-                        }
-                    }
-                    Ok(Err(e)) => {
-                        error!("Failed to read from TCP stream: {}", e);
-                        break;
-                    }
-                    Err(_) => {
-                        // Timeout - continue reading
-                        continue;
-                    }
-                }
-            }
-        });
+        let tx_handle = tokio::spawn(write_loop(writer, tx_receiver, message_sender.clone(), Arc::clone(&connected)));
+        let rx_handle = tokio::spawn(read_loop(reader, message_sender, Arc::clone(&connected)));
         
         Ok(Self {
-            stream,
             tx_sender,
-            message_receiver,
+            dropped: message_receiver.drop_counter(),
+            message_receiver: Some(message_receiver),
+            connected,
+            peer_addr,
+            local_addr,
             _tx_handle: tx_handle,
             _rx_handle: rx_handle,
         })
     }
     
     pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
-        self.tx_sender.send(data).map_err(|e| TermComError::Communication {
+        self.tx_sender.send(data).await.map_err(|e| TermComError::Communication {
             message: format!("Failed to send data to TCP tx channel: {}", e),
         })?;
         
@@ -157,7 +115,17 @@ impl TcpClient {
     }
     
     pub async fn receive(&mut self) -> Option<TcpMessage> {
-        self.message_receiver.recv().await
+        match self.message_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+    
+    /// Take the message receiver so messages can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_message_receiver(&mut self) -> Option<QueueReceiver<TcpMessage>> {
+        self.message_receiver.take()
     }
     
     pub async fn send_command(&self, command: &str) -> TermComResult<()> {
@@ -165,27 +133,25 @@ impl TcpClient {
         self.send(data).await
     }
     
+    /// Whether the connection is still up, as far as reads and writes tell
     pub async fn is_connected(&self) -> bool {
-        // Try to peek at the stream to check if it's still connected
-        let _stream = self.stream.lock().await;
-        // Simple check - if we can lock the stream, assume it's connected
-        // More sophisticated connection checking could be added here
-        true
+        self.connected.load(Ordering::Relaxed)
+    }
+    
+    /// Messages discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
     }
     
     pub async fn close(self) -> TermComResult<()> {
-        // Drop the sender to signal background tasks to exit
-        drop(self.tx_sender);
+        // Nobody reads messages from here on; dropping the receiver also
+        // releases tasks waiting on a full queue
+        drop(self.message_receiver);
         
-        // Shutdown the stream
-        {
-            let mut stream = self.stream.lock().await;
-            if let Err(e) = stream.shutdown().await {
-                warn!("Failed to shutdown TCP stream: {}", e);
-            }
-        }
+        // Dropping the sender lets the writer finish queued data, then shut
+        // down its half of the connection
+        drop(self.tx_sender);
         
-        // Wait for background tasks to complete
         if let Err(e) = self._tx_handle.await {
             warn!("TX task completed with error: {}", e);
         }
@@ -197,13 +163,85 @@ impl TcpClient {
     }
     
     pub async fn get_peer_addr(&self) -> Option<std::net::SocketAddr> {
-        let stream = self.stream.lock().await;
-        stream.peer_addr().ok()
+        self.peer_addr
     }
     
     pub async fn get_local_addr(&self) -> Option<std::net::SocketAddr> {
-        let stream = self.stream.lock().await;
-        stream.local_addr().ok()
+        self.local_addr
+    }
+}
+
+/// Write queued data until the queue closes or a write fails
+///
+/// Owns the write half, so sends never wait for a read in progress.
+async fn write_loop(
+    mut writer: OwnedWriteHalf,
+    mut queue: mpsc::Receiver<Vec<u8>>,
+    messages: QueueSender<TcpMessage>,
+    connected: Arc<AtomicBool>,
+) {
+    while let Some(data) = queue.recv().await {
+        if let Err(e) = writer.write_all(&data).await {
+            error!("Failed to write to TCP stream: {}", e);
+            connected.store(false, Ordering::Relaxed);
+            let _ = messages.send(TcpMessage {
+                timestamp: std::time::SystemTime::now(),
+                direction: MessageDirection::Error,
+                data: format!("Write failed: {}", e).into_bytes(),
+            }).await;
+            return;
+        }
+        
+        debug!("Sent {} bytes over TCP", data.len());
+        if messages.send(TcpMessage {
+            timestamp: std::time::SystemTime::now(),
+            direction: MessageDirection::Sent,
+            data,
+        }).await.is_err() {
+            debug!("TCP message receiver is gone");
+        }
+    }
+    
+    if let Err(e) = writer.shutdown().await {
+        warn!("Failed to shutdown TCP stream: {}", e);
+    }
+}
+
+/// Hand on received data until the peer closes the connection or a read fails
+async fn read_loop(mut reader: OwnedReadHalf, messages: QueueSender<TcpMessage>, connected: Arc<AtomicBool>) {
+    let mut buffer = vec![0u8; 4096];
+    
+    loop {
+        let (direction, data) = match reader.read(&mut buffer).await {
+            Ok(0) => {
+                info!("TCP connection closed by peer");
+                (MessageDirection::Disconnected, b"Connection closed by peer".to_vec())
+            }
+            Ok(n) => {
+                debug!("Received {} bytes over TCP", n);
+                if messages.send(TcpMessage {
+                    timestamp: std::time::SystemTime::now(),
+                    direction: MessageDirection::Received,
+                    data: buffer[..n].to_vec(),
+                }).await.is_err() {
+                    // Nobody is listening any more
+                    return;
+                }
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to read from TCP stream: {}", e);
+                (MessageDirection::Error, format!("Read failed: {}", e).into_bytes())
+            }
+        };
+        
+        connected.store(false, Ordering::Relaxed);
+        let _ = messages.send(TcpMessage {
+            timestamp: std::time::SystemTime::now(),
+            direction,
+            data,
+        }).await;
+        return;
     }
 }
 
@@ -294,4 +332,56 @@ mod tests {
             assert!(e.to_string().contains("timeout") || e.to_string().contains("Connection"));
         }
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_send_does_not_wait_for_reads() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        // The peer stays silent, so the reader is always waiting
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 64];
+            let mut received = Vec::new();
+            while received.len() < 3 {
+                let n = socket.read(&mut buf).await.unwrap();
+                received.extend_from_slice(&buf[..n]);
+            }
+            received
+        });
+        
+        let mut client = TcpClient::new(&create_test_config(addr.port())).await.unwrap();
+        let start = std::time::Instant::now();
+        for byte in [b'a', b'b', b'c'] {
+            client.send(vec![byte]).await.unwrap();
+            let sent = client.receive().await.unwrap();
+            assert!(matches!(sent.direction, MessageDirection::Sent));
+        }
+        assert!(start.elapsed() < Duration::from_millis(50), "Sends waited {:?}", start.elapsed());
+        assert_eq!(server.await.unwrap(), b"abc");
+        
+        client.close().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_peer_close_is_reported() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"bye").await.unwrap();
+        });
+        
+        let mut client = TcpClient::new(&create_test_config(addr.port())).await.unwrap();
+        let received = client.receive().await.unwrap();
+        assert!(matches!(received.direction, MessageDirection::Received));
+        assert_eq!(received.data, b"bye");
+        
+        let closed = tokio::time::timeout(Duration::from_secs(1), client.receive()).await.unwrap().unwrap();
+        assert!(matches!(closed.direction, MessageDirection::Disconnected));
+        assert!(!client.is_connected().await);
+        
+        client.close().await.unwrap();
+    }
+}
@@ -0,0 +1,340 @@
+use crate::domain::{config::{ConnectionConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver, QueueSender};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// What happened on a peer connection
+#[derive(Debug, Clone)]
+pub enum PeerEvent {
+    /// The peer connected
+    Connected,
+    /// The peer sent data
+    Data(Vec<u8>),
+    /// The peer disconnected
+    Disconnected,
+}
+
+/// An event on one of the listener's peer connections
+#[derive(Debug, Clone)]
+pub struct PeerMessage {
+    pub timestamp: std::time::SystemTime,
+    pub peer: SocketAddr,
+    pub event: PeerEvent,
+}
+
+struct Peer {
+    writer: Arc<Mutex<OwnedWriteHalf>>,
+    reader_handle: tokio::task::JoinHandle<()>,
+}
+
+type PeerMap = Arc<RwLock<HashMap<SocketAddr, Peer>>>;
+
+/// Listening socket that accepts connections from devices
+///
+/// Every accepted peer is read independently; data can be sent to a single
+/// peer or to all of them.
+pub struct DeviceListener {
+    local_addr: SocketAddr,
+    peers: PeerMap,
+    event_receiver: Option<QueueReceiver<PeerMessage>>,
+    /// Events discarded because nobody kept up with the peers
+    dropped: DropCounter,
+    _accept_handle: tokio::task::JoinHandle<()>,
+}
+
+impl DeviceListener {
+    pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+
+    /// Listen with peer events buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, peers are not
+    /// read until there is room again, which lets TCP flow control slow
+    /// them down.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (bind, port, max_clients) = match config {
+            ConnectionConfig::TcpServer { bind, port, max_clients } => (bind.as_str(), *port, *max_clients),
+            _ => return Err(TermComError::Communication {
+                message: "Invalid connection type for TCP listener".to_string(),
+            }),
+        };
+
+        let listener = TcpListener::bind((bind, port)).await.map_err(|e| TermComError::Communication {
+            message: format!("Failed to listen on {}:{}: {}", bind, port, e),
+        })?;
+        let local_addr = listener.local_addr()?;
+
+        info!("TCP listener accepting connections on {}", local_addr);
+
+        let peers: PeerMap = Arc::new(RwLock::new(HashMap::new()));
+        let (event_sender, event_receiver) = queue::bounded(queue);
+
+        let accept_handle = tokio::spawn(Self::accept_loop(listener, Arc::clone(&peers), event_sender, max_clients));
+
+        Ok(Self {
+            local_addr,
+            peers,
+            dropped: event_receiver.drop_counter(),
+            event_receiver: Some(event_receiver),
+            _accept_handle: accept_handle,
+        })
+    }
+
+    async fn accept_loop(
+        listener: TcpListener,
+        peers: PeerMap,
+        event_sender: QueueSender<PeerMessage>,
+        max_clients: Option<usize>,
+    ) {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Failed to accept TCP connection: {}", e);
+                    continue;
+                }
+            };
+
+            let mut peers_guard = peers.write().await;
+            if max_clients.is_some_and(|max| peers_guard.len() >= max) {
+                warn!("Rejecting connection from {}: client limit reached", peer);
+                continue;
+            }
+
+            info!("Accepted TCP connection from {}", peer);
+            let (mut reader, writer) = stream.into_split();
+
+            let reader_peers = Arc::clone(&peers);
+            let reader_sender = event_sender.clone();
+            let reader_handle = tokio::spawn(async move {
+                // Sent from here so a full queue never holds up accepting
+                // with the peer map locked
+                let connected = PeerMessage {
+                    timestamp: std::time::SystemTime::now(),
+                    peer,
+                    event: PeerEvent::Connected,
+                };
+                if reader_sender.send(connected).await.is_err() {
+                    return;
+                }
+
+                let mut buffer = vec![0u8; 4096];
+
+                loop {
+                    match reader.read(&mut buffer).await {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            debug!("Received {} bytes from {}", n, peer);
+                            let message = PeerMessage {
+                                timestamp: std::time::SystemTime::now(),
+                                peer,
+                                event: PeerEvent::Data(buffer[..n].to_vec()),
+                            };
+                            if reader_sender.send(message).await.is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to read from {}: {}", peer, e);
+                            break;
+                        }
+                    }
+                }
+
+                info!("TCP peer {} disconnected", peer);
+                reader_peers.write().await.remove(&peer);
+                let _ = reader_sender.send(PeerMessage {
+                    timestamp: std::time::SystemTime::now(),
+                    peer,
+                    event: PeerEvent::Disconnected,
+                }).await;
+            });
+
+            peers_guard.insert(peer, Peer {
+                writer: Arc::new(Mutex::new(writer)),
+                reader_handle,
+            });
+        }
+    }
+
+    /// Send data to one peer
+    pub async fn send_to(&self, peer: SocketAddr, data: &[u8]) -> TermComResult<()> {
+        let writer = self
+            .peers
+            .read()
+            .await
+            .get(&peer)
+            .map(|peer| Arc::clone(&peer.writer))
+            .ok_or_else(|| TermComError::Communication {
+                message: format!("Peer {} is not connected", peer),
+            })?;
+
+        let mut writer = writer.lock().await;
+        writer.write_all(data).await.map_err(|e| TermComError::Communication {
+            message: format!("Failed to send to {}: {}", peer, e),
+        })?;
+        writer.flush().await?;
+
+        debug!("Sent {} bytes to {}", data.len(), peer);
+        Ok(())
+    }
+
+    /// Send data to every connected peer
+    ///
+    /// Fails if no peer is connected or no peer could be written to.
+    pub async fn broadcast(&self, data: &[u8]) -> TermComResult<()> {
+        let peers = self.peers().await;
+        if peers.is_empty() {
+            return Err(TermComError::Communication {
+                message: format!("No clients connected to {}", self.local_addr),
+            });
+        }
+
+        let mut delivered = 0;
+        let mut last_error = None;
+        for peer in peers {
+            match self.send_to(peer, data).await {
+                Ok(()) => delivered += 1,
+                Err(e) => {
+                    warn!("{}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if delivered == 0 => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the addresses of all connected peers
+    pub async fn peers(&self) -> Vec<SocketAddr> {
+        let mut peers: Vec<_> = self.peers.read().await.keys().copied().collect();
+        peers.sort();
+        peers
+    }
+
+    /// Take the event receiver so events can be consumed outside the listener
+    pub fn take_event_receiver(&mut self) -> Option<QueueReceiver<PeerMessage>> {
+        self.event_receiver.take()
+    }
+
+    /// Events discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    pub fn get_local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop listening and drop every peer connection
+    pub async fn close(self) -> TermComResult<()> {
+        self._accept_handle.abort();
+
+        for (peer, handle) in self.peers.write().await.drain() {
+            handle.reader_handle.abort();
+            if let Err(e) = handle.writer.lock().await.shutdown().await {
+                debug!("Failed to shut down connection to {}: {}", peer, e);
+            }
+        }
+
+        info!("TCP listener on {} closed", self.local_addr);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::net::TcpStream;
+
+    fn create_test_config(max_clients: Option<usize>) -> ConnectionConfig {
+        ConnectionConfig::TcpServer {
+            bind: "127.0.0.1".to_string(),
+            port: 0,
+            max_clients,
+        }
+    }
+
+    async fn next_event(receiver: &mut QueueReceiver<PeerMessage>) -> PeerMessage {
+        tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_listener_serves_several_peers() {
+        let mut listener = DeviceListener::new(&create_test_config(None)).await.unwrap();
+        let mut events = listener.take_event_receiver().unwrap();
+        let addr = listener.get_local_addr();
+
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        let first_event = next_event(&mut events).await;
+        assert!(matches!(first_event.event, PeerEvent::Connected));
+        assert_eq!(first_event.peer, first.local_addr().unwrap());
+
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        assert!(matches!(next_event(&mut events).await.event, PeerEvent::Connected));
+        assert_eq!(listener.peers().await.len(), 2);
+
+        second.write_all(b"hello").await.unwrap();
+        let data = next_event(&mut events).await;
+        assert_eq!(data.peer, second.local_addr().unwrap());
+        assert!(matches!(data.event, PeerEvent::Data(ref bytes) if bytes == b"hello"));
+
+        listener.send_to(first.local_addr().unwrap(), b"one").await.unwrap();
+        listener.broadcast(b"all").await.unwrap();
+
+        let mut buffer = [0u8; 16];
+        let n = first.read(&mut buffer).await.unwrap();
+        let mut received = buffer[..n].to_vec();
+        while received.len() < 6 {
+            let n = first.read(&mut buffer).await.unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+        assert_eq!(received, b"oneall");
+        let n = second.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"all");
+
+        drop(first);
+        let gone = next_event(&mut events).await;
+        assert!(matches!(gone.event, PeerEvent::Disconnected));
+        assert_eq!(listener.peers().await, vec![second.local_addr().unwrap()]);
+
+        listener.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_listener_enforces_client_limit() {
+        let mut listener = DeviceListener::new(&create_test_config(Some(1))).await.unwrap();
+        let mut events = listener.take_event_receiver().unwrap();
+        let addr = listener.get_local_addr();
+
+        let _first = TcpStream::connect(addr).await.unwrap();
+        assert!(matches!(next_event(&mut events).await.event, PeerEvent::Connected));
+
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        let mut buffer = [0u8; 1];
+        let closed = tokio::time::timeout(Duration::from_secs(2), second.read(&mut buffer)).await.unwrap();
+        assert!(matches!(closed, Ok(0) | Err(_)));
+        assert_eq!(listener.peers().await.len(), 1);
+
+        listener.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_without_peers_fails() {
+        let listener = DeviceListener::new(&create_test_config(None)).await.unwrap();
+        assert!(listener.broadcast(b"nobody").await.is_err());
+        assert!(listener.send_to("127.0.0.1:1".parse().unwrap(), b"x").await.is_err());
+        listener.close().await.unwrap();
+    }
+}
@@ -0,0 +1,296 @@
+use crate::domain::{config::{DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::tcp::listener::{DeviceListener, PeerMessage};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use std::sync::Arc;
+use tracing::{error, info};
+
+pub type SessionId = String;
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub device_name: String,
+    pub status: SessionStatus,
+    pub created_at: std::time::SystemTime,
+    pub last_activity: std::time::SystemTime,
+    pub local_addr: SocketAddr,
+    /// Peer events discarded because they were not received in time
+    pub dropped_messages: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionStatus {
+    Connected,
+    Disconnected,
+    Error(String),
+}
+
+pub struct SessionHandle {
+    listener: DeviceListener,
+    info: SessionInfo,
+    _forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.listener.dropped_messages(), ..self.info.clone() }
+    }
+}
+
+/// Manages listening sessions that devices connect to
+pub struct TcpListenerManager {
+    sessions: Arc<RwLock<HashMap<SessionId, SessionHandle>>>,
+    max_sessions: usize,
+    queue: QueueConfig,
+    event_receiver: Mutex<mpsc::Receiver<(SessionId, PeerMessage)>>,
+    event_sender: mpsc::Sender<(SessionId, PeerMessage)>,
+}
+
+impl TcpListenerManager {
+    pub fn new(max_sessions: usize) -> Self {
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+
+    /// Create a manager whose sessions buffer peer events according to `queue`
+    ///
+    /// Events of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (event_sender, event_receiver) = mpsc::channel(queue.capacity.max(1));
+
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            max_sessions,
+            queue,
+            event_receiver: Mutex::new(event_receiver),
+            event_sender,
+        }
+    }
+
+    pub async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<SessionId> {
+        let sessions = self.sessions.read().await;
+
+        if sessions.len() >= self.max_sessions {
+            return Err(TermComError::Communication {
+                message: format!("Maximum number of sessions ({}) reached", self.max_sessions),
+            });
+        }
+
+        // Check if device is already listening
+        if sessions.values().any(|handle| handle.info.device_name == device_config.name) {
+            return Err(TermComError::Communication {
+                message: format!("Device '{}' is already connected", device_config.name),
+            });
+        }
+
+        drop(sessions);
+
+        let mut listener = DeviceListener::with_queue(&device_config.connection, self.queue).await?;
+        let session_id = format!("tcp_server_{}", uuid::Uuid::new_v4().simple());
+
+        let session_info = SessionInfo {
+            id: session_id.clone(),
+            device_name: device_config.name.clone(),
+            status: SessionStatus::Connected,
+            created_at: std::time::SystemTime::now(),
+            last_activity: std::time::SystemTime::now(),
+            local_addr: listener.get_local_addr(),
+            dropped_messages: 0,
+        };
+
+        let forward_handle = Self::spawn_event_forwarder(
+            session_id.clone(),
+            listener.take_event_receiver(),
+            self.event_sender.clone(),
+        );
+
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session_id.clone(), SessionHandle {
+            listener,
+            info: session_info,
+            _forward_handle: forward_handle,
+        });
+
+        info!("Created TCP listener session '{}' for device '{}'", session_id, device_config.name);
+
+        Ok(session_id)
+    }
+
+    pub async fn close_session(&self, session_id: &SessionId) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle._forward_handle.abort();
+            session_handle.listener.close().await?;
+            info!("Closed TCP listener session '{}'", session_id);
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    /// Send data to every client of a session
+    pub async fn send_data(&self, session_id: &SessionId, data: Vec<u8>) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+        let session_handle = sessions.get_mut(session_id).ok_or_else(|| not_found(session_id))?;
+
+        session_handle.listener.broadcast(&data).await?;
+        session_handle.info.last_activity = std::time::SystemTime::now();
+        Ok(())
+    }
+
+    /// Send data to one client of a session
+    pub async fn send_to_peer(&self, session_id: &SessionId, peer: &str, data: Vec<u8>) -> TermComResult<()> {
+        let peer: SocketAddr = peer.parse().map_err(|_| {
+            TermComError::InvalidInput(format!("Invalid peer address '{}', expected IP:PORT", peer))
+        })?;
+
+        let mut sessions = self.sessions.write().await;
+        let session_handle = sessions.get_mut(session_id).ok_or_else(|| not_found(session_id))?;
+
+        session_handle.listener.send_to(peer, &data).await?;
+        session_handle.info.last_activity = std::time::SystemTime::now();
+        Ok(())
+    }
+
+    pub async fn send_command(&self, session_id: &SessionId, command: &str) -> TermComResult<()> {
+        let data = command.as_bytes().to_vec();
+        self.send_data(session_id, data).await
+    }
+
+    /// Get the addresses of the clients connected to a session
+    pub async fn list_peers(&self, session_id: &SessionId) -> Vec<SocketAddr> {
+        let sessions = self.sessions.read().await;
+        match sessions.get(session_id) {
+            Some(handle) => handle.listener.peers().await,
+            None => Vec::new(),
+        }
+    }
+
+    pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(SessionHandle::info)
+    }
+
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.values().map(SessionHandle::info).collect()
+    }
+
+    /// Receive the next peer event from any session, tagged with its session ID
+    pub async fn receive_event(&self) -> Option<(SessionId, PeerMessage)> {
+        self.event_receiver.lock().await.recv().await
+    }
+
+    pub async fn close_all_sessions(&self) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        for (session_id, session_handle) in sessions.drain() {
+            session_handle._forward_handle.abort();
+            if let Err(e) = session_handle.listener.close().await {
+                error!("Failed to close session '{}': {}", session_id, e);
+            }
+        }
+
+        info!("Closed all TCP listener sessions");
+        Ok(())
+    }
+
+    pub async fn is_session_connected(&self, session_id: &SessionId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(session_id)
+    }
+
+    pub async fn get_session_count(&self) -> usize {
+        let sessions = self.sessions.read().await;
+        sessions.len()
+    }
+
+    pub fn get_max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    fn spawn_event_forwarder(
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<PeerMessage>>,
+        sender: mpsc::Sender<(SessionId, PeerMessage)>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+
+            while let Some(event) = receiver.recv().await {
+                if sender.send((session_id.clone(), event)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+fn not_found(session_id: &str) -> TermComError {
+    TermComError::Communication {
+        message: format!("Session '{}' not found", session_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::ConnectionConfig;
+    use crate::infrastructure::tcp::listener::PeerEvent;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpStream;
+
+    fn create_test_device_config(name: &str) -> DeviceConfig {
+        DeviceConfig {
+            description: "Test TCP listener".to_string(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::TcpServer {
+                    bind: "127.0.0.1".to_string(),
+                    port: 0,
+                    max_clients: None,
+                },
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_listener_session_addresses_peers() {
+        let manager = TcpListenerManager::new(5);
+        let session_id = manager.create_session(&create_test_device_config("collector")).await.unwrap();
+        let addr = manager.get_session_info(&session_id).await.unwrap().local_addr;
+
+        assert!(manager.send_data(&session_id, b"nobody".to_vec()).await.is_err());
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (id, event) = tokio::time::timeout(Duration::from_secs(2), manager.receive_event())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, session_id);
+        assert!(matches!(event.event, PeerEvent::Connected));
+
+        let peer = client.local_addr().unwrap();
+        assert_eq!(manager.list_peers(&session_id).await, vec![peer]);
+
+        manager.send_to_peer(&session_id, &peer.to_string(), b"hi".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 8];
+        let n = client.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"hi");
+
+        assert!(manager.send_to_peer(&session_id, "not-an-address", b"x".to_vec()).await.is_err());
+
+        manager.close_session(&session_id).await.unwrap();
+        assert_eq!(manager.get_session_count().await, 0);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::domain::{config::DeviceConfig, error::{TermComError, TermComResult}};
-use crate::infrastructure::tcp::client::{TcpClient, TcpMessage};
+use crate::domain::{config::{DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::tcp::client::{MessageDirection, TcpClient, TcpMessage};
 use crate::infrastructure::tcp::server::{EchoServer, ServerMessage};
 use std::collections::HashMap;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
@@ -17,6 +18,8 @@ pub struct SessionInfo {
     pub last_activity: std::time::SystemTime,
     pub peer_addr: Option<std::net::SocketAddr>,
     pub local_addr: Option<std::net::SocketAddr>,
+    /// Messages discarded because they were not received in time
+    pub dropped_messages: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -29,26 +32,42 @@ pub enum SessionStatus {
 pub struct SessionHandle {
     client: TcpClient,
     info: SessionInfo,
-    #[allow(dead_code)]
-    message_sender: mpsc::UnboundedSender<TcpMessage>,
+    forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.client.dropped_messages(), ..self.info.clone() }
+    }
 }
 
 pub struct TcpManager {
     sessions: Arc<RwLock<HashMap<SessionId, SessionHandle>>>,
     max_sessions: usize,
-    message_receiver: mpsc::UnboundedReceiver<TcpMessage>,
-    message_sender: mpsc::UnboundedSender<TcpMessage>,
+    queue: QueueConfig,
+    message_receiver: Mutex<mpsc::Receiver<(SessionId, TcpMessage)>>,
+    message_sender: mpsc::Sender<(SessionId, TcpMessage)>,
     echo_server: Option<EchoServer>,
 }
 
 impl TcpManager {
     pub fn new(max_sessions: usize) -> Self {
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+    
+    /// Create a manager whose sessions buffer messages according to `queue`
+    ///
+    /// Messages of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(queue.capacity.max(1));
         
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             max_sessions,
-            message_receiver,
+            queue,
+            message_receiver: Mutex::new(message_receiver),
             message_sender,
             echo_server: None,
         }
@@ -72,7 +91,7 @@ impl TcpManager {
         
         drop(sessions);
         
-        let client = TcpClient::new(&device_config.connection).await?;
+        let mut client = TcpClient::with_queue(&device_config.connection, self.queue).await?;
         let session_id = format!("tcp_{}", uuid::Uuid::new_v4().simple());
         
         // Get connection info
@@ -87,12 +106,15 @@ impl TcpManager {
             last_activity: std::time::SystemTime::now(),
             peer_addr,
             local_addr,
+            dropped_messages: 0,
         };
         
+        let forward_handle = self.spawn_message_forwarder(session_id.clone(), client.take_message_receiver());
+        
         let session_handle = SessionHandle {
             client,
             info: session_info,
-            message_sender: self.message_sender.clone(),
+            forward_handle,
         };
         
         let mut sessions = self.sessions.write().await;
@@ -107,6 +129,7 @@ impl TcpManager {
         let mut sessions = self.sessions.write().await;
         
         if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle.forward_handle.abort();
             session_handle.client.close().await?;
             info!("Closed TCP session '{}'", session_id);
             Ok(())
@@ -121,6 +144,15 @@ impl TcpManager {
         let sessions = self.sessions.read().await;
         
         if let Some(session_handle) = sessions.get(session_id) {
+            match &session_handle.info.status {
+                SessionStatus::Connected => {}
+                SessionStatus::Disconnected => return Err(TermComError::Communication {
+                    message: format!("TCP session '{}' was closed by the peer", session_id),
+                }),
+                SessionStatus::Error(e) => return Err(TermComError::Communication {
+                    message: format!("TCP session '{}' failed: {}", session_id, e),
+                }),
+            }
             session_handle.client.send(data).await?;
             
             // Update last activity
@@ -145,16 +177,17 @@ impl TcpManager {
     
     pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
         let sessions = self.sessions.read().await;
-        sessions.get(session_id).map(|handle| handle.info.clone())
+        sessions.get(session_id).map(SessionHandle::info)
     }
     
     pub async fn list_sessions(&self) -> Vec<SessionInfo> {
         let sessions = self.sessions.read().await;
-        sessions.values().map(|handle| handle.info.clone()).collect()
+        sessions.values().map(SessionHandle::info).collect()
     }
     
-    pub async fn receive_message(&mut self) -> Option<TcpMessage> {
-        self.message_receiver.recv().await
+    /// Receive the next message from any session, tagged with its session ID
+    pub async fn receive_message(&self) -> Option<(SessionId, TcpMessage)> {
+        self.message_receiver.lock().await.recv().await
     }
     
     pub async fn close_all_sessions(&self) -> TermComResult<()> {
@@ -163,6 +196,7 @@ impl TcpManager {
         
         for session_id in session_ids {
             if let Some(session_handle) = sessions.remove(&session_id) {
+                session_handle.forward_handle.abort();
                 if let Err(e) = session_handle.client.close().await {
                     error!("Failed to close session '{}': {}", session_id, e);
                 }
@@ -173,13 +207,12 @@ impl TcpManager {
         Ok(())
     }
     
+    /// Whether the session belongs to this manager
+    ///
+    /// Sessions whose connection went down still count, so they can be
+    /// closed; their status tells whether the connection is up.
     pub async fn is_session_connected(&self, session_id: &SessionId) -> bool {
-        let sessions = self.sessions.read().await;
-        if let Some(session_handle) = sessions.get(session_id) {
-            session_handle.client.is_connected().await
-        } else {
-            false
-        }
+        self.sessions.read().await.contains_key(session_id)
     }
     
     pub async fn get_session_count(&self) -> usize {
@@ -191,6 +224,42 @@ impl TcpManager {
         self.max_sessions
     }
     
+    /// Forward a session's messages to the shared receiver
+    ///
+    /// A closed or failed connection is also recorded in the session status.
+    fn spawn_message_forwarder(
+        &self,
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<TcpMessage>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let sessions = Arc::clone(&self.sessions);
+        let sender = self.message_sender.clone();
+        
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+            
+            while let Some(message) = receiver.recv().await {
+                let status = match message.direction {
+                    MessageDirection::Disconnected => Some(SessionStatus::Disconnected),
+                    MessageDirection::Error => Some(SessionStatus::Error(String::from_utf8_lossy(&message.data).into_owned())),
+                    _ => None,
+                };
+                if let Some(status) = status {
+                    if let Some(session_handle) = sessions.write().await.get_mut(&session_id) {
+                        warn!("TCP session '{}' lost its connection: {}", session_id, String::from_utf8_lossy(&message.data));
+                        session_handle.info.status = status;
+                    }
+                }
+                
+                if sender.send((session_id.clone(), message)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+    
     pub async fn get_session_stats(&self, session_id: &SessionId) -> Option<SessionStats> {
         let sessions = self.sessions.read().await;
         if let Some(session_handle) = sessions.get(session_id) {
@@ -256,7 +325,7 @@ impl TcpManager {
     }
     
     pub fn is_echo_server_running(&self) -> bool {
-        self.echo_server.as_ref().map_or(false, |s| s.is_running())
+        self.echo_server.as_ref().is_some_and(|s| s.is_running())
     }
     
     pub async fn get_echo_server_stats(&self) -> Option<crate::infrastructure::tcp::server::ServerStats> {
@@ -313,15 +382,16 @@ mod tests {
     
     fn create_test_device_config(name: &str, port: u16) -> DeviceConfig {
         DeviceConfig {
-            name: name.to_string(),
             description: "Test TCP device".to_string(),
-            connection: ConnectionConfig::Tcp {
-                host: "127.0.0.1".to_string(),
-                port,
-                timeout_ms: 1000,
-                keep_alive: true,
-            },
-            commands: Vec::new(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port,
+                    timeout_ms: 1000,
+                    keep_alive: true,
+                },
+            )
         }
     }
     
@@ -391,6 +461,7 @@ mod tests {
             last_activity: std::time::SystemTime::now(),
             peer_addr: None,
             local_addr: None,
+            dropped_messages: 0,
         };
         
         assert_eq!(session_info.id, "test_session");
@@ -449,4 +520,31 @@ mod tests {
             let _ = manager.close_session(&session_id).await;
         }
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_peer_close_updates_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        // Accept and hang up straight away
+        tokio::spawn(async move {
+            let _ = listener.accept().await;
+        });
+        
+        let manager = TcpManager::new(5);
+        let session_id = manager.create_session(&create_test_device_config("flaky", addr.port())).await.unwrap();
+        
+        let (id, message) = tokio::time::timeout(Duration::from_secs(1), manager.receive_message()).await.unwrap().unwrap();
+        assert_eq!(id, session_id);
+        assert!(matches!(message.direction, MessageDirection::Disconnected));
+        
+        let info = manager.get_session_info(&session_id).await.unwrap();
+        assert!(matches!(info.status, SessionStatus::Disconnected));
+        let error = manager.send_data(&session_id, b"ping".to_vec()).await.unwrap_err();
+        assert!(error.to_string().contains("closed by the peer"), "{}", error);
+        
+        // Lost sessions can still be closed
+        assert!(manager.is_session_connected(&session_id).await);
+        manager.close_session(&session_id).await.unwrap();
+    }
+}
@@ -1,7 +1,10 @@
 // TCP module - TCP communication implementation
 pub mod client;
+pub mod listener;
+pub mod listener_manager;
 pub mod manager;
 pub mod server;
 
+pub use listener_manager::TcpListenerManager;
 pub use manager::TcpManager;
 pub use server::EchoServer;
\ No newline at end of file
@@ -1,4 +1,6 @@
+use crate::domain::config::{OverflowPolicy, QueueConfig};
 use crate::domain::error::{TermComError, TermComResult};
+use crate::infrastructure::queue::{self, QueueReceiver, QueueSender};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::{mpsc, Mutex};
@@ -26,8 +28,8 @@ pub struct EchoServer {
     listener: TcpListener,
     bind_addr: SocketAddr,
     clients: Arc<Mutex<Vec<ClientConnection>>>,
-    message_sender: mpsc::UnboundedSender<ServerMessage>,
-    message_receiver: mpsc::UnboundedReceiver<ServerMessage>,
+    message_sender: QueueSender<ServerMessage>,
+    message_receiver: QueueReceiver<ServerMessage>,
     shutdown_sender: mpsc::Sender<()>,
     shutdown_receiver: mpsc::Receiver<()>,
     server_handle: Option<tokio::task::JoinHandle<()>>,
@@ -45,7 +47,12 @@ impl EchoServer {
                 message: format!("Failed to get local address: {}", e),
             })?;
         
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+        // Received data is only kept for monitoring, so the oldest is let go
+        // rather than holding up the echo when nobody reads it
+        let (message_sender, message_receiver) = queue::bounded(QueueConfig {
+            overflow: OverflowPolicy::DropOldest,
+            ..QueueConfig::default()
+        });
         let (shutdown_sender, shutdown_receiver) = mpsc::channel(1);
         
         info!("Echo server created on {}", actual_addr);
@@ -147,7 +154,7 @@ impl EchoServer {
         mut stream: TcpStream,
         addr: SocketAddr,
         clients: Arc<Mutex<Vec<ClientConnection>>>,
-        message_sender: mpsc::UnboundedSender<ServerMessage>,
+        message_sender: QueueSender<ServerMessage>,
     ) -> TermComResult<()> {
         let mut buffer = vec![0u8; 4096];
         
@@ -175,12 +182,12 @@ impl EchoServer {
                     }
                     
                     // Send message to channel for monitoring
-                    if let Err(e) = message_sender.send(ServerMessage {
+                    if message_sender.send(ServerMessage {
                         timestamp: std::time::SystemTime::now(),
                         client_addr: addr,
                         data: data.clone(),
-                    }) {
-                        warn!("Failed to send message to channel: {}", e);
+                    }).await.is_err() {
+                        warn!("Failed to send message to channel: receiver is gone");
                     }
                     
                     // Echo the data back
@@ -320,7 +327,7 @@ mod tests {
     #[tokio::test]
     async fn test_echo_server_start_stop() {
         let mut server = EchoServer::new("127.0.0.1:0").await.unwrap();
-        let addr = server.get_bind_addr();
+        let _addr = server.get_bind_addr();
         
         // Start server
         let start_result = server.start().await;
@@ -0,0 +1,302 @@
+use crate::domain::{config::{ConnectionConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver};
+use tokio::net::{lookup_host, UdpSocket};
+use tokio::sync::Mutex;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+/// Largest payload a UDP datagram can carry
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// A datagram received from a peer
+#[derive(Debug, Clone)]
+pub struct UdpDatagram {
+    pub timestamp: std::time::SystemTime,
+    pub peer: SocketAddr,
+    pub data: Vec<u8>,
+}
+
+/// UDP socket bound to a local address
+///
+/// Sent data goes to the configured remote, or to the last peer a datagram
+/// was received from when no remote is configured.
+pub struct UdpClient {
+    socket: Arc<UdpSocket>,
+    remote: Option<SocketAddr>,
+    last_peer: Arc<Mutex<Option<SocketAddr>>>,
+    datagram_receiver: Option<QueueReceiver<UdpDatagram>>,
+    /// Datagrams discarded because nobody kept up with the socket
+    dropped: DropCounter,
+    _rx_handle: tokio::task::JoinHandle<()>,
+}
+
+impl UdpClient {
+    pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+
+    /// Bind with received datagrams buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, the socket is not
+    /// read until there is room again, and the kernel drops what does not
+    /// fit its own buffer.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (bind, remote, broadcast, multicast) = match config {
+            ConnectionConfig::Udp {
+                bind,
+                remote,
+                broadcast,
+                multicast,
+            } => (bind.as_str(), remote.as_deref(), *broadcast, multicast.as_deref()),
+            _ => return Err(TermComError::Communication {
+                message: "Invalid connection type for UDP client".to_string(),
+            }),
+        };
+
+        let socket = UdpSocket::bind(bind).await.map_err(|e| TermComError::Communication {
+            message: format!("Failed to bind UDP socket to {}: {}", bind, e),
+        })?;
+
+        if broadcast {
+            socket.set_broadcast(true).map_err(|e| TermComError::Communication {
+                message: format!("Failed to enable UDP broadcast: {}", e),
+            })?;
+        }
+
+        if let Some(group) = multicast {
+            join_multicast(&socket, group)?;
+        }
+
+        let remote = match remote {
+            Some(remote) => Some(resolve(remote).await?),
+            None => None,
+        };
+
+        info!("UDP socket bound to {}", bind);
+
+        let socket = Arc::new(socket);
+        let last_peer = Arc::new(Mutex::new(None));
+        let (datagram_sender, datagram_receiver) = queue::bounded(queue);
+
+        let rx_socket = Arc::clone(&socket);
+        let rx_last_peer = Arc::clone(&last_peer);
+        let rx_handle = tokio::spawn(async move {
+            let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+
+            loop {
+                match rx_socket.recv_from(&mut buffer).await {
+                    Ok((n, peer)) => {
+                        debug!("Received {} byte datagram from {}", n, peer);
+                        *rx_last_peer.lock().await = Some(peer);
+
+                        let datagram = UdpDatagram {
+                            timestamp: std::time::SystemTime::now(),
+                            peer,
+                            data: buffer[..n].to_vec(),
+                        };
+                        if datagram_sender.send(datagram).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        // ICMP errors from earlier sends surface here; the socket stays usable
+                        warn!("Failed to receive UDP datagram: {}", e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            socket,
+            remote,
+            last_peer,
+            dropped: datagram_receiver.drop_counter(),
+            datagram_receiver: Some(datagram_receiver),
+            _rx_handle: rx_handle,
+        })
+    }
+
+    /// Send data as a single datagram
+    pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
+        let target = match self.remote {
+            Some(remote) => remote,
+            None => self.last_peer.lock().await.ok_or_else(|| TermComError::Communication {
+                message: "No UDP destination: configure a remote or wait for a peer to send first".to_string(),
+            })?,
+        };
+
+        self.send_to(data, target).await
+    }
+
+    /// Send data as a single datagram to a specific peer
+    pub async fn send_to(&self, data: Vec<u8>, target: SocketAddr) -> TermComResult<()> {
+        let sent = self.socket.send_to(&data, target).await.map_err(|e| TermComError::Communication {
+            message: format!("Failed to send UDP datagram to {}: {}", target, e),
+        })?;
+
+        if sent < data.len() {
+            error!("UDP datagram to {} truncated: {} of {} bytes sent", target, sent, data.len());
+        }
+        debug!("Sent {} byte datagram to {}", sent, target);
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> Option<UdpDatagram> {
+        match self.datagram_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+
+    /// Take the datagram receiver so datagrams can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_datagram_receiver(&mut self) -> Option<QueueReceiver<UdpDatagram>> {
+        self.datagram_receiver.take()
+    }
+
+    /// Datagrams discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    pub fn get_local_addr(&self) -> Option<SocketAddr> {
+        self.socket.local_addr().ok()
+    }
+
+    pub fn get_remote_addr(&self) -> Option<SocketAddr> {
+        self.remote
+    }
+
+    pub async fn close(self) -> TermComResult<()> {
+        self._rx_handle.abort();
+        info!("UDP socket closed");
+        Ok(())
+    }
+}
+
+async fn resolve(address: &str) -> TermComResult<SocketAddr> {
+    lookup_host(address)
+        .await
+        .map_err(|e| TermComError::Communication {
+            message: format!("Failed to resolve UDP remote {}: {}", address, e),
+        })?
+        .next()
+        .ok_or_else(|| TermComError::Communication {
+            message: format!("UDP remote {} did not resolve to an address", address),
+        })
+}
+
+fn join_multicast(socket: &UdpSocket, group: &str) -> TermComResult<()> {
+    let group: IpAddr = group.parse().map_err(|_| TermComError::Communication {
+        message: format!("Invalid multicast group '{}'", group),
+    })?;
+
+    let result = match group {
+        IpAddr::V4(group) => socket.join_multicast_v4(group, Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(group) => socket.join_multicast_v6(&group, 0),
+    };
+
+    result.map_err(|e| TermComError::Communication {
+        message: format!("Failed to join multicast group {}: {}", group, e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn create_test_config(remote: Option<String>) -> ConnectionConfig {
+        ConnectionConfig::Udp {
+            bind: "127.0.0.1:0".to_string(),
+            remote,
+            broadcast: false,
+            multicast: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_udp_client_keeps_datagram_boundaries() {
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+
+        let mut client = UdpClient::new(&create_test_config(Some(peer_addr.to_string()))).await.unwrap();
+        let client_addr = client.get_local_addr().unwrap();
+
+        peer.send_to(b"first", client_addr).await.unwrap();
+        peer.send_to(b"second", client_addr).await.unwrap();
+
+        for expected in [&b"first"[..], &b"second"[..]] {
+            let datagram = tokio::time::timeout(Duration::from_secs(1), client.receive())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(datagram.data, expected);
+            assert_eq!(datagram.peer, peer_addr);
+        }
+
+        client.send(b"reply".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let (n, from) = peer.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"reply");
+        assert_eq!(from, client_addr);
+    }
+
+    #[tokio::test]
+    async fn test_udp_client_replies_to_last_peer() {
+        let mut client = UdpClient::new(&create_test_config(None)).await.unwrap();
+        assert!(client.send(b"nobody".to_vec()).await.is_err());
+
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        peer.send_to(b"hello", client.get_local_addr().unwrap()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), client.receive()).await.unwrap().unwrap();
+
+        client.send(b"back".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let (n, _) = peer.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"back");
+    }
+
+    #[tokio::test]
+    async fn test_udp_client_counts_dropped_datagrams() {
+        let queue = QueueConfig { capacity: 1, overflow: crate::domain::config::OverflowPolicy::DropNewest };
+        let mut client = UdpClient::with_queue(&create_test_config(None), queue).await.unwrap();
+        let client_addr = client.get_local_addr().unwrap();
+
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        for data in [&b"kept"[..], &b"lost"[..]] {
+            peer.send_to(data, client_addr).await.unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while client.dropped_messages() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let datagram = client.receive().await.unwrap();
+        assert_eq!(datagram.data, b"kept");
+        assert_eq!(client.dropped_messages(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_udp_client_rejects_invalid_config() {
+        let mut config = create_test_config(None);
+        if let ConnectionConfig::Udp { multicast, .. } = &mut config {
+            *multicast = Some("not-an-address".to_string());
+        }
+        assert!(UdpClient::new(&config).await.is_err());
+
+        let tcp = ConnectionConfig::Tcp {
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            timeout_ms: 100,
+            keep_alive: false,
+        };
+        assert!(UdpClient::new(&tcp).await.is_err());
+    }
+}
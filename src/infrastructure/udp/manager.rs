@@ -0,0 +1,285 @@
+use crate::domain::{config::{DeviceConfig, QueueConfig}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::QueueReceiver;
+use crate::infrastructure::udp::client::{UdpClient, UdpDatagram};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use std::sync::Arc;
+use tracing::{error, info};
+
+pub type SessionId = String;
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: SessionId,
+    pub device_name: String,
+    pub status: SessionStatus,
+    pub created_at: std::time::SystemTime,
+    pub last_activity: std::time::SystemTime,
+    pub local_addr: Option<std::net::SocketAddr>,
+    pub remote_addr: Option<std::net::SocketAddr>,
+    /// Datagrams discarded because they were not received in time
+    pub dropped_messages: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionStatus {
+    Connected,
+    Disconnected,
+    Error(String),
+}
+
+pub struct SessionHandle {
+    client: UdpClient,
+    info: SessionInfo,
+    _forward_handle: tokio::task::JoinHandle<()>,
+}
+
+impl SessionHandle {
+    fn info(&self) -> SessionInfo {
+        SessionInfo { dropped_messages: self.client.dropped_messages(), ..self.info.clone() }
+    }
+}
+
+pub struct UdpManager {
+    sessions: Arc<RwLock<HashMap<SessionId, SessionHandle>>>,
+    max_sessions: usize,
+    queue: QueueConfig,
+    datagram_receiver: Mutex<mpsc::Receiver<(SessionId, UdpDatagram)>>,
+    datagram_sender: mpsc::Sender<(SessionId, UdpDatagram)>,
+}
+
+impl UdpManager {
+    pub fn new(max_sessions: usize) -> Self {
+        Self::with_queue(max_sessions, QueueConfig::default())
+    }
+
+    /// Create a manager whose sessions buffer datagrams according to `queue`
+    ///
+    /// Datagrams of all sessions are handed on through one queue of the same
+    /// capacity. When it is full, each session's own queue fills up and its
+    /// overflow policy decides what happens.
+    pub fn with_queue(max_sessions: usize, queue: QueueConfig) -> Self {
+        let (datagram_sender, datagram_receiver) = mpsc::channel(queue.capacity.max(1));
+
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            max_sessions,
+            queue,
+            datagram_receiver: Mutex::new(datagram_receiver),
+            datagram_sender,
+        }
+    }
+
+    pub async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<SessionId> {
+        let sessions = self.sessions.read().await;
+
+        if sessions.len() >= self.max_sessions {
+            return Err(TermComError::Communication {
+                message: format!("Maximum number of sessions ({}) reached", self.max_sessions),
+            });
+        }
+
+        // Check if device is already connected
+        if sessions.values().any(|handle| handle.info.device_name == device_config.name) {
+            return Err(TermComError::Communication {
+                message: format!("Device '{}' is already connected", device_config.name),
+            });
+        }
+
+        drop(sessions);
+
+        let mut client = UdpClient::with_queue(&device_config.connection, self.queue).await?;
+        let session_id = format!("udp_{}", uuid::Uuid::new_v4().simple());
+
+        let session_info = SessionInfo {
+            id: session_id.clone(),
+            device_name: device_config.name.clone(),
+            status: SessionStatus::Connected,
+            created_at: std::time::SystemTime::now(),
+            last_activity: std::time::SystemTime::now(),
+            local_addr: client.get_local_addr(),
+            remote_addr: client.get_remote_addr(),
+            dropped_messages: 0,
+        };
+
+        let forward_handle = Self::spawn_datagram_forwarder(
+            session_id.clone(),
+            client.take_datagram_receiver(),
+            self.datagram_sender.clone(),
+        );
+
+        let session_handle = SessionHandle {
+            client,
+            info: session_info,
+            _forward_handle: forward_handle,
+        };
+
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session_id.clone(), session_handle);
+
+        info!("Created UDP session '{}' for device '{}'", session_id, device_config.name);
+
+        Ok(session_id)
+    }
+
+    pub async fn close_session(&self, session_id: &SessionId) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.remove(session_id) {
+            session_handle._forward_handle.abort();
+            session_handle.client.close().await?;
+            info!("Closed UDP session '{}'", session_id);
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_data(&self, session_id: &SessionId, data: Vec<u8>) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session_handle) = sessions.get_mut(session_id) {
+            session_handle.client.send(data).await?;
+            session_handle.info.last_activity = std::time::SystemTime::now();
+            Ok(())
+        } else {
+            Err(TermComError::Communication {
+                message: format!("Session '{}' not found", session_id),
+            })
+        }
+    }
+
+    pub async fn send_command(&self, session_id: &SessionId, command: &str) -> TermComResult<()> {
+        let data = command.as_bytes().to_vec();
+        self.send_data(session_id, data).await
+    }
+
+    pub async fn get_session_info(&self, session_id: &SessionId) -> Option<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(SessionHandle::info)
+    }
+
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let sessions = self.sessions.read().await;
+        sessions.values().map(SessionHandle::info).collect()
+    }
+
+    /// Receive the next datagram from any session, tagged with its session ID
+    pub async fn receive_datagram(&self) -> Option<(SessionId, UdpDatagram)> {
+        self.datagram_receiver.lock().await.recv().await
+    }
+
+    pub async fn close_all_sessions(&self) -> TermComResult<()> {
+        let mut sessions = self.sessions.write().await;
+
+        for (session_id, session_handle) in sessions.drain() {
+            session_handle._forward_handle.abort();
+            if let Err(e) = session_handle.client.close().await {
+                error!("Failed to close session '{}': {}", session_id, e);
+            }
+        }
+
+        info!("Closed all UDP sessions");
+        Ok(())
+    }
+
+    pub async fn is_session_connected(&self, session_id: &SessionId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(session_id)
+    }
+
+    pub async fn get_session_count(&self) -> usize {
+        let sessions = self.sessions.read().await;
+        sessions.len()
+    }
+
+    pub fn get_max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    fn spawn_datagram_forwarder(
+        session_id: SessionId,
+        receiver: Option<QueueReceiver<UdpDatagram>>,
+        sender: mpsc::Sender<(SessionId, UdpDatagram)>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let Some(mut receiver) = receiver else {
+                return;
+            };
+
+            while let Some(datagram) = receiver.recv().await {
+                if sender.send((session_id.clone(), datagram)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::ConnectionConfig;
+    use std::time::Duration;
+    use tokio::net::UdpSocket;
+
+    fn create_test_device_config(name: &str, remote: Option<String>) -> DeviceConfig {
+        DeviceConfig {
+            description: "Test UDP device".to_string(),
+            ..DeviceConfig::new(
+                name,
+                ConnectionConfig::Udp {
+                    bind: "127.0.0.1:0".to_string(),
+                    remote,
+                    broadcast: false,
+                    multicast: None,
+                },
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_udp_session_round_trip() {
+        let peer = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+
+        let manager = UdpManager::new(5);
+        let session_id = manager
+            .create_session(&create_test_device_config("beacon", Some(peer_addr.to_string())))
+            .await
+            .unwrap();
+        let info = manager.get_session_info(&session_id).await.unwrap();
+        assert_eq!(info.remote_addr, Some(peer_addr));
+
+        peer.send_to(b"ping", info.local_addr.unwrap()).await.unwrap();
+        let (id, datagram) = tokio::time::timeout(Duration::from_secs(1), manager.receive_datagram())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, session_id);
+        assert_eq!(datagram.data, b"ping");
+        assert_eq!(datagram.peer, peer_addr);
+
+        manager.send_data(&session_id, b"pong".to_vec()).await.unwrap();
+        let mut buffer = [0u8; 16];
+        let (n, _) = peer.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"pong");
+
+        manager.close_session(&session_id).await.unwrap();
+        assert_eq!(manager.get_session_count().await, 0);
+        assert!(manager.close_session(&session_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_max_sessions_limit() {
+        let manager = UdpManager::new(1);
+        manager.create_session(&create_test_device_config("first", None)).await.unwrap();
+
+        let result = manager.create_session(&create_test_device_config("second", None)).await;
+        assert!(result.unwrap_err().to_string().contains("Maximum number"));
+
+        manager.close_all_sessions().await.unwrap();
+    }
+}
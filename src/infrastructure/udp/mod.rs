@@ -0,0 +1,5 @@
+// UDP module - UDP communication implementation
+pub mod client;
+pub mod manager;
+
+pub use manager::UdpManager;
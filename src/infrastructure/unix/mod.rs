@@ -0,0 +1,5 @@
+// Unix module - Unix domain socket communication implementation
+pub mod manager;
+pub mod socket;
+
+pub use manager::UnixManager;
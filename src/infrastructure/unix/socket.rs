@@ -0,0 +1,397 @@
+use crate::domain::{config::{ConnectionConfig, QueueConfig, UnixSocketMode}, error::{TermComError, TermComResult}};
+use crate::infrastructure::queue::{self, DropCounter, QueueReceiver, QueueSender};
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixDatagram, UnixListener, UnixStream};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Largest datagram read from a datagram socket
+const MAX_DATAGRAM_SIZE: usize = 65_536;
+
+/// What happened on a Unix domain socket
+#[derive(Debug, Clone)]
+pub enum UnixEvent {
+    /// Data arrived, with the sender's path for named datagram peers
+    Data { peer: Option<PathBuf>, data: Vec<u8> },
+    /// A client connected to a listening stream socket
+    PeerConnected,
+    /// The client of a listening stream socket disconnected
+    PeerDisconnected,
+    /// The socket we connected to was closed by the other side
+    Closed,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnixMessage {
+    pub timestamp: std::time::SystemTime,
+    pub event: UnixEvent,
+}
+
+impl UnixMessage {
+    fn new(event: UnixEvent) -> Self {
+        Self {
+            timestamp: std::time::SystemTime::now(),
+            event,
+        }
+    }
+}
+
+type SharedWriter = Arc<Mutex<Option<OwnedWriteHalf>>>;
+
+enum SocketIo {
+    /// Stream connection; `None` while a listening socket has no client
+    Stream(SharedWriter),
+    /// Datagram socket sending to `target`, or to the last sender when unset
+    Datagram {
+        socket: Arc<UnixDatagram>,
+        target: Option<PathBuf>,
+        last_peer: Arc<Mutex<Option<PathBuf>>>,
+    },
+}
+
+/// Unix domain socket in one of the [`UnixSocketMode`]s
+///
+/// Socket files created by the client (the listening path, or the local
+/// address a datagram client needs to get replies) are removed on close.
+pub struct UnixSocketClient {
+    path: PathBuf,
+    mode: UnixSocketMode,
+    io: SocketIo,
+    owned_path: Option<PathBuf>,
+    message_receiver: Option<QueueReceiver<UnixMessage>>,
+    /// Messages discarded because they were not received in time
+    dropped: DropCounter,
+    _io_handle: tokio::task::JoinHandle<()>,
+}
+
+impl UnixSocketClient {
+    pub async fn new(config: &ConnectionConfig) -> TermComResult<Self> {
+        Self::with_queue(config, QueueConfig::default()).await
+    }
+
+    /// Open the socket with received messages buffered according to `queue`
+    ///
+    /// When the queue is full and the policy is to block, the socket is not
+    /// read until there is room again, so stream peers are slowed down and
+    /// datagrams that do not fit in the kernel buffer are lost.
+    pub async fn with_queue(config: &ConnectionConfig, queue: QueueConfig) -> TermComResult<Self> {
+        let (path, mode) = match config {
+            ConnectionConfig::Unix { path, mode } => (PathBuf::from(path), *mode),
+            _ => return Err(TermComError::Communication {
+                message: "Invalid connection type for Unix socket client".to_string(),
+            }),
+        };
+
+        let (message_sender, message_receiver) = queue::bounded(queue);
+
+        let (io, owned_path, io_handle) = match mode {
+            UnixSocketMode::Stream => {
+                let stream = UnixStream::connect(&path).await.map_err(|e| socket_error("connect to", &path, e))?;
+                let (reader, writer) = stream.into_split();
+                let handle = tokio::spawn(async move {
+                    read_stream(reader, &message_sender).await;
+                    let _ = message_sender.send(UnixMessage::new(UnixEvent::Closed)).await;
+                });
+                info!("Connected to Unix socket {}", path.display());
+                (SocketIo::Stream(Arc::new(Mutex::new(Some(writer)))), None, handle)
+            }
+            UnixSocketMode::StreamListen => {
+                remove_stale_socket(&path, mode).await;
+                let listener = UnixListener::bind(&path).map_err(|e| socket_error("listen on", &path, e))?;
+                let writer: SharedWriter = Arc::new(Mutex::new(None));
+                let handle = tokio::spawn(accept_loop(listener, Arc::clone(&writer), message_sender));
+                info!("Listening on Unix socket {}", path.display());
+                (SocketIo::Stream(writer), Some(path.clone()), handle)
+            }
+            UnixSocketMode::Datagram => {
+                // Bind a local address so the other side can reply
+                let local = std::env::temp_dir().join(format!("termcom-{}.sock", uuid::Uuid::new_v4().simple()));
+                let socket = UnixDatagram::bind(&local).map_err(|e| socket_error("bind", &local, e))?;
+                if let Err(e) = socket.connect(&path) {
+                    let _ = std::fs::remove_file(&local);
+                    return Err(socket_error("connect to", &path, e));
+                }
+                let socket = Arc::new(socket);
+                let last_peer = Arc::new(Mutex::new(None));
+                let handle = tokio::spawn(read_datagrams(Arc::clone(&socket), Arc::clone(&last_peer), message_sender));
+                info!("Sending datagrams to Unix socket {} from {}", path.display(), local.display());
+                (SocketIo::Datagram { socket, target: Some(path.clone()), last_peer }, Some(local), handle)
+            }
+            UnixSocketMode::DatagramListen => {
+                remove_stale_socket(&path, mode).await;
+                let socket = Arc::new(UnixDatagram::bind(&path).map_err(|e| socket_error("bind", &path, e))?);
+                let last_peer = Arc::new(Mutex::new(None));
+                let handle = tokio::spawn(read_datagrams(Arc::clone(&socket), Arc::clone(&last_peer), message_sender));
+                info!("Receiving datagrams on Unix socket {}", path.display());
+                (SocketIo::Datagram { socket, target: None, last_peer }, Some(path.clone()), handle)
+            }
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            io,
+            owned_path,
+            dropped: message_receiver.drop_counter(),
+            message_receiver: Some(message_receiver),
+            _io_handle: io_handle,
+        })
+    }
+
+    pub async fn send(&self, data: Vec<u8>) -> TermComResult<()> {
+        match &self.io {
+            SocketIo::Stream(writer) => {
+                let mut writer = writer.lock().await;
+                let stream = writer.as_mut().ok_or_else(|| TermComError::Communication {
+                    message: format!("No client connected to {}", self.path.display()),
+                })?;
+                stream.write_all(&data).await.map_err(|e| socket_error("write to", &self.path, e))?;
+                stream.flush().await?;
+            }
+            SocketIo::Datagram { socket, target, last_peer } => {
+                let target = match target {
+                    Some(target) => target.clone(),
+                    None => last_peer.lock().await.clone().ok_or_else(|| TermComError::Communication {
+                        message: format!("No datagram received on {} to reply to yet", self.path.display()),
+                    })?,
+                };
+                socket.send_to(&data, &target).await.map_err(|e| socket_error("send to", &target, e))?;
+            }
+        }
+
+        debug!("Sent {} bytes on Unix socket {}", data.len(), self.path.display());
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> Option<UnixMessage> {
+        match self.message_receiver.as_mut() {
+            Some(receiver) => receiver.recv().await,
+            None => None,
+        }
+    }
+
+    /// Take the message receiver so messages can be consumed outside the client
+    ///
+    /// After this, `receive` always returns `None`.
+    pub fn take_message_receiver(&mut self) -> Option<QueueReceiver<UnixMessage>> {
+        self.message_receiver.take()
+    }
+
+    /// Messages discarded so far because they were not received in time
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.get()
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_mode(&self) -> UnixSocketMode {
+        self.mode
+    }
+
+    pub async fn close(self) -> TermComResult<()> {
+        self._io_handle.abort();
+        if let SocketIo::Stream(writer) = &self.io {
+            if let Some(mut stream) = writer.lock().await.take() {
+                let _ = stream.shutdown().await;
+            }
+        }
+        if let Some(ref owned_path) = self.owned_path {
+            let _ = std::fs::remove_file(owned_path);
+        }
+
+        info!("Unix socket {} closed", self.path.display());
+        Ok(())
+    }
+}
+
+fn socket_error(action: &str, path: &Path, error: std::io::Error) -> TermComError {
+    TermComError::Communication {
+        message: format!("Failed to {} {}: {}", action, path.display(), error),
+    }
+}
+
+/// Remove a socket file left behind by a process that is no longer listening
+///
+/// Anything that is not a socket, or a socket someone still answers on, is
+/// left alone so binding fails with a clear error instead.
+async fn remove_stale_socket(path: &Path, mode: UnixSocketMode) {
+    let is_socket = std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket());
+    if !is_socket {
+        return;
+    }
+
+    let in_use = match mode {
+        UnixSocketMode::DatagramListen => UnixDatagram::unbound().is_ok_and(|socket| socket.connect(path).is_ok()),
+        _ => UnixStream::connect(path).await.is_ok(),
+    };
+    if !in_use {
+        debug!("Removing stale Unix socket {}", path.display());
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+async fn read_stream(mut reader: impl AsyncRead + Unpin, sender: &QueueSender<UnixMessage>) {
+    let mut buffer = vec![0u8; 4096];
+
+    loop {
+        match reader.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(n) => {
+                debug!("Received {} bytes from Unix socket", n);
+                let event = UnixEvent::Data { peer: None, data: buffer[..n].to_vec() };
+                if sender.send(UnixMessage::new(event)).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                warn!("Failed to read from Unix socket: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Accept clients one at a time; others are turned away while one is connected
+async fn accept_loop(listener: UnixListener, writer: SharedWriter, sender: QueueSender<UnixMessage>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!("Failed to accept Unix socket connection: {}", e);
+                continue;
+            }
+        };
+
+        let mut current = writer.lock().await;
+        if current.is_some() {
+            warn!("Rejecting Unix socket connection: a client is already connected");
+            continue;
+        }
+
+        let (reader, stream_writer) = stream.into_split();
+        *current = Some(stream_writer);
+        drop(current);
+
+        info!("Accepted Unix socket connection");
+        let _ = sender.send(UnixMessage::new(UnixEvent::PeerConnected)).await;
+
+        let reader_writer = Arc::clone(&writer);
+        let reader_sender = sender.clone();
+        tokio::spawn(async move {
+            read_stream(reader, &reader_sender).await;
+            reader_writer.lock().await.take();
+            info!("Unix socket client disconnected");
+            let _ = reader_sender.send(UnixMessage::new(UnixEvent::PeerDisconnected)).await;
+        });
+    }
+}
+
+async fn read_datagrams(
+    socket: Arc<UnixDatagram>,
+    last_peer: Arc<Mutex<Option<PathBuf>>>,
+    sender: QueueSender<UnixMessage>,
+) {
+    let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+
+    loop {
+        match socket.recv_from(&mut buffer).await {
+            Ok((n, addr)) => {
+                let peer = addr.as_pathname().map(Path::to_path_buf);
+                debug!("Received {} byte datagram from {:?}", n, peer);
+                if peer.is_some() {
+                    *last_peer.lock().await = peer.clone();
+                }
+
+                let event = UnixEvent::Data { peer, data: buffer[..n].to_vec() };
+                if sender.send(UnixMessage::new(event)).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                warn!("Failed to receive Unix datagram: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn create_test_config(path: &Path, mode: UnixSocketMode) -> ConnectionConfig {
+        ConnectionConfig::Unix {
+            path: path.display().to_string(),
+            mode,
+        }
+    }
+
+    async fn next_event(client: &mut UnixSocketClient) -> UnixEvent {
+        tokio::time::timeout(Duration::from_secs(2), client.receive()).await.unwrap().unwrap().event
+    }
+
+    #[tokio::test]
+    async fn test_stream_client_and_listener() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.sock");
+
+        let mut listener = UnixSocketClient::new(&create_test_config(&path, UnixSocketMode::StreamListen)).await.unwrap();
+        assert!(listener.send(b"nobody".to_vec()).await.is_err());
+
+        let mut client = UnixSocketClient::new(&create_test_config(&path, UnixSocketMode::Stream)).await.unwrap();
+        assert!(matches!(next_event(&mut listener).await, UnixEvent::PeerConnected));
+
+        client.send(b"login: ".to_vec()).await.unwrap();
+        assert!(matches!(next_event(&mut listener).await, UnixEvent::Data { peer: None, ref data } if data == b"login: "));
+
+        listener.send(b"root\n".to_vec()).await.unwrap();
+        assert!(matches!(next_event(&mut client).await, UnixEvent::Data { ref data, .. } if data == b"root\n"));
+
+        listener.close().await.unwrap();
+        assert!(matches!(next_event(&mut client).await, UnixEvent::Closed));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_datagram_client_gets_replies() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("emulator.sock");
+
+        let mut server = UnixSocketClient::new(&create_test_config(&path, UnixSocketMode::DatagramListen)).await.unwrap();
+        assert!(server.send(b"early".to_vec()).await.is_err());
+
+        let mut client = UnixSocketClient::new(&create_test_config(&path, UnixSocketMode::Datagram)).await.unwrap();
+        client.send(b"ping".to_vec()).await.unwrap();
+        assert!(matches!(next_event(&mut server).await, UnixEvent::Data { peer: Some(_), ref data } if data == b"ping"));
+
+        server.send(b"pong".to_vec()).await.unwrap();
+        assert!(matches!(next_event(&mut client).await, UnixEvent::Data { ref data, .. } if data == b"pong"));
+
+        client.close().await.unwrap();
+        server.close().await.unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_listener_replaces_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let listener = UnixSocketClient::new(&create_test_config(&path, UnixSocketMode::StreamListen)).await.unwrap();
+        listener.close().await.unwrap();
+
+        let file = dir.path().join("plain");
+        std::fs::write(&file, b"keep me").unwrap();
+        assert!(UnixSocketClient::new(&create_test_config(&file, UnixSocketMode::StreamListen)).await.is_err());
+        assert_eq!(std::fs::read(&file).unwrap(), b"keep me");
+    }
+}
@@ -8,6 +8,8 @@ pub mod tui;
 pub mod core;
 pub mod domain;
 pub mod infrastructure;
+#[cfg(unix)]
+pub mod daemon;
 
 pub use domain::error::{TermComError, TermComResult};
 pub use domain::config::TermComConfig;
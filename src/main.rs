@@ -1,15 +1,10 @@
 // TermCom - Embedded Device Communication Debug Tool
-mod cli;
-mod tui;
-mod core;
-mod domain;
-mod infrastructure;
-
 use clap::Parser;
-use cli::args::{Args, Command};
-use cli::commands::execute_command;
-use domain::error::TermComError;
-use tui::app::App;
+use termcom::cli::args::{Args, Command};
+use termcom::cli::commands::execute_command;
+use termcom::infrastructure::config::ConfigManager;
+use termcom::tui::app::App;
+use termcom::TermComError;
 
 #[tokio::main]
 async fn main() -> Result<(), TermComError> {
@@ -17,7 +12,12 @@ async fn main() -> Result<(), TermComError> {
     
     match &args.command {
         Command::Tui => {
-            let mut app = App::new()?;
+            let config_manager = ConfigManager::new()?;
+            let config = match &args.config {
+                Some(config_path) => config_manager.load_config_from_path(config_path.as_ref())?,
+                None => config_manager.load_config()?,
+            };
+            let mut app = App::new(&config)?;
             app.run().await?;
             Ok(())
         }
@@ -12,7 +12,7 @@ use ratatui::{
 };
 
 use crate::{
-    domain::error::TermComError,
+    domain::{config::{TermComConfig, UnixSocketMode}, error::TermComError},
     core::{
         communication::CommunicationEngine,
         session::SessionManager,
@@ -24,6 +24,9 @@ use super::{
 };
 use std::sync::Arc;
 
+/// Length of the break sent with 'b'
+const BREAK_DURATION: Duration = Duration::from_millis(250);
+
 pub struct App {
     state: AppState,
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
@@ -35,7 +38,7 @@ pub struct App {
 }
 
 impl App {
-    pub fn new() -> Result<Self, TermComError> {
+    pub fn new(config: &TermComConfig) -> Result<Self, TermComError> {
         // Setup terminal
         enable_raw_mode().map_err(|e| TermComError::TuiError(e.to_string()))?;
         let mut stdout = io::stdout();
@@ -46,8 +49,17 @@ impl App {
             .map_err(|e| TermComError::TuiError(e.to_string()))?;
 
         // Initialize communication engine and session manager
-        let communication_engine = Arc::new(CommunicationEngine::new(1000, 10));
-        let session_manager = Arc::new(SessionManager::new(Arc::clone(&communication_engine), 10));
+        let communication_engine = Arc::new(
+            CommunicationEngine::builder()
+                .max_history_size(config.global.history_limit)
+                .max_sessions_per_transport(config.global.max_sessions)
+                .queue(config.global.queue())
+                .build(),
+        );
+        let session_manager = Arc::new(SessionManager::new(
+            Arc::clone(&communication_engine),
+            config.global.max_sessions,
+        ));
 
         let state = AppState::new();
 
@@ -71,10 +83,8 @@ impl App {
             if let Ok(true) = event::poll(self.tick_rate) {
                 if let Ok(event) = event::read() {
                     match event {
-                        Event::Key(key) => {
-                            if self.handle_key_event(key).await? {
-                                break;
-                            }
+                        Event::Key(key) if self.handle_key_event(key).await? => {
+                            break;
                         }
                         Event::Resize(width, height) => {
                             self.state.terminal_size = (width, height);
@@ -100,6 +110,12 @@ impl App {
             }
         }
 
+        // Close the active session before the engine goes away
+        if self.state.get_connection().is_some() {
+            self.state.close_connection(&self.session_manager).await?;
+        }
+        self.communication_engine.stop().await?;
+
         Ok(())
     }
 
@@ -115,7 +131,7 @@ impl App {
                     match self.state.view_mode {
                         ViewMode::Chat => {
                             if self.state.get_connection().is_some() {
-                                self.state.add_message(input, true).await?;
+                                self.state.send_input(&self.session_manager, input).await?;
                             }
                         }
                         ViewMode::Command => {
@@ -160,6 +176,15 @@ impl App {
                 self.state.view_mode = ViewMode::Command;
                 self.state.input_mode = true;
             }
+            KeyCode::Char('d') => {
+                self.state.toggle_dtr(&self.session_manager).await?;
+            }
+            KeyCode::Char('r') => {
+                self.state.toggle_rts(&self.session_manager).await?;
+            }
+            KeyCode::Char('b') => {
+                self.state.send_break(&self.session_manager, BREAK_DURATION).await?;
+            }
             _ => {}
         }
 
@@ -176,7 +201,7 @@ impl App {
         let command = &command[1..]; // Remove ':'
         let parts: Vec<&str> = command.split_whitespace().collect();
         
-        match parts.get(0) {
+        match parts.first() {
             Some(&"serial") => {
                 if parts.len() >= 3 {
                     let port = parts[1].to_string();
@@ -203,8 +228,33 @@ impl App {
                     self.state.set_status_message("Usage: :tcp <host> <port>".to_string());
                 }
             }
+            Some(&"unix") => {
+                match (parts.get(1), parts.get(2).map(|mode| mode.parse::<UnixSocketMode>())) {
+                    (Some(path), None) => {
+                        self.state.create_unix_connection(&self.session_manager, path.to_string(), UnixSocketMode::Stream).await?;
+                        self.state.view_mode = ViewMode::Chat;
+                    }
+                    (Some(path), Some(Ok(mode))) => {
+                        self.state.create_unix_connection(&self.session_manager, path.to_string(), mode).await?;
+                        self.state.view_mode = ViewMode::Chat;
+                    }
+                    (Some(_), Some(Err(e))) => {
+                        self.state.set_status_message(e.to_string());
+                    }
+                    (None, _) => {
+                        self.state.set_status_message("Usage: :unix <path> [stream|stream-listen|datagram|datagram-listen]".to_string());
+                    }
+                }
+            }
+            Some(&"baud") => {
+                match parts.get(1).map(|rate| rate.parse::<u32>()) {
+                    Some(Ok(baud_rate)) => self.state.set_baud_rate(&self.session_manager, baud_rate).await?,
+                    Some(Err(_)) => self.state.set_status_message("Invalid baud rate".to_string()),
+                    None => self.state.set_status_message("Usage: :baud <rate>".to_string()),
+                }
+            }
             Some(&"close") => {
-                self.state.close_connection().await?;
+                self.state.close_connection(&self.session_manager).await?;
             }
             Some(&"quit") => {
                 self.should_quit = true;
@@ -222,7 +272,7 @@ impl App {
 
     async fn tick(&mut self) -> Result<(), TermComError> {
         // Update connection status and receive messages
-        self.state.update_connection().await?;
+        self.state.update_connection(&self.session_manager).await?;
         Ok(())
     }
 }
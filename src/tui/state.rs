@@ -1,12 +1,23 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::broadcast;
 
 use crate::{
-    core::session::{SessionManager, SessionConfig},
-    domain::{error::TermComError, config::{DeviceConfig, ConnectionConfig}},
+    core::{
+        communication::{message::MessageType, ControlLines, Message},
+        session::{SessionConfig, SessionManager, SessionStatus, SessionType},
+    },
+    domain::{
+        config::{ConnectionConfig, DeviceConfig, FlowControlConfig, ParityConfig, SerialSettings, UnixSocketMode},
+        error::TermComError,
+    },
 };
 
 use super::{ui::ViewMode, widgets::main::ChatMessage};
 
+/// Line ending appended to every line typed in the chat view
+const LINE_ENDING: &str = "\r\n";
+
 #[derive(Debug)]
 pub struct AppState {
     pub view_mode: ViewMode,
@@ -25,6 +36,20 @@ pub struct Connection {
     pub connected: bool,
     pub messages: Vec<ChatMessage>,
     pub last_activity: SystemTime,
+    /// ID of the session backing this connection
+    pub session_id: String,
+    /// Last DTR level set; ports open with DTR asserted
+    pub dtr: bool,
+    /// Last RTS level set; ports open with RTS asserted
+    pub rts: bool,
+    /// Live feed of the session's messages
+    receiver: broadcast::Receiver<Message>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AppState {
@@ -40,80 +65,232 @@ impl AppState {
         }
     }
 
-    pub async fn create_serial_connection(&mut self, _session_manager: &SessionManager, port: String, baud_rate: u32) -> Result<(), TermComError> {
+    pub async fn create_serial_connection(&mut self, session_manager: &SessionManager, port: String, baud_rate: u32) -> Result<(), TermComError> {
+        let connection = ConnectionConfig::Serial {
+            port: port.clone(),
+            baud_rate,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        };
+
+        self.open_connection(
+            session_manager,
+            format!("Serial {}", port),
+            format!("Port: {}, Baud: {}", port, baud_rate),
+            port,
+            connection,
+        ).await
+    }
+
+    pub async fn create_tcp_connection(&mut self, session_manager: &SessionManager, host: String, port: u16) -> Result<(), TermComError> {
+        let connection = ConnectionConfig::Tcp {
+            host: host.clone(),
+            port,
+            timeout_ms: 3000,
+            keep_alive: false,
+        };
+
+        self.open_connection(
+            session_manager,
+            format!("TCP {}:{}", host, port),
+            format!("Host: {}, Port: {}", host, port),
+            format!("{}:{}", host, port),
+            connection,
+        ).await
+    }
+
+    pub async fn create_unix_connection(&mut self, session_manager: &SessionManager, path: String, mode: UnixSocketMode) -> Result<(), TermComError> {
+        let connection = ConnectionConfig::Unix {
+            path: path.clone(),
+            mode,
+        };
+
+        self.open_connection(
+            session_manager,
+            format!("Unix {}", path),
+            format!("Path: {}, Mode: {}", path, mode),
+            path,
+            connection,
+        ).await
+    }
+
+    /// Start a session for the connection and make it the active one
+    ///
+    /// Connect errors are shown in the status bar rather than returned, so a
+    /// typo in a port name does not end the TUI.
+    async fn open_connection(
+        &mut self,
+        session_manager: &SessionManager,
+        name: String,
+        config_info: String,
+        device_name: String,
+        connection: ConnectionConfig,
+    ) -> Result<(), TermComError> {
         // Close existing connection if any
         if self.connection.is_some() {
-            self.close_connection().await?;
+            self.close_connection(session_manager).await?;
         }
 
-        // TODO: Implement actual serial connection
-        // For now, create a mock connection
-        let connection = Connection {
-            name: format!("Serial {}", port),
-            config_info: format!("Port: {}, Baud: {}", port, baud_rate),
+        let device_config = DeviceConfig {
+            description: name.clone(),
+            ..DeviceConfig::new(device_name, connection)
+        };
+        let config = SessionConfig::from_device(format!("tui {}", name), SessionType::Interactive, device_config);
+
+        let session_id = match session_manager.create_session(config).await {
+            Ok(session_id) => session_id,
+            Err(e) => {
+                self.status_message = Some(format!("Failed to connect to {}: {}", name, e));
+                return Ok(());
+            }
+        };
+
+        // Subscribe before starting so nothing sent on connect is missed
+        let receiver = session_manager.subscribe(&session_id).await?;
+        if let Err(e) = session_manager.start_session(&session_id).await {
+            let _ = session_manager.remove_session(&session_id).await;
+            self.status_message = Some(format!("Failed to connect to {}: {}", name, e));
+            return Ok(());
+        }
+
+        self.status_message = Some(format!("Connected to {}", name));
+        self.connection = Some(Connection {
+            name,
+            config_info,
             connected: true,
             messages: Vec::new(),
             last_activity: SystemTime::now(),
+            session_id,
+            dtr: true,
+            rts: true,
+            receiver,
+        });
+
+        Ok(())
+    }
+
+    /// Send a line typed by the user to the active session
+    ///
+    /// The line shows up in the chat once the session reports it as sent.
+    pub async fn send_input(&mut self, session_manager: &SessionManager, input: String) -> Result<(), TermComError> {
+        let Some(connection) = &self.connection else {
+            self.status_message = Some("No active connection".to_string());
+            return Ok(());
         };
 
-        self.connection = Some(connection);
-        self.status_message = Some(format!("Connected to serial port: {}", port));
+        let data = format!("{}{}", input, LINE_ENDING).into_bytes();
+        if let Err(e) = session_manager.send_data(&connection.session_id, data).await {
+            self.status_message = Some(format!("Failed to send: {}", e));
+        }
 
         Ok(())
     }
 
-    pub async fn create_tcp_connection(&mut self, _session_manager: &SessionManager, host: String, port: u16) -> Result<(), TermComError> {
-        // Close existing connection if any
-        if self.connection.is_some() {
-            self.close_connection().await?;
+    /// Flip the DTR line of the active connection
+    pub async fn toggle_dtr(&mut self, session_manager: &SessionManager) -> Result<(), TermComError> {
+        let Some(connection) = &self.connection else {
+            self.status_message = Some("No active connection".to_string());
+            return Ok(());
+        };
+
+        let lines = ControlLines::dtr(!connection.dtr);
+        if self.set_control_lines(session_manager, lines).await {
+            if let Some(connection) = &mut self.connection {
+                connection.dtr = !connection.dtr;
+            }
         }
+        Ok(())
+    }
 
-        // TODO: Implement actual TCP connection
-        // For now, create a mock connection
-        let connection = Connection {
-            name: format!("TCP {}:{}", host, port),
-            config_info: format!("Host: {}, Port: {}", host, port),
-            connected: true,
-            messages: Vec::new(),
-            last_activity: SystemTime::now(),
+    /// Flip the RTS line of the active connection
+    pub async fn toggle_rts(&mut self, session_manager: &SessionManager) -> Result<(), TermComError> {
+        let Some(connection) = &self.connection else {
+            self.status_message = Some("No active connection".to_string());
+            return Ok(());
         };
 
-        self.connection = Some(connection);
-        self.status_message = Some(format!("Connected to TCP: {}:{}", host, port));
+        let lines = ControlLines::rts(!connection.rts);
+        if self.set_control_lines(session_manager, lines).await {
+            if let Some(connection) = &mut self.connection {
+                connection.rts = !connection.rts;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply control lines and report the outcome in the status bar
+    async fn set_control_lines(&mut self, session_manager: &SessionManager, lines: ControlLines) -> bool {
+        let Some(connection) = &self.connection else {
+            return false;
+        };
 
+        match session_manager.set_control_lines(&connection.session_id, lines).await {
+            Ok(()) => {
+                self.status_message = Some(format!("Set {}", lines));
+                true
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Failed to set {}: {}", lines, e));
+                false
+            }
+        }
+    }
+
+    /// Send a break condition on the active connection
+    pub async fn send_break(&mut self, session_manager: &SessionManager, duration: Duration) -> Result<(), TermComError> {
+        let Some(connection) = &self.connection else {
+            self.status_message = Some("No active connection".to_string());
+            return Ok(());
+        };
+
+        self.status_message = Some(match session_manager.send_break(&connection.session_id, duration).await {
+            Ok(()) => format!("Sent {} ms break", duration.as_millis()),
+            Err(e) => format!("Failed to send break: {}", e),
+        });
+        Ok(())
+    }
+
+    /// Switch the active serial connection to another baud rate
+    pub async fn set_baud_rate(&mut self, session_manager: &SessionManager, baud_rate: u32) -> Result<(), TermComError> {
+        let Some(connection) = &mut self.connection else {
+            self.status_message = Some("No active connection".to_string());
+            return Ok(());
+        };
+
+        match session_manager.set_serial_settings(&connection.session_id, SerialSettings::baud_rate(baud_rate)).await {
+            Ok(settings) => {
+                if let Some((port, _)) = connection.config_info.split_once(", Baud: ") {
+                    connection.config_info = format!("{}, Baud: {}", port, baud_rate);
+                }
+                self.status_message = Some(format!("Now running at {}", settings));
+            }
+            Err(e) => self.status_message = Some(format!("Failed to change baud rate: {}", e)),
+        }
         Ok(())
     }
 
     pub async fn add_message(&mut self, content: String, is_sent: bool) -> Result<(), TermComError> {
         if let Some(connection) = &mut self.connection {
-            let message = ChatMessage {
-                content: content.clone(),
+            connection.messages.push(ChatMessage {
+                content,
                 timestamp: SystemTime::now(),
                 is_sent,
-            };
-
-            connection.messages.push(message);
+            });
             connection.last_activity = SystemTime::now();
-
-            // TODO: Implement actual message sending
-            // For now, just simulate echo if it's sent
-            if is_sent {
-                // Simulate echo after a short delay
-                let echo_message = ChatMessage {
-                    content: format!("Echo: {}", content),
-                    timestamp: SystemTime::now(),
-                    is_sent: false,
-                };
-                connection.messages.push(echo_message);
-            }
         }
 
         Ok(())
     }
 
-    pub async fn close_connection(&mut self) -> Result<(), TermComError> {
+    pub async fn close_connection(&mut self, session_manager: &SessionManager) -> Result<(), TermComError> {
         if let Some(connection) = self.connection.take() {
-            // TODO: Implement actual connection closing
+            if let Err(e) = session_manager.remove_session(&connection.session_id).await {
+                self.status_message = Some(format!("Failed to close {}: {}", connection.name, e));
+                return Ok(());
+            }
             self.status_message = Some(format!("Closed connection: {}", connection.name));
         } else {
             self.status_message = Some("No connection to close".to_string());
@@ -122,9 +299,39 @@ impl AppState {
         Ok(())
     }
 
-    pub async fn update_connection(&mut self) -> Result<(), TermComError> {
-        // TODO: Implement actual connection updates
-        // For now, this is a no-op since we're using mock connections
+    /// Pull new session messages into the chat and refresh the link status
+    pub async fn update_connection(&mut self, session_manager: &SessionManager) -> Result<(), TermComError> {
+        let Some(connection) = &mut self.connection else {
+            return Ok(());
+        };
+
+        loop {
+            match connection.receiver.try_recv() {
+                Ok(message) => {
+                    connection.messages.push(chat_message(&message));
+                    connection.last_activity = message.timestamp;
+                }
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    self.status_message = Some(format!("Display fell behind, skipped {} messages", skipped));
+                }
+                Err(_) => break,
+            }
+        }
+
+        let status = session_manager
+            .get_session_state(&connection.session_id)
+            .await
+            .map(|state| state.status);
+        let connected = matches!(status, Some(SessionStatus::Active));
+
+        if connection.connected && !connected {
+            self.status_message = Some(match status {
+                Some(SessionStatus::Error(e)) => format!("{} failed: {}", connection.name, e),
+                _ => format!("{} disconnected", connection.name),
+            });
+        }
+        connection.connected = connected;
+
         Ok(())
     }
 
@@ -147,4 +354,186 @@ impl AppState {
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
     }
-}
\ No newline at end of file
+}
+
+/// Convert a session message into a chat line
+fn chat_message(message: &Message) -> ChatMessage {
+    let is_sent = matches!(message.message_type, MessageType::Sent | MessageType::Command);
+    let content = String::from_utf8_lossy(&message.data)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+
+    ChatMessage {
+        content,
+        timestamp: message.timestamp,
+        is_sent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::communication::CommunicationEngine;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn session_manager() -> SessionManager {
+        let engine = Arc::new(CommunicationEngine::new(100, 5));
+        engine.start().await.unwrap();
+        SessionManager::new(engine, 5)
+    }
+
+    /// Answer one "ping" line with "pong", then stay connected until the TUI hangs up
+    async fn answer_ping(mut stream: impl AsyncRead + AsyncWrite + Unpin) {
+        let mut buffer = [0u8; 64];
+        let n = stream.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"ping\r\n");
+        stream.write_all(b"pong\r\n").await.unwrap();
+        let _ = stream.read(&mut buffer).await;
+    }
+
+    /// Exchange a ping with the peer of the open connection, then close it
+    async fn assert_round_trip(manager: &SessionManager, state: &mut AppState) {
+        assert!(state.get_connection().unwrap().connected);
+
+        state.send_input(manager, "ping".to_string()).await.unwrap();
+
+        for _ in 0..50 {
+            state.update_connection(manager).await.unwrap();
+            if state.get_connection().unwrap().messages.len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let messages = &state.get_connection().unwrap().messages;
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].is_sent);
+        assert_eq!(messages[0].content, "ping");
+        assert!(!messages[1].is_sent);
+        assert_eq!(messages[1].content, "pong");
+
+        state.close_connection(manager).await.unwrap();
+        assert!(state.get_connection().is_none());
+        assert_eq!(manager.get_session_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_connection_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { answer_ping(listener.accept().await.unwrap().0).await });
+
+        let manager = session_manager().await;
+        let mut state = AppState::new();
+        state.create_tcp_connection(&manager, "127.0.0.1".to_string(), port).await.unwrap();
+        assert_round_trip(&manager, &mut state).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_connection_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("console.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move { answer_ping(listener.accept().await.unwrap().0).await });
+
+        let manager = session_manager().await;
+        let mut state = AppState::new();
+        state.create_unix_connection(&manager, path.display().to_string(), UnixSocketMode::Stream).await.unwrap();
+        assert_round_trip(&manager, &mut state).await;
+    }
+
+    #[tokio::test]
+    async fn test_control_lines_need_a_serial_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.read(&mut [0u8; 16]).await;
+        });
+
+        let manager = session_manager().await;
+        let mut state = AppState::new();
+        state.create_tcp_connection(&manager, "127.0.0.1".to_string(), port).await.unwrap();
+
+        state.toggle_dtr(&manager).await.unwrap();
+        assert!(state.get_connection().unwrap().dtr);
+        assert!(state.status_message.as_deref().unwrap().starts_with("Failed to set DTR off"));
+
+        state.send_break(&manager, Duration::from_millis(10)).await.unwrap();
+        assert!(state.status_message.as_deref().unwrap().starts_with("Failed to send break"));
+
+        state.close_connection(&manager).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_baud_rate_change_keeps_connection() {
+        use crate::infrastructure::pty::{device, VirtualSerialPair};
+
+        let pair = VirtualSerialPair::open().unwrap();
+        let device = device::attach(pair.second_path()).unwrap();
+        let port = pair.first_path().display().to_string();
+
+        let manager = session_manager().await;
+        let mut state = AppState::new();
+        state.create_serial_connection(&manager, port.clone(), 9600).await.unwrap();
+        let session_id = state.get_connection().unwrap().session_id.clone();
+        state.send_input(&manager, "hello".to_string()).await.unwrap();
+
+        state.set_baud_rate(&manager, 115200).await.unwrap();
+        assert_eq!(state.status_message.as_deref(), Some("Now running at 115200 baud, 8 data bits, no parity, 1 stop bit, no flow control"));
+        let connection = state.get_connection().unwrap();
+        assert_eq!(connection.config_info, format!("Port: {}, Baud: 115200", port));
+        assert_eq!(connection.session_id, session_id);
+
+        state.send_input(&manager, "again".to_string()).await.unwrap();
+        let mut received = Vec::new();
+        while received.len() < 14 {
+            let mut buffer = [0u8; 64];
+            let n = tokio::time::timeout(Duration::from_secs(2), device.read(&mut buffer)).await.unwrap().unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+        assert_eq!(received, b"hello\r\nagain\r\n");
+
+        // History from before the change is kept; it is recorded asynchronously
+        let sent: Vec<_> = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let sent: Vec<_> = manager.get_message_history(&session_id).await.unwrap()
+                    .into_iter()
+                    .filter(|message| matches!(message.message_type, MessageType::Sent))
+                    .map(|message| message.data)
+                    .collect();
+                if sent.len() >= 2 {
+                    return sent;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(sent, vec![b"hello\r\n".to_vec(), b"again\r\n".to_vec()]);
+
+        state.set_baud_rate(&manager, 0).await.unwrap();
+        assert!(state.status_message.as_deref().unwrap().starts_with("Failed to change baud rate"));
+
+        state.close_connection(&manager).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connect_error_is_shown() {
+        // Bind and drop a listener to get a port nothing is listening on
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+
+        let manager = session_manager().await;
+        let mut state = AppState::new();
+        state.create_tcp_connection(&manager, "127.0.0.1".to_string(), port).await.unwrap();
+
+        assert!(state.get_connection().is_none());
+        assert!(state.status_message.as_deref().unwrap().starts_with("Failed to connect to TCP"));
+        assert_eq!(manager.get_session_count().await, 0);
+    }
+}
@@ -38,6 +38,7 @@ fn render_instructions(f: &mut Frame, area: Rect, _state: &AppState) {
         Line::from("Connection Types:"),
         Line::from("  Serial: serial <port> <baud_rate>"),
         Line::from("  TCP:    tcp <host> <port>"),
+        Line::from("  Unix:   unix <path> [mode]"),
         Line::from(""),
         Line::from("Controls:"),
         Line::from("  i - Start typing connection command"),
@@ -108,6 +109,10 @@ fn render_examples(f: &mut Frame, area: Rect, _state: &AppState) {
         Line::from("  tcp 192.168.1.100 23"),
         Line::from("  tcp example.com 1234"),
         Line::from(""),
+        Line::from(Span::styled("Unix Socket Connections:", Style::default().fg(Color::Magenta))),
+        Line::from("  unix /tmp/qemu-serial.sock"),
+        Line::from("  unix /tmp/console.sock stream-listen"),
+        Line::from(""),
         Line::from(Span::styled("Common Serial Baud Rates:", Style::default().fg(Color::Yellow))),
         Line::from("  9600, 19200, 38400, 57600, 115200"),
     ];
@@ -24,12 +24,19 @@ pub fn render_help_popup(f: &mut Frame, area: Rect, _state: &AppState) {
         Line::from(""),
         Line::from("Chat Mode:"),
         Line::from("  i        - Type message (when connected)"),
-        Line::from("  Enter    - Send message (in input mode)"),
+        Line::from("  Enter    - Send message + CR LF (in input mode)"),
         Line::from("  :        - Enter command mode"),
         Line::from(""),
+        Line::from("Serial Lines:"),
+        Line::from("  d        - Toggle DTR"),
+        Line::from("  r        - Toggle RTS"),
+        Line::from("  b        - Send a 250 ms break"),
+        Line::from(""),
         Line::from("Command Mode:"),
         Line::from("  :serial <port> <baud>  - Connect serial"),
         Line::from("  :tcp <host> <port>     - Connect TCP"),
+        Line::from("  :unix <path> [mode]    - Connect Unix socket"),
+        Line::from("  :baud <rate>           - Change serial baud rate"),
         Line::from("  :close                 - Close connection"),
         Line::from("  :quit                  - Quit app"),
         Line::from(""),
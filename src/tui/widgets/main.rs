@@ -1,8 +1,8 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::Rect,
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Clear},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 use std::time::SystemTime;
@@ -97,6 +97,7 @@ fn render_command_view(f: &mut Frame, area: Rect, _state: &AppState) {
         Line::from("Available commands:"),
         Line::from("  :serial <port> <baud>     - Connect to serial port"),
         Line::from("  :tcp <host> <port>        - Connect to TCP server"),
+        Line::from("  :unix <path> [mode]       - Connect to Unix socket"),
         Line::from("  :close                    - Close current session"),
         Line::from("  :quit                     - Quit application"),
         Line::from(""),
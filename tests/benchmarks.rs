@@ -302,7 +302,7 @@ mod integration_benchmarks {
         // Create a complete system setup
         let memory_manager = Arc::new(MemoryManager::new(100));
         let engine = Arc::new(CommunicationEngine::new(5000, 20));
-        let session_manager = SessionManager::new(engine.clone(), 20);
+        let _session_manager = SessionManager::new(engine.clone(), 20);
         
         // Start system
         engine.start().await.expect("Failed to start engine");
@@ -25,7 +25,7 @@ mod error_handling_tests {
             assert!(!display.is_empty(), "Error display should not be empty");
             
             // All errors should have source information
-            let source = error.source();
+            let _source = error.source();
             // Some errors might not have sources, which is fine
             
             // All errors should be Send + Sync for async compatibility
@@ -1,7 +1,6 @@
 use std::time::Duration;
 use termcom::{TermComConfig, TermComError, SessionType};
 use tokio::time::timeout;
-use toml;
 
 /// Integration tests for TermCom library
 #[cfg(test)]
@@ -73,6 +72,347 @@ mod integration_tests {
         assert_eq!(stats.active_sessions, 0);
     }
 
+    #[tokio::test]
+    async fn test_session_receives_from_loopback_peer() {
+        use termcom::{SessionManager, CommunicationEngine};
+        use termcom::core::communication::message::MessageType;
+        use termcom::core::session::SessionConfig;
+        use termcom::domain::config::{ConnectionConfig, DeviceConfig};
+        use std::sync::Arc;
+        use tokio::io::AsyncWriteExt;
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        let _peer = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"READY\r\n").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            socket.write_all(b"OK\r\n").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        });
+        
+        let engine = Arc::new(CommunicationEngine::new(1000, 10));
+        engine.start().await.unwrap();
+        let manager = SessionManager::new(engine, 10);
+        
+        let config = SessionConfig {
+            name: "loopback".to_string(),
+            device_config: DeviceConfig {
+                description: "Loopback TCP peer".to_string(),
+                ..DeviceConfig::new(
+                    "loopback-device",
+                    ConnectionConfig::Tcp {
+                        host: addr.ip().to_string(),
+                        port: addr.port(),
+                        timeout_ms: 1000,
+                        keep_alive: false,
+                    },
+                )
+            },
+            ..SessionConfig::default()
+        };
+        
+        let session_id = manager.create_session(config).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+        
+        // Wait until both chunks have been delivered and accounted for
+        let state = timeout(Duration::from_secs(2), async {
+            loop {
+                let state = manager.get_session_state(&session_id).await.unwrap();
+                if state.statistics.bytes_received >= 11 {
+                    return state;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("received data was not recorded");
+        
+        assert_eq!(state.statistics.bytes_received, 11);
+        
+        let history = manager.get_message_history(&session_id).await.unwrap();
+        let received: Vec<_> = history
+            .iter()
+            .filter(|m| matches!(m.message_type, MessageType::Received))
+            .collect();
+        let payload: Vec<u8> = received.iter().flat_map(|m| m.data.clone()).collect();
+        assert_eq!(payload, b"READY\r\nOK\r\n");
+        assert_eq!(state.statistics.messages_received as usize, received.len());
+        assert!(received.windows(2).all(|w| w[0].metadata.sequence < w[1].metadata.sequence));
+        
+        manager.remove_session(&session_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport_plugs_into_sessions() {
+        use termcom::{SessionManager, CommunicationEngine};
+        use termcom::core::communication::{Message, Transport, TransportType};
+        use termcom::core::communication::message::MessageType;
+        use termcom::core::communication::transport::{SessionInfo, SessionStatus};
+        use termcom::core::session::SessionConfig;
+        use termcom::domain::config::{ConnectionConfig, DeviceConfig};
+        use termcom::TermComResult;
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+        use tokio::sync::mpsc;
+        
+        /// Echoes everything sent to it, prefixed as configured
+        struct LoopbackTransport {
+            sessions: Mutex<HashMap<String, (String, String)>>,
+            sender: mpsc::UnboundedSender<Message>,
+            receiver: tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>,
+        }
+        
+        #[derive(serde::Deserialize)]
+        struct LoopbackOptions {
+            prefix: String,
+        }
+        
+        impl LoopbackTransport {
+            fn new() -> Self {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                Self {
+                    sessions: Mutex::new(HashMap::new()),
+                    sender,
+                    receiver: tokio::sync::Mutex::new(receiver),
+                }
+            }
+        }
+        
+        #[async_trait::async_trait]
+        impl Transport for LoopbackTransport {
+            fn transport_type(&self) -> TransportType {
+                TransportType::Custom("loopback".to_string())
+            }
+            
+            async fn create_session(&self, device_config: &DeviceConfig) -> TermComResult<String> {
+                let options: LoopbackOptions = device_config.connection.custom_options()?;
+                let session_id = format!("loopback_{}", self.sessions.lock().unwrap().len());
+                self.sessions.lock().unwrap().insert(session_id.clone(), (device_config.name.clone(), options.prefix));
+                Ok(session_id)
+            }
+            
+            async fn close_session(&self, session_id: &str) -> TermComResult<()> {
+                self.sessions.lock().unwrap().remove(session_id);
+                Ok(())
+            }
+            
+            async fn send_data(&self, session_id: &str, data: Vec<u8>) -> TermComResult<()> {
+                let (device_name, prefix) = self.sessions.lock().unwrap()[session_id].clone();
+                let echo = [prefix.into_bytes(), data].concat();
+                let _ = self.sender.send(Message::received(session_id.to_string(), device_name, echo, "loopback".to_string()));
+                Ok(())
+            }
+            
+            async fn send_command(&self, session_id: &str, command: &str) -> TermComResult<()> {
+                self.send_data(session_id, command.as_bytes().to_vec()).await
+            }
+            
+            async fn receive_message(&self) -> Option<Message> {
+                self.receiver.lock().await.recv().await
+            }
+            
+            async fn is_session_connected(&self, session_id: &str) -> bool {
+                self.sessions.lock().unwrap().contains_key(session_id)
+            }
+            
+            async fn get_session_info(&self, session_id: &str) -> Option<SessionInfo> {
+                let (device_name, _) = self.sessions.lock().unwrap().get(session_id)?.clone();
+                Some(SessionInfo {
+                    id: session_id.to_string(),
+                    device_name,
+                    transport_type: self.transport_type(),
+                    status: SessionStatus::Connected,
+                    created_at: std::time::SystemTime::now(),
+                    last_activity: std::time::SystemTime::now(),
+                    bytes_sent: 0,
+                    bytes_received: 0,
+                    messages_sent: 0,
+                    messages_received: 0,
+                    dropped_messages: 0,
+                })
+            }
+            
+            async fn list_sessions(&self) -> Vec<SessionInfo> {
+                Vec::new()
+            }
+            
+            async fn close_all_sessions(&self) -> TermComResult<()> {
+                self.sessions.lock().unwrap().clear();
+                Ok(())
+            }
+            
+            async fn get_session_count(&self) -> usize {
+                self.sessions.lock().unwrap().len()
+            }
+            
+            fn get_max_sessions(&self) -> usize {
+                10
+            }
+        }
+        
+        let engine = Arc::new(CommunicationEngine::builder()
+            .without_builtin_transports()
+            .transport(LoopbackTransport::new())
+            .build());
+        assert_eq!(engine.available_transports().await, vec![TransportType::Custom("loopback".to_string())]);
+        engine.start().await.unwrap();
+        let manager = SessionManager::new(engine, 10);
+        
+        let device: DeviceConfig = toml::from_str(r#"
+name = "echo"
+
+[connection]
+type = "custom"
+transport = "loopback"
+options = { prefix = "echo: " }
+"#).unwrap();
+        let session_id = manager.create_session(SessionConfig {
+            name: "custom".to_string(),
+            device_config: device,
+            ..SessionConfig::default()
+        }).await.unwrap();
+        manager.start_session(&session_id).await.unwrap();
+        manager.send_data(&session_id, b"hi".to_vec()).await.unwrap();
+        
+        let payload = timeout(Duration::from_secs(2), async {
+            loop {
+                let payload: Vec<u8> = manager.get_message_history(&session_id).await.unwrap()
+                    .iter()
+                    .filter(|m| matches!(m.message_type, MessageType::Received))
+                    .flat_map(|m| m.data.clone())
+                    .collect();
+                if !payload.is_empty() {
+                    return payload;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("custom transport did not deliver the echo");
+        assert_eq!(payload, b"echo: hi");
+        
+        // Devices for transports nobody registered cannot be connected
+        let unknown = SessionConfig {
+            name: "unknown".to_string(),
+            device_config: DeviceConfig::new("bus", ConnectionConfig::Custom { transport: "can".to_string(), options: Default::default() }),
+            ..SessionConfig::default()
+        };
+        let unknown_id = manager.create_session(unknown).await.unwrap();
+        let error = manager.start_session(&unknown_id).await.unwrap_err();
+        assert!(error.to_string().contains("Transport 'can' is not registered"));
+        
+        manager.remove_session(&session_id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serial_session_talks_to_pty_session() {
+        use termcom::{SessionManager, CommunicationEngine};
+        use termcom::core::communication::message::MessageType;
+        use termcom::core::session::SessionConfig;
+        use termcom::domain::config::{ConnectionConfig, DeviceConfig, FlowControlConfig, ParityConfig};
+        use std::sync::Arc;
+        
+        fn device(name: &str, connection: ConnectionConfig) -> SessionConfig {
+            SessionConfig {
+                name: name.to_string(),
+                device_config: DeviceConfig::new(name, connection),
+                ..SessionConfig::default()
+            }
+        }
+        
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("ttySIM");
+        
+        let engine = Arc::new(CommunicationEngine::new(1000, 10));
+        engine.start().await.unwrap();
+        let manager = SessionManager::new(engine, 10);
+        
+        // The simulator side owns a fresh pseudo-terminal...
+        let simulator = manager.create_session(device("simulator", ConnectionConfig::Pty {
+            path: None,
+            link: Some(link.display().to_string()),
+        })).await.unwrap();
+        manager.start_session(&simulator).await.unwrap();
+        
+        // ...which the host opens as an ordinary serial port
+        let host = manager.create_session(device("host", ConnectionConfig::Serial {
+            port: link.display().to_string(),
+            baud_rate: 115200,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        })).await.unwrap();
+        manager.start_session(&host).await.unwrap();
+        
+        manager.send_data(&host, b"AT\r".to_vec()).await.unwrap();
+        manager.send_data(&simulator, b"OK\r\n".to_vec()).await.unwrap();
+        
+        for (session_id, expected) in [(&simulator, &b"AT\r"[..]), (&host, &b"OK\r\n"[..])] {
+            let payload = timeout(Duration::from_secs(2), async {
+                loop {
+                    let payload: Vec<u8> = manager.get_message_history(session_id).await.unwrap()
+                        .iter()
+                        .filter(|m| matches!(m.message_type, MessageType::Received))
+                        .flat_map(|m| m.data.clone())
+                        .collect();
+                    if payload.len() >= expected.len() {
+                        return payload;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }).await.expect("data did not cross the pseudo-terminal");
+            assert_eq!(payload, expected);
+        }
+        
+        manager.remove_session(&host).await.unwrap();
+        manager.remove_session(&simulator).await.unwrap();
+        assert!(link.symlink_metadata().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_automation_against_mock_device() {
+        use termcom::{SessionManager, CommunicationEngine};
+        use termcom::core::communication::MockTransport;
+        use termcom::core::communication::message::MessageType;
+        use termcom::core::session::{SessionConfig, SessionStatus};
+        use std::sync::Arc;
+
+        let mock = MockTransport::new();
+        let device = mock.handle();
+        let engine = Arc::new(CommunicationEngine::builder().transport(mock).build());
+        engine.start().await.unwrap();
+        let manager = SessionManager::new(engine, 10);
+
+        device.respond("AT+CSQ", "+CSQ: 21,0\r\nOK\r\n");
+        let config = SessionConfig::from_device("modem".to_string(), SessionType::Automated, MockTransport::device("modem"));
+        let session = manager.create_session(config).await.unwrap();
+        manager.start_session(&session).await.unwrap();
+
+        // The automation under test: poll signal quality
+        manager.send_command(&session, "AT+CSQ\r").await.unwrap();
+        let reply = timeout(Duration::from_secs(2), async {
+            loop {
+                let history = manager.get_message_history(&session).await.unwrap();
+                if let Some(reply) = history.iter().find(|m| matches!(m.message_type, MessageType::Received)) {
+                    return String::from_utf8_lossy(&reply.data).into_owned();
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("mock device did not answer");
+        assert!(reply.starts_with("+CSQ: 21"));
+        device.assert_sent("modem", "AT+CSQ\r");
+
+        // The device drops off the bus
+        device.disconnect("modem").unwrap();
+        timeout(Duration::from_secs(2), async {
+            while manager.get_session_state(&session).await.unwrap().status != SessionStatus::Disconnected {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("session did not notice the disconnect");
+        assert!(manager.send_command(&session, "AT\r").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_timeout_behavior() {
         // Test that long-running operations can be timed out
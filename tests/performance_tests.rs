@@ -171,7 +171,7 @@ mod performance_tests {
         let start = Instant::now();
         for i in 0..1000 {
             // Memory usage should remain bounded despite high message volume
-            let stats = manager.get_statistics().await;
+            let _stats = manager.get_statistics().await;
             if i % 100 == 0 {
                 // Periodically check that operations remain fast
                 assert!(start.elapsed() < Duration::from_secs(1), 
@@ -284,4 +284,234 @@ mod performance_tests {
         assert!(elapsed < Duration::from_millis(100), 
                 "Session lifecycle operations too slow: {:?}", elapsed);
     }
-}
\ No newline at end of file
+
+    /// Serial client on one side of a virtual serial pair, with the other
+    /// side standing in for the device
+    #[cfg(unix)]
+    async fn serial_over_pty(
+        baud_rate: u32,
+    ) -> (
+        termcom::infrastructure::pty::VirtualSerialPair,
+        termcom::infrastructure::serial::client::SerialClient,
+        termcom::infrastructure::pty::device::PtyIo,
+    ) {
+        use termcom::domain::config::{ConnectionConfig, FlowControlConfig, ParityConfig};
+        use termcom::infrastructure::pty::{device, VirtualSerialPair};
+        use termcom::infrastructure::serial::client::SerialClient;
+
+        let pair = VirtualSerialPair::open().expect("Failed to open virtual serial pair");
+        let device = device::attach(pair.second_path()).expect("Failed to attach device side");
+        let client = SerialClient::new(&ConnectionConfig::Serial {
+            port: pair.first_path().display().to_string(),
+            baud_rate,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: ParityConfig::None,
+            flow_control: FlowControlConfig::None,
+            rs485: None,
+        })
+        .await
+        .expect("Failed to open serial client");
+        (pair, client, device)
+    }
+
+    // Wall-clock limits are unreliable on a loaded machine and in debug
+    // builds; run with `cargo test --release -- --ignored`
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn test_serial_receive_latency() {
+        use termcom::infrastructure::serial::client::MessageDirection;
+
+        let (_pair, mut client, device) = serial_over_pty(3_000_000).await;
+        let mut messages = client.take_message_receiver().unwrap();
+
+        let mut latencies = Vec::new();
+        for round in 0..200u32 {
+            let start = Instant::now();
+            device.write_all(&round.to_le_bytes()).await.unwrap();
+            let mut received = 0;
+            while received < 4 {
+                let message = timeout(Duration::from_secs(1), messages.recv())
+                    .await
+                    .expect("Timed out waiting for data")
+                    .unwrap();
+                assert!(matches!(message.direction, MessageDirection::Received));
+                received += message.data.len();
+            }
+            latencies.push(start.elapsed());
+        }
+        client.close().await.unwrap();
+
+        latencies.sort();
+        let median = latencies[latencies.len() / 2];
+        let p99 = latencies[latencies.len() * 99 / 100];
+
+        // Data is handed on as soon as it arrives instead of on the next poll
+        assert!(
+            median < Duration::from_millis(1),
+            "Serial receive latency too high: median {:?}, p99 {:?}",
+            median,
+            p99
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn test_serial_full_duplex_throughput() {
+        use termcom::infrastructure::serial::client::MessageDirection;
+
+        // One second of traffic each way at 3 Mbaud, with 8N1 framing. A
+        // pseudo-terminal ignores the baud rate, so this shows the client
+        // keeps up with that much data, not that a real port reaches it.
+        const BAUD_RATE: u32 = 3_000_000;
+        const TOTAL: usize = BAUD_RATE as usize / 10;
+        const CHUNK: usize = 4096;
+
+        let (_pair, mut client, device) = serial_over_pty(BAUD_RATE).await;
+        let mut messages = client.take_message_receiver().unwrap();
+        let device = Arc::new(device);
+        let payload: Vec<u8> = (0..TOTAL).map(|i| (i % 251) as u8).collect();
+
+        let start = Instant::now();
+
+        // Device to client
+        let device_tx = Arc::clone(&device);
+        let downstream = payload.clone();
+        let device_writer = tokio::spawn(async move {
+            for chunk in downstream.chunks(CHUNK) {
+                device_tx.write_all(chunk).await.unwrap();
+            }
+        });
+
+        // Client to device
+        for chunk in payload.chunks(CHUNK) {
+            client.send(chunk.to_vec()).await.unwrap();
+        }
+        let device_rx = Arc::clone(&device);
+        let device_reader = tokio::spawn(async move {
+            let mut received = Vec::with_capacity(TOTAL);
+            let mut buffer = [0u8; CHUNK];
+            while received.len() < TOTAL {
+                let n = device_rx.read(&mut buffer).await.unwrap();
+                received.extend_from_slice(&buffer[..n]);
+            }
+            (received, start.elapsed())
+        });
+
+        let mut received = Vec::with_capacity(TOTAL);
+        while received.len() < TOTAL {
+            let message = timeout(Duration::from_secs(5), messages.recv())
+                .await
+                .expect("Timed out waiting for serial traffic")
+                .unwrap();
+            match message.direction {
+                MessageDirection::Received => received.extend_from_slice(&message.data),
+                MessageDirection::Disconnected => panic!("Serial port lost during benchmark"),
+            }
+        }
+        let receive_time = start.elapsed();
+
+        device_writer.await.unwrap();
+        let (device_received, send_time) = timeout(Duration::from_secs(5), device_reader)
+            .await
+            .expect("Timed out waiting for the device to read")
+            .unwrap();
+        client.close().await.unwrap();
+
+        assert_eq!(received, payload);
+        assert_eq!(device_received, payload);
+
+        let rate = |elapsed: Duration| TOTAL as f64 / elapsed.as_secs_f64();
+
+        // Both directions at once must keep up with the line rate
+        assert!(
+            receive_time < Duration::from_secs(1),
+            "Receiving too slow: {:?} ({:.0} B/s)",
+            receive_time,
+            rate(receive_time)
+        );
+        assert!(
+            send_time < Duration::from_secs(1),
+            "Sending too slow: {:?} ({:.0} B/s)",
+            send_time,
+            rate(send_time)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn test_tcp_echo_latency() {
+        use termcom::domain::config::ConnectionConfig;
+        use termcom::infrastructure::tcp::client::{MessageDirection, TcpClient};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.set_nodelay(true).unwrap();
+            let mut buffer = [0u8; 1024];
+            loop {
+                match socket.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => socket.write_all(&buffer[..n]).await.unwrap(),
+                }
+            }
+        });
+
+        let mut client = TcpClient::new(&ConnectionConfig::Tcp {
+            host: addr.ip().to_string(),
+            port: addr.port(),
+            timeout_ms: 1000,
+            keep_alive: true,
+        })
+        .await
+        .expect("Failed to connect to echo server");
+        let mut messages = client.take_message_receiver().unwrap();
+
+        // An interactive session: one keystroke at a time, each echoed back
+        let mut latencies = Vec::new();
+        for round in 0..200u32 {
+            let start = Instant::now();
+            client.send(round.to_le_bytes().to_vec()).await.unwrap();
+            let mut received = 0;
+            while received < 4 {
+                let message = timeout(Duration::from_secs(1), messages.recv())
+                    .await
+                    .expect("Timed out waiting for echo")
+                    .unwrap();
+                match message.direction {
+                    MessageDirection::Received => received += message.data.len(),
+                    MessageDirection::Sent => {}
+                    other => panic!("Connection lost during benchmark: {:?}", other),
+                }
+            }
+            latencies.push(start.elapsed());
+        }
+        client.close().await.unwrap();
+
+        latencies.sort();
+        let median = latencies[latencies.len() / 2];
+        let p99 = latencies[latencies.len() * 99 / 100];
+        let average = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+
+        println!(
+            "TCP echo round trip: min {:?}, avg {:?}, median {:?}, p99 {:?}, max {:?}",
+            latencies[0],
+            average,
+            median,
+            p99,
+            latencies[latencies.len() - 1]
+        );
+
+        // Sends no longer queue behind a pending read
+        assert!(
+            median < Duration::from_millis(1),
+            "TCP echo round trip too slow: median {:?}, p99 {:?}",
+            median,
+            p99
+        );
+    }
+}